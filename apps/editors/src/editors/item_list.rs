@@ -0,0 +1,168 @@
+use ::item_list::SItemList;
+use egui::{DragValue, Grid, Layout, ScrollArea, Ui};
+use egui_extras::{Size, StripBuilder};
+use std::path::PathBuf;
+
+use crate::encodings::{utf8_to_windows1252, windows1252_to_utf8};
+
+use super::EditorRender;
+
+pub struct ItemListEditor {
+    folder: PathBuf,
+    items: Vec<SItemList>,
+    names: Vec<String>,
+    selected: usize,
+    saved: Option<bool>,
+}
+
+impl EditorRender for ItemListEditor {
+    fn name() -> &'static str {
+        "Item List"
+    }
+
+    fn new(folder: PathBuf) -> Option<Box<Self>> {
+        let items = ::item_list::load(&folder)?;
+
+        // the names are windows-1252 and end at the first zero byte
+
+        let names = items
+            .iter()
+            .map(|item| {
+                let len = item
+                    .name
+                    .iter()
+                    .position(|c| *c == 0)
+                    .unwrap_or(item.name.len());
+                windows1252_to_utf8(&item.name[..len])
+            })
+            .collect();
+
+        Some(Box::new(Self {
+            folder,
+            items,
+            names,
+            selected: 0,
+            saved: None,
+        }))
+    }
+
+    fn render(&mut self, ui: &mut Ui) {
+        ui.set_width(560.0);
+        ui.set_height(400.0);
+
+        StripBuilder::new(ui)
+            .size(Size::remainder())
+            .size(Size::exact(24.0))
+            .vertical(|mut s| {
+                s.strip(|builder| {
+                    builder
+                        .size(Size::exact(200.0))
+                        .size(Size::remainder())
+                        .horizontal(|mut s| {
+                            s.cell(|ui| self.items_list(ui));
+                            s.cell(|ui| self.fields(ui));
+                        });
+                });
+                s.cell(|ui| self.footer_actions(ui));
+            });
+    }
+}
+
+impl ItemListEditor {
+    fn items_list(&mut self, ui: &mut Ui) {
+        ScrollArea::vertical().show_rows(ui, 20.0, self.items.len(), |ui, row_range| {
+            for row in row_range {
+                let label = format!("{} - {}", row, self.names[row]);
+
+                if ui.selectable_label(self.selected == row, label).clicked() {
+                    self.selected = row;
+                }
+            }
+        });
+    }
+
+    fn fields(&mut self, ui: &mut Ui) {
+        let Some(item) = self.items.get_mut(self.selected) else {
+            return;
+        };
+
+        Grid::new("item_list_fields").num_columns(2).show(ui, |ui| {
+            ui.label("Nome:");
+            ui.text_edit_singleline(&mut self.names[self.selected]);
+            ui.end_row();
+
+            let fields: [(&str, &mut i16); 12] = [
+                ("Malha:", &mut item.mesh),
+                ("Textura:", &mut item.texture),
+                ("Efeito visual:", &mut item.visual_effect),
+                ("Nível:", &mut item.req_level),
+                ("Força:", &mut item.req_str),
+                ("Inteligência:", &mut item.req_int),
+                ("Destreza:", &mut item.req_dex),
+                ("Constituição:", &mut item.req_con),
+                ("Único:", &mut item.unique),
+                ("Posição:", &mut item.pos),
+                ("Extremo:", &mut item.extreme),
+                ("Grau:", &mut item.grade),
+            ];
+
+            for (label, value) in fields {
+                ui.label(label);
+                ui.add(DragValue::new(value));
+                ui.end_row();
+            }
+
+            ui.label("Preço:");
+            ui.add(DragValue::new(&mut item.price));
+            ui.end_row();
+
+            for effect in item.effects.iter_mut() {
+                ui.label("Efeito:");
+                ui.horizontal(|ui| {
+                    ui.add(DragValue::new(&mut effect.index));
+                    ui.add(DragValue::new(&mut effect.value));
+                });
+                ui.end_row();
+            }
+        });
+    }
+
+    fn footer_actions(&mut self, ui: &mut Ui) {
+        ui.allocate_ui_with_layout(
+            ui.available_size(),
+            Layout::bottom_up(egui::Align::Min),
+            |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    if ui.button("Salvar").clicked() {
+                        self.saved = Some(self.save());
+                    }
+
+                    match self.saved {
+                        Some(true) => {
+                            ui.label(format!("{} salvo.", ::item_list::ITEM_LIST_FILE));
+                        }
+                        Some(false) => {
+                            ui.label(format!(
+                                "Não foi possível salvar {}.",
+                                ::item_list::ITEM_LIST_FILE
+                            ));
+                        }
+                        None => {}
+                    }
+                });
+            },
+        );
+    }
+
+    fn save(&mut self) -> bool {
+        for (item, name) in self.items.iter_mut().zip(&self.names) {
+            let buf = utf8_to_windows1252(name);
+            let len = buf.len().min(item.name.len() - 1);
+
+            item.name = [0; 64];
+            item.name[..len].copy_from_slice(&buf[..len]);
+        }
+
+        ::item_list::save(&self.folder, &self.items)
+    }
+}
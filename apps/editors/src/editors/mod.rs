@@ -3,8 +3,12 @@ use std::{fs, path::PathBuf};
 
 use crate::consts::LANGS_FOLDER;
 
-use self::{server_list::ServerListEditor, server_name::ServerNameEditor, strdef::StrDefEditor};
+use self::{
+    item_list::ItemListEditor, server_list::ServerListEditor, server_name::ServerNameEditor,
+    strdef::StrDefEditor,
+};
 
+pub mod item_list;
 pub mod server_list;
 pub mod server_name;
 pub mod strdef;
@@ -26,6 +30,7 @@ pub struct Editors {
     server_list: Option<(bool, Box<ServerListEditor>)>,
     server_name: Option<(bool, Box<ServerNameEditor>)>,
     strdef: Option<(bool, Box<StrDefEditor>)>,
+    item_list: Option<(bool, Box<ItemListEditor>)>,
 }
 
 impl Editors {
@@ -49,6 +54,7 @@ impl Editors {
             server_list: Default::default(),
             server_name: Default::default(),
             strdef: Default::default(),
+            item_list: Default::default(),
         }
     }
 
@@ -60,6 +66,7 @@ impl Editors {
         Self::clear_editor(&mut self.server_list);
         Self::clear_editor(&mut self.server_name);
         Self::clear_editor(&mut self.strdef);
+        Self::clear_editor(&mut self.item_list);
     }
 
     fn clear_editor<T: EditorRender>(editor: &mut Option<(bool, Box<T>)>) {
@@ -72,6 +79,7 @@ impl Editors {
         Self::manage_window(&mut self.server_list, ctx);
         Self::manage_window(&mut self.server_name, ctx);
         Self::manage_window(&mut self.strdef, ctx);
+        Self::manage_window(&mut self.item_list, ctx);
     }
 
     fn lef_panel(&mut self, ctx: &Context) {
@@ -105,6 +113,12 @@ impl Editors {
                             ui,
                             self.selected_lang_folder.clone(),
                         );
+
+                        Self::manage_editor_btn(
+                            &mut self.item_list,
+                            ui,
+                            self.client_folder.clone(),
+                        );
                     });
                 });
             });
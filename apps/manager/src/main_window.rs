@@ -1,20 +1,54 @@
 use eframe::App;
 use egui::CentralPanel;
+use server_data::{data_folder, RELOAD_REQUEST_FILE};
+use std::{fs, path::PathBuf};
 
-pub struct MainWindow {}
+pub struct MainWindow {
+    folder: String,
+    status: String,
+}
 
 impl Default for MainWindow {
     fn default() -> Self {
-        Self {}
+        Self {
+            folder: data_folder().display().to_string(),
+            status: String::new(),
+        }
+    }
+}
+
+impl MainWindow {
+    // the request goes to the data folder of the server, which is not always where the
+    // manager was started from
+
+    fn request_reload(&mut self) {
+        let file = PathBuf::from(self.folder.trim()).join(RELOAD_REQUEST_FILE);
+
+        self.status = match fs::write(&file, "") {
+            Ok(()) => "Reload requested, the result shows in the server console".to_string(),
+            Err(error) => format!("Could not request a reload in {:?}: {}", file, error),
+        };
     }
 }
 
 impl App for MainWindow {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         CentralPanel::default().show(ctx, |ui| {
-            ui.centered_and_justified(|ui| {
-                ui.heading("Future W2.Rust Manager");
+            ui.heading("W2.Rust Manager");
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.label("Server data folder:");
+                ui.text_edit_singleline(&mut self.folder);
             });
+
+            if ui.button("Reload game data").clicked() {
+                self.request_reload();
+            }
+
+            if !self.status.is_empty() {
+                ui.label(self.status.as_str());
+            }
         });
     }
 }
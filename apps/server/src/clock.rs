@@ -0,0 +1,161 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+// everything time based in the world asks the clock instead of the system, so the whole
+// simulation can be driven by hand
+
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+
+    // seconds since the unix epoch, for whatever has to outlive a restart
+
+    fn timestamp(&self) -> u64;
+}
+
+#[derive(Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn timestamp(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0)
+    }
+}
+
+#[derive(Clone)]
+pub struct ManualClock {
+    start: Instant,
+    now: Arc<Mutex<Instant>>,
+}
+
+impl ManualClock {
+    pub fn new(start: Instant) -> Self {
+        Self {
+            start,
+            now: Arc::new(Mutex::new(start)),
+        }
+    }
+
+    pub fn advance(&self, duration: Duration) -> Instant {
+        let mut now = self.now.lock().unwrap();
+        *now += duration;
+        *now
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new(Instant::now())
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        *self.now.lock().unwrap()
+    }
+
+    fn timestamp(&self) -> u64 {
+        (self.now() - self.start).as_secs()
+    }
+}
+
+// fixed timestep, every step is handed the time it was meant to run at no matter how late
+// the loop woke up, falling too far behind drops the extra steps instead of spiraling
+
+pub struct Ticker {
+    step: Duration,
+    max_steps: u32,
+    next: Instant,
+}
+
+impl Ticker {
+    pub fn new(step: Duration, max_steps: u32, now: Instant) -> Self {
+        Self {
+            step,
+            max_steps,
+            next: now,
+        }
+    }
+
+    pub fn due(&mut self, now: Instant) -> Vec<Instant> {
+        let mut steps = Vec::new();
+
+        while self.next <= now {
+            if steps.len() as u32 >= self.max_steps {
+                let skipped = (now - self.next).as_nanos() / self.step.as_nanos().max(1) + 1;
+                println!("clock.ticker.due: {} ticks behind, skipping", skipped);
+
+                self.next = now + self.step;
+                break;
+            }
+
+            steps.push(self.next);
+            self.next += self.step;
+        }
+
+        steps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STEP: Duration = Duration::from_millis(100);
+
+    #[test]
+    fn a_late_wake_up_catches_up_every_missed_step() {
+        let clock = ManualClock::default();
+        let start = clock.now();
+        let mut ticker = Ticker::new(STEP, 10, start);
+
+        assert_eq!(ticker.due(start), vec![start]);
+        assert!(ticker.due(clock.advance(STEP / 2)).is_empty());
+
+        let now = clock.advance(STEP * 3);
+
+        assert_eq!(
+            ticker.due(now),
+            vec![start + STEP, start + STEP * 2, start + STEP * 3]
+        );
+    }
+
+    #[test]
+    fn falling_too_far_behind_skips_the_extra_steps() {
+        let clock = ManualClock::default();
+        let start = clock.now();
+        let mut ticker = Ticker::new(STEP, 3, start);
+
+        let now = clock.advance(STEP * 10);
+
+        assert_eq!(ticker.due(now), vec![start, start + STEP, start + STEP * 2]);
+
+        // the skipped steps are gone, the next one is a full step after the wake up
+
+        assert!(ticker.due(now).is_empty());
+        assert!(ticker.due(clock.advance(STEP / 2)).is_empty());
+        assert_eq!(ticker.due(clock.advance(STEP / 2)), vec![now + STEP]);
+    }
+
+    #[test]
+    fn the_manual_clock_only_moves_when_told() {
+        let clock = ManualClock::default();
+        let start = clock.now();
+
+        assert_eq!(clock.now(), start);
+        assert_eq!(clock.timestamp(), 0);
+
+        clock.advance(Duration::from_secs(90));
+
+        assert_eq!(clock.now(), start + Duration::from_secs(90));
+        assert_eq!(clock.timestamp(), 90);
+    }
+}
@@ -0,0 +1,212 @@
+use crate::{
+    combat::{Attack, AttackKind, Combatant, Outcome, Rolls},
+    consts::{
+        BASE_CRITICAL_PERCENT, BASE_HIT_PERCENT, CRITICAL_DAMAGE_PERCENT, DEX_PER_HIT_PERCENT,
+        MAX_CRITICAL_PERCENT, MAX_HIT_PERCENT, MAX_RESIST_PERCENT, MIN_HIT_PERCENT,
+    },
+};
+
+pub fn hit_chance(attacker: &Combatant, defender: &Combatant) -> i32 {
+    let dex = attacker.dex.saturating_sub(defender.dex) / DEX_PER_HIT_PERCENT;
+    let level = attacker.level.saturating_sub(defender.level);
+
+    BASE_HIT_PERCENT
+        .saturating_add(dex)
+        .saturating_add(level)
+        .clamp(MIN_HIT_PERCENT, MAX_HIT_PERCENT)
+}
+
+pub fn critical_chance(attacker: &Combatant) -> i32 {
+    BASE_CRITICAL_PERCENT
+        .saturating_add(attacker.critical)
+        .clamp(0, MAX_CRITICAL_PERCENT)
+}
+
+pub fn resist(defender: &Combatant, element: usize) -> i32 {
+    defender
+        .resist
+        .get(element)
+        .copied()
+        .unwrap_or(0)
+        .clamp(0, MAX_RESIST_PERCENT)
+}
+
+// magic never misses, it is held back by resistances instead
+
+pub fn resolve(
+    attacker: &Combatant,
+    defender: &Combatant,
+    attack: &Attack,
+    rolls: &Rolls,
+) -> Outcome {
+    if attack.kind == AttackKind::Physical && rolls.hit >= hit_chance(attacker, defender) {
+        return Outcome {
+            miss: true,
+            ..Outcome::default()
+        };
+    }
+
+    // the skill values come from the data files, so everything is worked out wide and only
+    // narrowed back at the end
+
+    let base = match attack.kind {
+        AttackKind::Physical => attacker.damage as i64 - defender.defense as i64 / 2,
+        AttackKind::Magic { .. } => attacker.magic as i64 * 2 - defender.defense as i64 / 4,
+    };
+
+    let mut damage = base.max(0).saturating_add(attack.bonus as i64);
+    damage = damage.saturating_mul(100 + rolls.variance as i64) / 100;
+
+    let critical = rolls.critical < critical_chance(attacker);
+
+    if critical {
+        damage = damage.saturating_mul(CRITICAL_DAMAGE_PERCENT as i64) / 100;
+    }
+
+    if let AttackKind::Magic { element } = attack.kind {
+        damage = damage.saturating_mul((100 - resist(defender, element)) as i64) / 100;
+    }
+
+    Outcome {
+        damage: damage.clamp(1, i32::MAX as i64) as i32,
+        critical,
+        miss: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STEADY: Rolls = Rolls {
+        hit: 0,
+        critical: 99,
+        variance: 0,
+    };
+
+    fn fighter() -> Combatant {
+        Combatant {
+            level: 10,
+            damage: 200,
+            magic: 100,
+            defense: 100,
+            dex: 50,
+            critical: 0,
+            resist: [0; 4],
+        }
+    }
+
+    fn magic(bonus: i32) -> Attack {
+        Attack {
+            kind: AttackKind::Magic { element: 1 },
+            bonus,
+        }
+    }
+
+    #[test]
+    fn hit_chance_is_clamped() {
+        let mut attacker = fighter();
+        let defender = fighter();
+
+        assert_eq!(hit_chance(&attacker, &defender), BASE_HIT_PERCENT);
+
+        attacker.dex += 50;
+        attacker.level += 5;
+        assert_eq!(hit_chance(&attacker, &defender), MAX_HIT_PERCENT);
+
+        attacker.dex = i32::MIN;
+        assert_eq!(hit_chance(&attacker, &defender), MIN_HIT_PERCENT);
+    }
+
+    #[test]
+    fn physical_hit() {
+        let outcome = resolve(&fighter(), &fighter(), &Attack::basic(), &STEADY);
+
+        assert_eq!(
+            outcome,
+            Outcome {
+                damage: 150,
+                critical: false,
+                miss: false
+            }
+        );
+    }
+
+    #[test]
+    fn variance_scales_the_damage() {
+        let rolls = Rolls {
+            variance: -10,
+            ..STEADY
+        };
+
+        assert_eq!(
+            resolve(&fighter(), &fighter(), &Attack::basic(), &rolls).damage,
+            135
+        );
+    }
+
+    #[test]
+    fn miss_when_the_roll_is_over_the_chance() {
+        let rolls = Rolls {
+            hit: BASE_HIT_PERCENT,
+            ..STEADY
+        };
+
+        let outcome = resolve(&fighter(), &fighter(), &Attack::basic(), &rolls);
+
+        assert!(outcome.miss);
+        assert_eq!(outcome.damage, 0);
+    }
+
+    #[test]
+    fn critical_hit() {
+        let rolls = Rolls {
+            critical: BASE_CRITICAL_PERCENT - 1,
+            ..STEADY
+        };
+
+        let outcome = resolve(&fighter(), &fighter(), &Attack::basic(), &rolls);
+
+        assert!(outcome.critical);
+        assert_eq!(outcome.damage, 225);
+    }
+
+    #[test]
+    fn magic_never_misses_and_resist_is_capped() {
+        let rolls = Rolls { hit: 99, ..STEADY };
+        let mut defender = fighter();
+
+        assert_eq!(
+            resolve(&fighter(), &defender, &magic(0), &rolls).damage,
+            175
+        );
+
+        defender.resist[1] = 95;
+        assert_eq!(resist(&defender, 1), MAX_RESIST_PERCENT);
+        assert_eq!(resolve(&fighter(), &defender, &magic(0), &rolls).damage, 35);
+    }
+
+    #[test]
+    fn damage_never_drops_below_one() {
+        let mut defender = fighter();
+        defender.defense = 10_000;
+
+        assert_eq!(
+            resolve(&fighter(), &defender, &Attack::basic(), &STEADY).damage,
+            1
+        );
+    }
+
+    #[test]
+    fn huge_values_do_not_overflow() {
+        let mut attacker = fighter();
+        attacker.magic = i32::MAX;
+
+        let attack = magic(i32::MAX);
+
+        assert_eq!(
+            resolve(&attacker, &fighter(), &attack, &STEADY).damage,
+            i32::MAX
+        );
+    }
+}
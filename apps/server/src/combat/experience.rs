@@ -0,0 +1,144 @@
+use crate::consts::{EXP_GAP_PENALTY_PERCENT, EXP_LEVEL_GAP, EXP_MIN_PERCENT};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Contribution {
+    pub id: u16,
+    pub level: i32,
+    pub damage: i32,
+}
+
+// killing things far below your level is worth less and less, down to a floor
+
+pub fn level_gap_percent(player_level: i32, mob_level: i32) -> i32 {
+    let gap = player_level
+        .saturating_sub(mob_level)
+        .saturating_sub(EXP_LEVEL_GAP);
+
+    if gap <= 0 {
+        return 100;
+    }
+
+    100i32
+        .saturating_sub(gap.saturating_mul(EXP_GAP_PENALTY_PERCENT))
+        .max(EXP_MIN_PERCENT)
+}
+
+// the mob exp is split by how much of its life each one took
+
+pub fn distribute(exp: i64, mob_level: i32, contributions: &[Contribution]) -> Vec<(u16, u64)> {
+    let total: i64 = contributions.iter().map(|c| c.damage.max(0) as i64).sum();
+
+    if exp <= 0 || total <= 0 {
+        return Vec::new();
+    }
+
+    contributions
+        .iter()
+        .filter(|c| c.damage > 0)
+        .map(|c| {
+            let share = exp as i128 * c.damage as i128 / total as i128;
+            let share = share * level_gap_percent(c.level, mob_level) as i128 / 100;
+
+            (c.id, share.max(1) as u64)
+        })
+        .collect()
+}
+
+// what a party earned is pooled and handed to the members around, higher levels take a
+// bigger slice
+
+pub fn split_by_level(exp: u64, members: &[(u16, i32)]) -> Vec<(u16, u64)> {
+    let total: u64 = members
+        .iter()
+        .map(|(_, level)| (*level).max(1) as u64)
+        .sum();
+
+    if exp == 0 || total == 0 {
+        return Vec::new();
+    }
+
+    members
+        .iter()
+        .map(|(id, level)| {
+            let share = exp as u128 * (*level).max(1) as u128 / total as u128;
+
+            (*id, (share as u64).max(1))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_gap_penalty() {
+        assert_eq!(level_gap_percent(20, 10), 100);
+        assert_eq!(level_gap_percent(25, 10), 50);
+        assert_eq!(level_gap_percent(30, 10), EXP_MIN_PERCENT);
+        assert_eq!(level_gap_percent(i32::MAX, i32::MIN), EXP_MIN_PERCENT);
+    }
+
+    #[test]
+    fn exp_follows_the_damage_dealt() {
+        let contributions = [
+            Contribution {
+                id: 1,
+                level: 10,
+                damage: 300,
+            },
+            Contribution {
+                id: 2,
+                level: 25,
+                damage: 100,
+            },
+            Contribution {
+                id: 3,
+                level: 10,
+                damage: 0,
+            },
+        ];
+
+        assert_eq!(
+            distribute(1000, 10, &contributions),
+            vec![(1, 750), (2, 125)]
+        );
+    }
+
+    #[test]
+    fn every_hitter_gets_at_least_one() {
+        let contributions = [
+            Contribution {
+                id: 1,
+                level: 10,
+                damage: 1,
+            },
+            Contribution {
+                id: 2,
+                level: 10,
+                damage: 1000,
+            },
+        ];
+
+        assert_eq!(distribute(10, 10, &contributions), vec![(1, 1), (2, 9)]);
+        assert!(distribute(0, 10, &contributions).is_empty());
+    }
+
+    #[test]
+    fn party_split_rounds_down() {
+        assert_eq!(
+            split_by_level(100, &[(1, 10), (2, 20)]),
+            vec![(1, 33), (2, 66)]
+        );
+        assert_eq!(split_by_level(1, &[(1, 10), (2, 20)]), vec![(1, 1), (2, 1)]);
+        assert!(split_by_level(0, &[(1, 10)]).is_empty());
+    }
+
+    #[test]
+    fn huge_exp_does_not_overflow() {
+        assert_eq!(
+            split_by_level(u64::MAX, &[(1, 1), (2, 1)]),
+            vec![(1, u64::MAX / 2), (2, u64::MAX / 2)]
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use rand::Rng;
+
+use crate::consts::DAMAGE_VARIANCE_PERCENT;
+
+pub mod damage;
+pub mod experience;
+pub mod rules;
+
+// the numbers the rules work with, players and mobs are both turned into this before a hit
+// is resolved so the formulas never look at where they came from
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Combatant {
+    pub level: i32,
+    pub damage: i32,
+    pub magic: i32,
+    pub defense: i32,
+    pub dex: i32,
+    pub critical: i32,
+    pub resist: [i32; 4],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttackKind {
+    Physical,
+    Magic { element: usize },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Attack {
+    pub kind: AttackKind,
+    pub bonus: i32,
+}
+
+impl Attack {
+    pub fn basic() -> Self {
+        Self {
+            kind: AttackKind::Physical,
+            bonus: 0,
+        }
+    }
+}
+
+// every random part of a hit is rolled up front, the same rolls always give the same outcome
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Rolls {
+    pub hit: i32,
+    pub critical: i32,
+    pub variance: i32,
+}
+
+impl Rolls {
+    pub fn roll<R: Rng>(rng: &mut R) -> Self {
+        Self {
+            hit: rng.gen_range(0..100),
+            critical: rng.gen_range(0..100),
+            variance: rng.gen_range(-DAMAGE_VARIANCE_PERCENT..=DAMAGE_VARIANCE_PERCENT),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Outcome {
+    pub damage: i32,
+    pub critical: bool,
+    pub miss: bool,
+}
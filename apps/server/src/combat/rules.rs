@@ -0,0 +1,66 @@
+use std::fmt::Display;
+
+use crate::{consts::PVP_DAMAGE_PERCENT, structs::position::Position, world::map::Map};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CombatError {
+    NotFound,
+    Dead,
+    Myself,
+    Protected,
+    SafeZone,
+    NoPvp,
+    OutOfRange,
+    TooFast,
+}
+
+impl Display for CombatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CombatError::NotFound => write!(f, "target not found"),
+            CombatError::Dead => write!(f, "dead"),
+            CombatError::Myself => write!(f, "attacking itself"),
+            CombatError::Protected => write!(f, "target is protected"),
+            CombatError::SafeZone => write!(f, "safe zone"),
+            CombatError::NoPvp => write!(f, "pvp not allowed"),
+            CombatError::OutOfRange => write!(f, "out of range"),
+            CombatError::TooFast => write!(f, "too fast"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Player { at_war: bool, event: bool },
+    Mob { merchant: bool },
+}
+
+// nobody fights inside safe zones, and players only fight each other where both stand on
+// pvp ground unless their guilds are at war or an event has opened the ground they stand on
+
+pub fn can_attack(
+    map: &Map,
+    attacker: &Position,
+    target_position: &Position,
+    target: Target,
+) -> Result<(), CombatError> {
+    if map.is_safe(attacker) || map.is_safe(target_position) {
+        return Err(CombatError::SafeZone);
+    }
+
+    match target {
+        Target::Mob { merchant: true } => Err(CombatError::Protected),
+        Target::Mob { merchant: false } => Ok(()),
+        Target::Player { at_war: true, .. } | Target::Player { event: true, .. } => Ok(()),
+        Target::Player { .. } if map.is_pvp(attacker) && map.is_pvp(target_position) => Ok(()),
+        Target::Player { .. } => Err(CombatError::NoPvp),
+    }
+}
+
+pub fn in_range(attacker: &Position, target: &Position, range: u16) -> bool {
+    attacker.distance(target) <= range
+}
+
+pub fn pvp_damage(damage: i32) -> i32 {
+    (damage * PVP_DAMAGE_PERCENT / 100).max(1)
+}
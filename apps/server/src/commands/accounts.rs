@@ -0,0 +1,29 @@
+use crate::{
+    repository::accounts::GmLevel,
+    statics::{REPOSITORY, WORLD},
+};
+
+pub async fn create(username: &str, password: &str) {
+    match REPOSITORY.create_account(username, password).await {
+        Ok(account) => println!("Account {} created", account.username),
+        Err(error) => println!("Account {} not created: {:?}", username, error),
+    }
+}
+
+pub async fn set_gm_level(username: &str, level: &str) {
+    let level = match GmLevel::from_name(level) {
+        Some(level) => level,
+        None => {
+            println!(
+                "Unknown level {}, use player, moderator, gamemaster or admin",
+                level
+            );
+            return;
+        }
+    };
+
+    match WORLD.set_gm_level(username, level).await {
+        true => println!("Account {} is now {:?}", username, level),
+        false => println!("Account {} not found", username),
+    }
+}
@@ -0,0 +1,47 @@
+use crate::statics::WORLD;
+
+pub async fn mute(name: &str, minutes: &str) {
+    let minutes = match minutes.parse::<u64>() {
+        Ok(minutes) => minutes,
+        Err(_error) => {
+            println!("Invalid minutes {}", minutes);
+            return;
+        }
+    };
+
+    match WORLD.mute(name, minutes).await {
+        true if minutes == 0 => println!("{} is no longer muted", name),
+        true => println!("{} muted for {} minutes", name, minutes),
+        false => println!("Could not mute {}", name),
+    }
+}
+
+pub fn show_filter() {
+    let words = WORLD.filter.words();
+
+    match words.is_empty() {
+        true => println!("Chat filter is empty"),
+        false => println!("Chat filter: {}", words.join(", ")),
+    }
+}
+
+pub fn add_filter(word: &str) {
+    let mut words = WORLD.filter.words();
+    words.push(word.to_string());
+    WORLD.filter.set_words(&words);
+
+    show_filter();
+}
+
+pub fn remove_filter(word: &str) {
+    let word = word.to_lowercase();
+    let words = WORLD
+        .filter
+        .words()
+        .into_iter()
+        .filter(|current| *current != word)
+        .collect::<Vec<_>>();
+    WORLD.filter.set_words(&words);
+
+    show_filter();
+}
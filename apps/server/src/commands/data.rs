@@ -0,0 +1,52 @@
+use server_data::{data_folder, RELOAD_REQUEST_FILE};
+use tokio::{fs, time::interval};
+
+use crate::{consts::RELOAD_POLL_INTERVAL, statics::WORLD, world::data::DataKind};
+
+pub async fn reload(names: &[&str]) {
+    let kinds = match DataKind::parse_list(names) {
+        Some(kinds) => kinds,
+        None => {
+            println!("Unknown data, use items, mobs, skills, recipes or scripts");
+            return;
+        }
+    };
+
+    let names = kinds
+        .iter()
+        .map(|kind| kind.to_string())
+        .collect::<Vec<_>>();
+
+    match WORLD.reload(&kinds).await {
+        Ok(()) => println!("Reloaded {}", names.join(", ")),
+        Err(error) => println!(
+            "Reload of {} failed, nothing changed: {}",
+            names.join(", "),
+            error
+        ),
+    }
+}
+
+// the manager asks for a reload by dropping a file in the data folder, it holds the names of
+// what to reload and is removed once it is picked up
+
+pub async fn watch() {
+    let file = data_folder().join(RELOAD_REQUEST_FILE);
+    let mut interval = interval(RELOAD_POLL_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        let content = match fs::read_to_string(&file).await {
+            Ok(content) => content,
+            Err(_error) => continue,
+        };
+
+        if let Err(error) = fs::remove_file(&file).await {
+            println!("commands.data.watch.error: {}", error);
+            continue;
+        }
+
+        reload(&content.split_whitespace().collect::<Vec<_>>()).await;
+    }
+}
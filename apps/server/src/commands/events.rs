@@ -0,0 +1,43 @@
+use crate::{statics::WORLD, world::events::Phase};
+
+pub async fn show() {
+    let events = WORLD.events().await;
+
+    if events.is_empty() {
+        println!("No events scheduled");
+        return;
+    }
+
+    let now = WORLD.clock.timestamp();
+
+    for (index, (name, phase, owner)) in events.iter().enumerate() {
+        let phase = match phase {
+            Phase::Idle => "idle".to_string(),
+            Phase::Registration { starts_at } => {
+                format!("registration, starts in {}s", starts_at.saturating_sub(now))
+            }
+            Phase::Battle { ends_at } => {
+                format!("battle, ends in {}s", ends_at.saturating_sub(now))
+            }
+        };
+
+        match owner {
+            Some(owner) => println!("{} {}: {}, held by guild {}", index, name, phase, owner),
+            None => println!("{} {}: {}", index, name, phase),
+        }
+    }
+}
+
+pub async fn start(index: &str) {
+    match index.parse::<usize>() {
+        Ok(index) if WORLD.start_event(index).await => show().await,
+        _ => println!("Could not start event {}", index),
+    }
+}
+
+pub async fn stop(index: &str) {
+    match index.parse::<usize>() {
+        Ok(index) if WORLD.stop_event(index).await => show().await,
+        _ => println!("Could not stop event {}", index),
+    }
+}
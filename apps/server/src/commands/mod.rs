@@ -0,0 +1,49 @@
+use tokio::io::{stdin, AsyncBufReadExt, BufReader};
+
+pub mod accounts;
+pub mod chat;
+pub mod data;
+pub mod events;
+pub mod rates;
+
+pub async fn listen() {
+    let mut lines = BufReader::new(stdin()).lines();
+
+    while let Ok(Some(line)) = lines.next_line().await {
+        let args = line.split_whitespace().collect::<Vec<_>>();
+
+        match args.as_slice() {
+            [] => {}
+            ["help"] => help(),
+            ["account", "create", username, password] => accounts::create(username, password).await,
+            ["account", "gm", username, level] => accounts::set_gm_level(username, level).await,
+            ["events"] => events::show().await,
+            ["event", "start", index] => events::start(index).await,
+            ["event", "stop", index] => events::stop(index).await,
+            ["mute", name, minutes] => chat::mute(name, minutes).await,
+            ["unmute", name] => chat::mute(name, "0").await,
+            ["filter"] => chat::show_filter(),
+            ["filter", "add", word] => chat::add_filter(word),
+            ["filter", "remove", word] => chat::remove_filter(word),
+            ["rates"] => rates::show(),
+            ["rates", kind, percent] => rates::set(kind, percent),
+            ["reload", kinds @ ..] => data::reload(kinds).await,
+            _ => println!("Unknown command, type help to list them"),
+        }
+    }
+}
+
+fn help() {
+    println!("help");
+    println!("account create <username> <password>");
+    println!("account gm <username> <player|moderator|gamemaster|admin>");
+    println!("events");
+    println!("event <start|stop> <index>");
+    println!("mute <name> <minutes>");
+    println!("unmute <name>");
+    println!("filter");
+    println!("filter <add|remove> <word>");
+    println!("rates");
+    println!("rates <drop|coin> <percent>");
+    println!("reload [items|mobs|skills|recipes|scripts]");
+}
@@ -0,0 +1,30 @@
+use crate::statics::WORLD;
+
+pub fn show() {
+    println!(
+        "Drop rate {}%, coin rate {}%",
+        WORLD.rates.drop(),
+        WORLD.rates.coin()
+    );
+}
+
+pub fn set(kind: &str, percent: &str) {
+    let percent = match percent.parse::<u32>() {
+        Ok(percent) => percent,
+        Err(_error) => {
+            println!("Invalid percent {}", percent);
+            return;
+        }
+    };
+
+    match kind {
+        "drop" => WORLD.rates.set_drop(percent),
+        "coin" => WORLD.rates.set_coin(percent),
+        _ => {
+            println!("Unknown rate {}, use drop or coin", kind);
+            return;
+        }
+    }
+
+    show();
+}
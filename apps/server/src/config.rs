@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+use server_data::data_folder;
+use std::fs;
+
+use crate::consts::{CONFIG_FILE, DEFAULT_ADDRESS};
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub address: String,
+    pub auto_register: bool,
+    pub drop_rate: u32,
+    pub coin_rate: u32,
+    pub shop_tax: u32,
+    pub chat_filter: Vec<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            address: DEFAULT_ADDRESS.to_string(),
+            auto_register: false,
+            drop_rate: 100,
+            coin_rate: 100,
+            shop_tax: 0,
+            chat_filter: Vec::new(),
+        }
+    }
+}
+
+impl Config {
+    pub fn load() -> Self {
+        let file = data_folder().join(CONFIG_FILE);
+
+        match fs::read_to_string(&file) {
+            Ok(content) => match serde_json::from_str::<Config>(&content) {
+                Ok(config) => config,
+                Err(error) => {
+                    println!("config.load.error: {}", error);
+                    Self::default()
+                }
+            },
+            Err(_error) => Self::default(),
+        }
+    }
+}
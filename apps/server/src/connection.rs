@@ -0,0 +1,110 @@
+use enc_dec::decode;
+use std::time::Duration;
+use tokio::{
+    io::{split, AsyncReadExt, AsyncWriteExt, ReadHalf},
+    net::{TcpListener, TcpStream},
+    select,
+    sync::mpsc::unbounded_channel,
+    time::timeout,
+};
+
+use crate::{
+    consts::{HEADER_SIZE, HELLO_SIZE, MAX_PACKET_SIZE},
+    handlers,
+    session::Session,
+    statics::{CONFIG, SESSIONS},
+};
+
+pub async fn listen() {
+    let listener = match TcpListener::bind(&CONFIG.address).await {
+        Ok(listener) => listener,
+        Err(error) => {
+            println!("connection.listen.error: {}", error);
+            return;
+        }
+    };
+
+    println!("Listening on {}", CONFIG.address);
+
+    loop {
+        match listener.accept().await {
+            Ok((stream, address)) => {
+                tokio::spawn(handle_connection(stream, address.ip().to_string()));
+            }
+            Err(error) => {
+                println!("connection.accept.error: {}", error);
+            }
+        }
+    }
+}
+
+async fn handle_connection(stream: TcpStream, ip: String) {
+    let (mut reader, mut writer) = split(stream);
+    let (sender, mut receiver) = unbounded_channel::<Vec<u8>>();
+
+    let session = match SESSIONS.add(ip, sender).await {
+        Some(session) => session,
+        None => return,
+    };
+
+    // an empty buffer tells the writer to flush what is left and stop
+
+    let writer_task = tokio::spawn(async move {
+        while let Some(buf) = receiver.recv().await {
+            if buf.is_empty() || writer.write_all(&buf).await.is_err() {
+                break;
+            }
+        }
+
+        let _ = writer.shutdown().await;
+    });
+
+    read_packets(&session, &mut reader).await;
+
+    handlers::disconnect(&session).await;
+    SESSIONS.remove(&session).await;
+
+    session.send_raw(Vec::new());
+    let _ = timeout(Duration::from_secs(5), writer_task).await;
+}
+
+async fn read_packets(session: &Session, reader: &mut ReadHalf<TcpStream>) {
+    let mut hello = [0u8; HELLO_SIZE];
+
+    match timeout(Duration::from_secs(10), reader.read_exact(&mut hello)).await {
+        Ok(Ok(_)) => {}
+        _ => return,
+    };
+
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+
+    loop {
+        let size = select! {
+            result = reader.read(&mut chunk) => match result {
+                Ok(0) | Err(_) => break,
+                Ok(size) => size,
+            },
+            _ = session.closed() => break,
+        };
+
+        buf.extend_from_slice(&chunk[..size]);
+
+        while buf.len() >= HEADER_SIZE {
+            let size = u16::from_le_bytes([buf[0], buf[1]]) as usize;
+
+            if !(HEADER_SIZE..=MAX_PACKET_SIZE).contains(&size) {
+                return;
+            }
+
+            if buf.len() < size {
+                break;
+            }
+
+            let mut packet = buf.drain(..size).collect::<Vec<_>>();
+            decode(&mut packet);
+
+            handlers::handle(session, packet).await;
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use std::time::Duration;
+
+pub const ACCOUNTS_FOLDER: &str = "accounts";
+pub const CONFIG_FILE: &str = "config.json";
+
+pub const DEFAULT_ADDRESS: &str = "0.0.0.0:8281";
+
+pub const HELLO_SIZE: usize = 4;
+pub const HEADER_SIZE: usize = 12;
+pub const MAX_PACKET_SIZE: usize = 8192;
+
+pub const MAX_USERS: u16 = 1000;
+
+pub const USERNAME_MIN_LEN: usize = 4;
+pub const USERNAME_MAX_LEN: usize = 12;
+pub const PASSWORD_MIN_LEN: usize = 4;
+pub const PASSWORD_MAX_LEN: usize = 12;
+
+pub const CHARACTER_NAME_MIN_LEN: usize = 4;
+pub const CHARACTER_NAME_MAX_LEN: usize = 12;
+pub const CHARACTERS_PER_ACCOUNT: usize = 4;
+
+pub const EQUIP_SLOTS: usize = 16;
+pub const INVENTORY_SLOTS: usize = 64;
+pub const STORAGE_SLOTS: usize = 128;
+pub const ITEM_EFFECT_AMOUNT: u8 = 61;
+pub const ITEM_EFFECT_SANC: u8 = 43;
+
+pub const SPAWN_POSITION: (u16, u16) = (2100, 2100);
+pub const BASE_SPEED: u8 = 3;
+
+pub const MAP_FOLDER: &str = "map";
+pub const HEIGHT_MAP_FILE: &str = "HeightMap.dat";
+pub const ATTRIBUTE_MAP_FILE: &str = "AttributeMap.dat";
+
+pub const MAP_SIZE: usize = 4096;
+pub const ATTRIBUTE_MAP_SIZE: usize = 1024;
+
+pub const MAP_ATTRIBUTE_VILLAGE: u8 = 0x01;
+pub const MAP_ATTRIBUTE_BLOCKED: u8 = 0x02;
+pub const MAP_ATTRIBUTE_SAFE: u8 = 0x10;
+pub const MAP_ATTRIBUTE_PVP: u8 = 0x40;
+
+pub const GRID_CELL_SIZE: usize = 16;
+pub const VIEW_RANGE: u16 = 16;
+
+pub const MAX_STEP_HEIGHT: u8 = 15;
+pub const MAX_POSITION_DRIFT: u16 = 1;
+pub const MOVE_TILES_PER_SPEED: f32 = 1.5;
+pub const MOVE_SPEED_TOLERANCE: f32 = 1.25;
+pub const MOVE_BURST_TILES: f32 = 24.0;
+
+pub const NPC_FOLDER: &str = "npc";
+pub const NPC_GENERATOR_FILE: &str = "NPCGener.txt";
+
+pub const FIRST_MOB_ID: u16 = MAX_USERS + 1;
+pub const LAST_MOB_ID: u16 = 30000;
+
+pub const SPAWN_ATTEMPTS: usize = 10;
+pub const SPAWN_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+pub const FIRST_GROUND_ITEM_ID: u16 = 10000;
+pub const LAST_GROUND_ITEM_ID: u16 = 15000;
+pub const GROUND_ITEM_DURATION: Duration = Duration::from_secs(120);
+pub const GROUND_OWNER_DURATION: Duration = Duration::from_secs(30);
+pub const PICKUP_RANGE: u16 = 3;
+pub const DROP_RANGE: u16 = 2;
+
+pub const TICK_INTERVAL: Duration = Duration::from_millis(100);
+pub const MAX_CATCH_UP_TICKS: u32 = 10;
+
+pub const REGEN_INTERVAL: Duration = Duration::from_secs(5);
+pub const REGEN_PERCENT: i32 = 5;
+
+pub const MOB_AGGRO_RANGE: u16 = 6;
+pub const MOB_ATTACK_RANGE: u16 = 1;
+pub const MOB_LEASH_RANGE: u16 = 20;
+pub const MOB_FLEE_HP_PERCENT: i32 = 15;
+pub const MOB_FLEE_DISTANCE: u16 = 8;
+pub const MOB_FLEE_DURATION: Duration = Duration::from_secs(5);
+pub const MOB_MOVE_INTERVAL: Duration = Duration::from_millis(1000);
+pub const MOB_ATTACK_INTERVAL: Duration = Duration::from_millis(1500);
+pub const MOB_THINK_INTERVAL: Duration = Duration::from_millis(500);
+
+pub const SKILL_DATA_FILE: &str = "SkillData.bin";
+pub const SKILLS_PER_CLASS: usize = 24;
+pub const SKILL_AREA_RANGE: u16 = 3;
+
+pub const AFFECT_SLOW: u8 = 1;
+pub const AFFECT_HASTE: u8 = 2;
+pub const AFFECT_POISON: u8 = 3;
+pub const AFFECT_SHIELD: u8 = 4;
+pub const AFFECT_TICK_INTERVAL: Duration = Duration::from_secs(1);
+pub const POISON_MAX_STACKS: u8 = 3;
+
+pub const PLAYER_ATTACK_RANGE: u16 = 2;
+pub const PLAYER_ATTACK_INTERVAL: Duration = Duration::from_millis(700);
+
+pub const BASE_HIT_PERCENT: i32 = 85;
+pub const MIN_HIT_PERCENT: i32 = 20;
+pub const MAX_HIT_PERCENT: i32 = 98;
+pub const DEX_PER_HIT_PERCENT: i32 = 5;
+pub const BASE_CRITICAL_PERCENT: i32 = 5;
+pub const MAX_CRITICAL_PERCENT: i32 = 50;
+pub const CRITICAL_DAMAGE_PERCENT: i32 = 150;
+pub const DAMAGE_VARIANCE_PERCENT: i32 = 10;
+pub const MAX_RESIST_PERCENT: i32 = 80;
+pub const PVP_DAMAGE_PERCENT: i32 = 50;
+
+pub const MAX_LEVEL: u16 = 400;
+pub const MAX_STAT: i16 = 32000;
+pub const EXP_TABLE_BASE: u64 = 100;
+pub const EXP_TABLE_FACTOR: u64 = 12;
+
+pub const EXP_LEVEL_GAP: i32 = 10;
+pub const EXP_GAP_PENALTY_PERCENT: i32 = 10;
+pub const EXP_MIN_PERCENT: i32 = 10;
+
+pub const MAX_REFINE: u8 = 15;
+pub const REFINE_MATERIALS: [(i16, u8); 2] = [(412, 9), (413, 15)];
+
+// success, downgrade and break percent for each level being reached, whatever is left over
+// only costs the material
+
+pub const REFINE_TABLE: [(u8, u8, u8); MAX_REFINE as usize] = [
+    (100, 0, 0),
+    (100, 0, 0),
+    (90, 0, 0),
+    (80, 0, 0),
+    (70, 0, 0),
+    (60, 10, 0),
+    (50, 20, 0),
+    (40, 25, 0),
+    (30, 30, 5),
+    (25, 30, 10),
+    (20, 30, 15),
+    (15, 30, 20),
+    (10, 30, 25),
+    (7, 30, 30),
+    (5, 30, 35),
+];
+
+pub const COMPOSE_FILE: &str = "Compose.txt";
+
+// chance out of 10000 for each group of eight carried items, the rarer ones are at the end
+
+pub const DROP_TIER_SIZE: usize = 8;
+pub const DROP_TIER_CHANCES: [u32; 8] = [4000, 2000, 1000, 500, 200, 100, 30, 10];
+pub const DROP_CHANCE_SCALE: u32 = 10000;
+pub const DROP_SPREAD: u16 = 1;
+
+pub const MAX_COIN: u32 = 2_000_000_000;
+pub const SHOP_RANGE: u16 = 6;
+pub const SELL_PRICE_PERCENT: u32 = 25;
+
+pub const TRADE_RANGE: u16 = 6;
+
+pub const VENDOR_TITLE_MAX_LEN: usize = 23;
+
+pub const AUCTIONS_FILE: &str = "auctions.json";
+pub const AUCTION_DURATION: Duration = Duration::from_secs(48 * 60 * 60);
+pub const AUCTION_FEE_PERCENT: u32 = 5;
+pub const AUCTION_LIMIT: usize = 10;
+pub const AUCTION_SENDER: &str = "Leilão";
+
+pub const PARTY_MAX_MEMBERS: usize = 12;
+pub const PARTY_EXP_RANGE: u16 = 16;
+pub const PARTY_CHAT_TARGET: &str = "=";
+
+pub const GUILDS_FOLDER: &str = "guilds";
+pub const GUILD_CREATE_FEE: u32 = 1_000_000;
+pub const GUILD_MAX_MEMBERS: usize = 50;
+pub const GUILD_NOTICE_MAX_LEN: usize = 79;
+
+pub const GUILD_CHAT_TARGET: &str = "-";
+pub const SHOUT_CHAT_TARGET: &str = "@";
+pub const CHAT_BURST: usize = 5;
+pub const CHAT_WINDOW: Duration = Duration::from_secs(5);
+pub const SHOUT_INTERVAL: Duration = Duration::from_secs(30);
+
+pub const AUDIT_FILE: &str = "gm.log";
+pub const GM_COMMAND_PREFIX: char = '/';
+pub const GM_SPAWN_MAX: u16 = 10;
+pub const SUMMONED_GROUP: usize = usize::MAX;
+
+pub const EVENTS_FILE: &str = "events.json";
+pub const CASTLES_FILE: &str = "castles.json";
+pub const CASTLE_SENDER: &str = "Castelo";
+pub const EVENT_MAX_MINUTES: u64 = 24 * 60;
+pub const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+pub const UNIX_EPOCH_WEEKDAY: u64 = 4;
+pub const TOWER_RANGE: u16 = 3;
+pub const TOWER_CAPTURE_TIME: Duration = Duration::from_secs(30);
+
+pub const SCRIPTS_FOLDER: &str = "scripts";
+pub const NPC_SCRIPTS_FOLDER: &str = "npcs";
+pub const ITEM_SCRIPTS_FOLDER: &str = "items";
+pub const EVENT_SCRIPTS_FOLDER: &str = "events";
+pub const SCRIPT_EXTENSION: &str = "rhai";
+pub const SCRIPT_MAX_OPERATIONS: u64 = 100_000;
+pub const SCRIPT_MAX_CALL_LEVELS: usize = 32;
+pub const SCRIPT_MAX_STRING_SIZE: usize = 1024;
+pub const SCRIPT_MAX_COLLECTION_SIZE: usize = 256;
+pub const SCRIPT_MAX_ACTIONS: usize = 64;
+pub const QUEST_FLAGS_MAX: usize = 256;
+pub const NPC_TALK_RANGE: u16 = 6;
+
+pub const RELOAD_POLL_INTERVAL: Duration = Duration::from_secs(1);
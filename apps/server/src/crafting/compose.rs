@@ -0,0 +1,151 @@
+use encoding_rs::WINDOWS_1252;
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{consts::COMPOSE_FILE, world::data::DataError};
+
+// one line of the recipes file: result chance coin index:amount index:amount ...
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recipe {
+    pub result: i16,
+    pub chance: u8,
+    pub coin: u32,
+    pub materials: BTreeMap<i16, u16>,
+}
+
+impl Recipe {
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split_whitespace();
+
+        let result = fields.next()?.parse().ok()?;
+        let chance = fields.next()?.parse::<u8>().ok()?.min(100);
+        let coin = fields.next()?.parse().ok()?;
+
+        let mut materials = BTreeMap::new();
+
+        for field in fields {
+            let (index, amount) = field.split_once(':')?;
+            let amount: u16 = amount.parse().ok().filter(|amount| *amount > 0)?;
+
+            *materials.entry(index.parse().ok()?).or_insert(0) += amount;
+        }
+
+        if materials.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            result,
+            chance,
+            coin,
+            materials,
+        })
+    }
+}
+
+// the materials have to match exactly, nothing more and nothing less
+
+pub fn find<'a>(recipes: &'a [Recipe], materials: &BTreeMap<i16, u16>) -> Option<&'a Recipe> {
+    recipes.iter().find(|recipe| recipe.materials == *materials)
+}
+
+pub fn load(folder: PathBuf) -> Vec<Recipe> {
+    match read(&folder, false) {
+        Ok(recipes) => recipes,
+        Err(_error) => {
+            println!("compose.load: {} not found, no recipes", COMPOSE_FILE);
+            Vec::new()
+        }
+    }
+}
+
+pub fn read(folder: &Path, strict: bool) -> Result<Vec<Recipe>, DataError> {
+    let content = match fs::read(folder.join(COMPOSE_FILE)) {
+        Ok(buf) => WINDOWS_1252.decode(&buf).0.to_string(),
+        Err(_error) => return Err(DataError::Missing(COMPOSE_FILE.to_string())),
+    };
+
+    let mut recipes = Vec::new();
+
+    for (number, line) in content.lines().enumerate() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with("//") {
+            continue;
+        }
+
+        match Recipe::parse(line) {
+            Some(recipe) => recipes.push(recipe),
+            None if strict => {
+                return Err(DataError::Invalid(format!(
+                    "{} linha {}",
+                    COMPOSE_FILE,
+                    number + 1
+                )))
+            }
+            None => println!("compose.load.error: line {} skipped", number + 1),
+        }
+    }
+
+    println!("Loaded {} recipes", recipes.len());
+
+    Ok(recipes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn materials(list: &[(i16, u16)]) -> BTreeMap<i16, u16> {
+        list.iter().copied().collect()
+    }
+
+    #[test]
+    fn a_line_reads_into_a_recipe() {
+        assert_eq!(
+            Recipe::parse("3200 60 5000 412:2 413:1"),
+            Some(Recipe {
+                result: 3200,
+                chance: 60,
+                coin: 5000,
+                materials: materials(&[(412, 2), (413, 1)]),
+            })
+        );
+    }
+
+    #[test]
+    fn a_material_listed_twice_is_summed() {
+        let recipe = Recipe::parse("3200 150 0 412:2 413:1 412:3").expect("recipe");
+
+        assert_eq!(recipe.chance, 100);
+        assert_eq!(recipe.materials, materials(&[(412, 5), (413, 1)]));
+    }
+
+    #[test]
+    fn broken_lines_are_refused() {
+        assert_eq!(Recipe::parse("3200 60 5000"), None);
+        assert_eq!(Recipe::parse("3200 60 5000 412:0"), None);
+        assert_eq!(Recipe::parse("3200 60 5000 412"), None);
+        assert_eq!(Recipe::parse("3200 sixty 5000 412:1"), None);
+    }
+
+    #[test]
+    fn only_the_exact_materials_find_a_recipe() {
+        let recipes = vec![
+            Recipe::parse("3200 60 0 412:2 413:1").expect("recipe"),
+            Recipe::parse("3201 60 0 412:2").expect("recipe"),
+        ];
+
+        let found = |list: &[(i16, u16)]| find(&recipes, &materials(list)).map(|r| r.result);
+
+        assert_eq!(found(&[(412, 2), (413, 1)]), Some(3200));
+        assert_eq!(found(&[(412, 2)]), Some(3201));
+        assert_eq!(found(&[(412, 3)]), None);
+        assert_eq!(found(&[(412, 2), (413, 1), (414, 1)]), None);
+        assert_eq!(found(&[(413, 1)]), None);
+    }
+}
@@ -0,0 +1,68 @@
+use rand::{rngs::StdRng, thread_rng, Rng, SeedableRng};
+use std::fmt::Display;
+
+use crate::structs::inventory::ItemError;
+
+pub mod compose;
+pub mod refine;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CraftError {
+    Item(ItemError),
+    NotRefinable,
+    NotMaterial,
+    MaxRefine,
+    WeakMaterial,
+    UnknownRecipe,
+    NotEnoughCoin,
+}
+
+impl Display for CraftError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CraftError::Item(error) => write!(f, "{}", error),
+            CraftError::NotRefinable => write!(f, "Este item não pode ser refinado."),
+            CraftError::NotMaterial => write!(f, "Este item não serve para refinar."),
+            CraftError::MaxRefine => write!(f, "Este item já está no nível máximo."),
+            CraftError::WeakMaterial => write!(f, "Este material não refina tão alto."),
+            CraftError::UnknownRecipe => write!(f, "Combinação inválida."),
+            CraftError::NotEnoughCoin => write!(f, "Gold insuficiente."),
+        }
+    }
+}
+
+impl From<ItemError> for CraftError {
+    fn from(error: ItemError) -> Self {
+        CraftError::Item(error)
+    }
+}
+
+// every craft rolls from its own seed, logging the seed is enough to replay what happened
+
+pub struct EventRng {
+    pub seed: u64,
+    rng: StdRng,
+}
+
+impl EventRng {
+    pub fn new() -> Self {
+        Self::from_seed(thread_rng().gen())
+    }
+
+    pub fn from_seed(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn percent(&mut self) -> u8 {
+        self.rng.gen_range(0..100)
+    }
+}
+
+impl Default for EventRng {
+    fn default() -> Self {
+        Self::new()
+    }
+}
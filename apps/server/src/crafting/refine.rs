@@ -0,0 +1,157 @@
+use std::fmt::Display;
+
+use crate::{
+    consts::{MAX_REFINE, REFINE_MATERIALS, REFINE_TABLE},
+    crafting::CraftError,
+    structs::{item::Item, item_table::ItemTable},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefineOutcome {
+    Success(u8),
+    Fail,
+    Downgrade(u8),
+    Break,
+}
+
+impl Display for RefineOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RefineOutcome::Success(level) => write!(f, "Refinação bem sucedida! +{}", level),
+            RefineOutcome::Fail => write!(f, "A refinação falhou."),
+            RefineOutcome::Downgrade(level) => {
+                write!(f, "A refinação falhou e o item voltou para +{}.", level)
+            }
+            RefineOutcome::Break => write!(f, "A refinação falhou e o item foi destruído."),
+        }
+    }
+}
+
+pub fn material_limit(index: i16) -> Option<u8> {
+    REFINE_MATERIALS
+        .iter()
+        .find(|(material, _)| *material == index)
+        .map(|(_, limit)| *limit)
+}
+
+// only equipment that does not stack can take a refine
+
+pub fn check(item: &Item, material: &Item, items: &ItemTable) -> Result<(), CraftError> {
+    let info = items.get(item.index).ok_or(CraftError::NotRefinable)?;
+
+    if info.slot_mask == 0 || info.is_stackable() {
+        return Err(CraftError::NotRefinable);
+    }
+
+    if item.refine() == 0 && item.effects.iter().all(|(effect, _)| *effect != 0) {
+        return Err(CraftError::NotRefinable);
+    }
+
+    let limit = material_limit(material.index).ok_or(CraftError::NotMaterial)?;
+
+    if item.refine() >= MAX_REFINE {
+        return Err(CraftError::MaxRefine);
+    }
+
+    if item.refine() >= limit {
+        return Err(CraftError::WeakMaterial);
+    }
+
+    Ok(())
+}
+
+// the roll is taken against the chances of the level being reached
+
+pub fn outcome(level: u8, roll: u8) -> RefineOutcome {
+    let next = level.saturating_add(1).min(MAX_REFINE);
+
+    let (success, downgrade, destroy) = REFINE_TABLE
+        .get(next as usize - 1)
+        .copied()
+        .unwrap_or((0, 0, 0));
+
+    if roll < success {
+        RefineOutcome::Success(next)
+    } else if roll < success + downgrade {
+        RefineOutcome::Downgrade(level.saturating_sub(1))
+    } else if roll < success + downgrade + destroy {
+        RefineOutcome::Break
+    } else {
+        RefineOutcome::Fail
+    }
+}
+
+pub fn apply(item: &mut Item, outcome: RefineOutcome) {
+    match outcome {
+        RefineOutcome::Success(level) | RefineOutcome::Downgrade(level) => {
+            item.set_refine(level);
+        }
+        RefineOutcome::Break => *item = Item::default(),
+        RefineOutcome::Fail => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crafting::EventRng;
+
+    #[test]
+    fn the_roll_falls_into_the_chances_of_the_next_level() {
+        // reaching +9 is 30 success, 30 downgrade and 5 break
+
+        assert_eq!(outcome(8, 0), RefineOutcome::Success(9));
+        assert_eq!(outcome(8, 29), RefineOutcome::Success(9));
+        assert_eq!(outcome(8, 30), RefineOutcome::Downgrade(7));
+        assert_eq!(outcome(8, 59), RefineOutcome::Downgrade(7));
+        assert_eq!(outcome(8, 60), RefineOutcome::Break);
+        assert_eq!(outcome(8, 64), RefineOutcome::Break);
+        assert_eq!(outcome(8, 65), RefineOutcome::Fail);
+        assert_eq!(outcome(8, 99), RefineOutcome::Fail);
+    }
+
+    #[test]
+    fn the_first_levels_never_fail() {
+        for roll in 0..100 {
+            assert_eq!(outcome(0, roll), RefineOutcome::Success(1));
+            assert_eq!(outcome(1, roll), RefineOutcome::Success(2));
+        }
+    }
+
+    #[test]
+    fn the_same_seed_replays_the_same_outcomes() {
+        let rolls = |seed| {
+            let mut rng = EventRng::from_seed(seed);
+            (0..MAX_REFINE)
+                .map(|level| outcome(level, rng.percent()))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(rolls(1234), rolls(1234));
+
+        let mut rng = EventRng::from_seed(1234);
+        let outcomes = rolls(1234);
+
+        for (level, result) in outcomes.into_iter().enumerate() {
+            assert_eq!(outcome(level as u8, rng.percent()), result);
+        }
+    }
+
+    #[test]
+    fn outcomes_change_the_item() {
+        let mut item = Item::new(800);
+        item.set_refine(8);
+
+        apply(&mut item, RefineOutcome::Fail);
+        assert_eq!(item.refine(), 8);
+
+        apply(&mut item, RefineOutcome::Success(9));
+        assert_eq!(item.refine(), 9);
+
+        apply(&mut item, RefineOutcome::Downgrade(8));
+        assert_eq!(item.refine(), 8);
+
+        apply(&mut item, RefineOutcome::Break);
+        assert!(item.is_empty());
+    }
+}
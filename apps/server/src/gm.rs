@@ -0,0 +1,423 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::{
+    consts::GM_COMMAND_PREFIX,
+    repository::accounts::GmLevel,
+    session::Session,
+    statics::{REPOSITORY, WORLD},
+    structs::{inventory::ItemError, position::Position},
+    world::data::{DataError, DataKind},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GmError {
+    Item(ItemError),
+    Data(DataError),
+    Unknown,
+    Usage(&'static str),
+    NoPermission,
+    NotFound,
+    InvalidPosition,
+    Failed,
+}
+
+impl Display for GmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GmError::Item(error) => write!(f, "{}", error),
+            GmError::Data(error) => write!(f, "{}", error),
+            GmError::Unknown => write!(f, "Comando desconhecido."),
+            GmError::Usage(usage) => write!(f, "Uso: {}", usage),
+            GmError::NoPermission => write!(f, "Você não tem permissão para isso."),
+            GmError::NotFound => write!(f, "Jogador não encontrado."),
+            GmError::InvalidPosition => write!(f, "Posição inválida."),
+            GmError::Failed => write!(f, "Não foi possível executar o comando."),
+        }
+    }
+}
+
+impl From<ItemError> for GmError {
+    fn from(error: ItemError) -> Self {
+        GmError::Item(error)
+    }
+}
+
+impl From<DataError> for GmError {
+    fn from(error: DataError) -> Self {
+        GmError::Data(error)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Destination {
+    Position(Position),
+    Player(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GmCommand {
+    Summon(String),
+    Teleport(Destination),
+    Item { index: i16, amount: u16 },
+    Kick(String),
+    Ban { name: String, hours: u64 },
+    Spawn { generator: usize, count: u16 },
+    Notice(String),
+    SetLevel { level: u16, name: Option<String> },
+    Reload(Vec<DataKind>),
+}
+
+impl GmCommand {
+    // a line is only a command when it starts with the prefix, anything else is plain chat
+
+    pub fn parse(line: &str) -> Option<Result<Self, GmError>> {
+        let line = line.trim().strip_prefix(GM_COMMAND_PREFIX)?;
+        let (name, rest) = line.split_once(' ').unwrap_or((line, ""));
+        let args = rest.split_whitespace().collect::<Vec<_>>();
+
+        Some(Self::parse_args(&name.to_lowercase(), rest.trim(), &args))
+    }
+
+    fn parse_args(name: &str, rest: &str, args: &[&str]) -> Result<Self, GmError> {
+        match (name, args) {
+            ("summon", [target]) => Ok(GmCommand::Summon(target.to_string())),
+            ("summon", _) => Err(GmError::Usage("/summon <nome>")),
+
+            ("teleport", [x, y]) => {
+                let usage = "/teleport <x> <y>";
+                let position = Position::new(number(x, usage)?, number(y, usage)?);
+                Ok(GmCommand::Teleport(Destination::Position(position)))
+            }
+            ("teleport", [target]) => {
+                Ok(GmCommand::Teleport(Destination::Player(target.to_string())))
+            }
+            ("teleport", _) => Err(GmError::Usage("/teleport <x> <y> ou /teleport <nome>")),
+
+            ("item", [index, amount @ ..]) if amount.len() <= 1 => {
+                let usage = "/item <índice> [quantidade]";
+                Ok(GmCommand::Item {
+                    index: number(index, usage)?,
+                    amount: amount
+                        .first()
+                        .map_or(Ok(1), |amount| number(amount, usage))?,
+                })
+            }
+            ("item", _) => Err(GmError::Usage("/item <índice> [quantidade]")),
+
+            ("kick", [target]) => Ok(GmCommand::Kick(target.to_string())),
+            ("kick", _) => Err(GmError::Usage("/kick <nome>")),
+
+            ("ban", [target, hours @ ..]) if hours.len() <= 1 => Ok(GmCommand::Ban {
+                name: target.to_string(),
+                hours: hours
+                    .first()
+                    .map_or(Ok(0), |hours| number(hours, "/ban <nome> [horas]"))?,
+            }),
+            ("ban", _) => Err(GmError::Usage("/ban <nome> [horas]")),
+
+            ("spawn", [generator, count @ ..]) if count.len() <= 1 => {
+                let usage = "/spawn <gerador> [quantidade]";
+                Ok(GmCommand::Spawn {
+                    generator: number(generator, usage)?,
+                    count: count.first().map_or(Ok(1), |count| number(count, usage))?,
+                })
+            }
+            ("spawn", _) => Err(GmError::Usage("/spawn <gerador> [quantidade]")),
+
+            ("notice", [_, ..]) => Ok(GmCommand::Notice(rest.to_string())),
+            ("notice", _) => Err(GmError::Usage("/notice <mensagem>")),
+
+            ("setlevel", [level, target @ ..]) if target.len() <= 1 => Ok(GmCommand::SetLevel {
+                level: number(level, "/setlevel <nível> [nome]")?,
+                name: target.first().map(|target| target.to_string()),
+            }),
+            ("setlevel", _) => Err(GmError::Usage("/setlevel <nível> [nome]")),
+
+            ("reload", kinds) => {
+                DataKind::parse_list(kinds)
+                    .map(GmCommand::Reload)
+                    .ok_or(GmError::Usage(
+                        "/reload [items|mobs|skills|recipes|scripts]",
+                    ))
+            }
+
+            _ => Err(GmError::Unknown),
+        }
+    }
+
+    pub fn level(&self) -> GmLevel {
+        match self {
+            GmCommand::Notice(_) | GmCommand::Kick(_) => GmLevel::Moderator,
+            GmCommand::Summon(_) | GmCommand::Teleport(_) | GmCommand::Ban { .. } => {
+                GmLevel::GameMaster
+            }
+            GmCommand::Item { .. }
+            | GmCommand::Spawn { .. }
+            | GmCommand::SetLevel { .. }
+            | GmCommand::Reload(_) => GmLevel::Admin,
+        }
+    }
+}
+
+fn number<T: FromStr>(arg: &str, usage: &'static str) -> Result<T, GmError> {
+    arg.parse::<T>().map_err(|_| GmError::Usage(usage))
+}
+
+// the audit log is one tab separated line per command, so tabs and line breaks typed into
+// a command can't be allowed to open new fields or entries
+
+fn audit_field(text: &str) -> String {
+    text.chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect()
+}
+
+// whatever comes out of a command, refused ones included, is answered to whoever typed it
+// and kept in the audit log
+
+pub async fn execute(session: &Session, line: &str) {
+    let id = *session.id;
+
+    let username = session.get_account().await.unwrap_or_default();
+    let level = REPOSITORY
+        .get_account(&username)
+        .await
+        .map(|account| account.gm_level)
+        .unwrap_or_default();
+    let name = WORLD
+        .get_player(id)
+        .await
+        .map(|player| player.character.name)
+        .unwrap_or_default();
+
+    let result = match GmCommand::parse(line) {
+        Some(Ok(command)) if level >= command.level() => run(id, &name, command).await,
+        Some(Ok(_)) => Err(GmError::NoPermission),
+        Some(Err(error)) => Err(error),
+        None => return,
+    };
+
+    let (status, reply) = match result {
+        Ok(reply) => ("ok", reply),
+        Err(error) => ("error", error.to_string()),
+    };
+
+    REPOSITORY
+        .audit(&format!(
+            "{}\t{}\t{}\t{:?}\t{}\t{}",
+            WORLD.clock.timestamp(),
+            audit_field(&username),
+            audit_field(&name),
+            level,
+            audit_field(line.trim()),
+            status
+        ))
+        .await;
+
+    session.send_message(&reply);
+}
+
+async fn run(id: u16, name: &str, command: GmCommand) -> Result<String, GmError> {
+    match command {
+        GmCommand::Summon(target) => match WORLD.summon(id, &target).await {
+            true => Ok(format!("{} foi trazido até você.", target)),
+            false => Err(GmError::NotFound),
+        },
+
+        GmCommand::Teleport(destination) => {
+            let position = match destination {
+                Destination::Position(position) => position,
+                Destination::Player(target) => {
+                    WORLD.position_of(&target).await.ok_or(GmError::NotFound)?
+                }
+            };
+
+            match WORLD.teleport(id, position).await {
+                true => Ok(format!("Teleportado para {} {}.", position.x, position.y)),
+                false => Err(GmError::InvalidPosition),
+            }
+        }
+
+        GmCommand::Item { index, amount } => {
+            WORLD.create_item(id, index, amount).await?;
+            Ok(format!("Item {} criado.", index))
+        }
+
+        GmCommand::Kick(target) => match WORLD.kick(&target).await {
+            true => Ok(format!("{} foi desconectado.", target)),
+            false => Err(GmError::NotFound),
+        },
+
+        GmCommand::Ban { name, hours } => match WORLD.ban(&name, hours).await {
+            true => Ok(format!("A conta de {} foi bloqueada.", name)),
+            false => Err(GmError::NotFound),
+        },
+
+        GmCommand::Spawn { generator, count } => match WORLD.spawn_at(id, generator, count).await {
+            0 => Err(GmError::Failed),
+            spawned => Ok(format!("{} monstro(s) criado(s).", spawned)),
+        },
+
+        GmCommand::Notice(message) => {
+            WORLD.notice(&format!("Aviso: {}", message)).await;
+            Ok("Aviso enviado.".to_string())
+        }
+
+        GmCommand::SetLevel {
+            level,
+            name: target,
+        } => {
+            let target = target.unwrap_or_else(|| name.to_string());
+
+            match WORLD.set_level(&target, level).await {
+                Some(level) => Ok(format!("{} agora está no nível {}.", target, level)),
+                None => Err(GmError::NotFound),
+            }
+        }
+
+        GmCommand::Reload(kinds) => {
+            WORLD.reload(&kinds).await?;
+
+            let names = kinds
+                .iter()
+                .map(|kind| kind.to_string())
+                .collect::<Vec<_>>();
+
+            Ok(format!("Dados recarregados: {}.", names.join(", ")))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> Result<GmCommand, GmError> {
+        GmCommand::parse(line).expect("not a command")
+    }
+
+    #[test]
+    fn plain_chat_is_not_a_command() {
+        assert_eq!(GmCommand::parse("hello /summon Bob"), None);
+    }
+
+    #[test]
+    fn optional_arguments_fall_back_to_their_defaults() {
+        assert_eq!(
+            parse("/item 400"),
+            Ok(GmCommand::Item {
+                index: 400,
+                amount: 1
+            })
+        );
+        assert_eq!(
+            parse("/item 400 20"),
+            Ok(GmCommand::Item {
+                index: 400,
+                amount: 20
+            })
+        );
+        assert_eq!(
+            parse("/ban Bob"),
+            Ok(GmCommand::Ban {
+                name: "Bob".to_string(),
+                hours: 0
+            })
+        );
+        assert_eq!(
+            parse("/ban Bob 12"),
+            Ok(GmCommand::Ban {
+                name: "Bob".to_string(),
+                hours: 12
+            })
+        );
+        assert_eq!(
+            parse("/setlevel 50"),
+            Ok(GmCommand::SetLevel {
+                level: 50,
+                name: None
+            })
+        );
+        assert_eq!(
+            parse("/setlevel 50 Bob"),
+            Ok(GmCommand::SetLevel {
+                level: 50,
+                name: Some("Bob".to_string())
+            })
+        );
+    }
+
+    #[test]
+    fn teleport_takes_a_position_or_a_player() {
+        assert_eq!(
+            parse("/teleport 2100 2100"),
+            Ok(GmCommand::Teleport(Destination::Position(Position::new(
+                2100, 2100
+            ))))
+        );
+        assert_eq!(
+            parse("/TELEPORT Bob"),
+            Ok(GmCommand::Teleport(Destination::Player("Bob".to_string())))
+        );
+    }
+
+    #[test]
+    fn a_notice_keeps_the_whole_message() {
+        assert_eq!(
+            parse("/notice server  restart soon"),
+            Ok(GmCommand::Notice("server  restart soon".to_string()))
+        );
+    }
+
+    #[test]
+    fn wrong_arguments_answer_with_the_usage() {
+        assert_eq!(parse("/summon"), Err(GmError::Usage("/summon <nome>")));
+        assert_eq!(
+            parse("/item potion"),
+            Err(GmError::Usage("/item <índice> [quantidade]"))
+        );
+        assert_eq!(
+            parse("/item 400 1 2"),
+            Err(GmError::Usage("/item <índice> [quantidade]"))
+        );
+        assert_eq!(
+            parse("/ban Bob forever"),
+            Err(GmError::Usage("/ban <nome> [horas]"))
+        );
+        assert_eq!(
+            parse("/teleport 1 2 3"),
+            Err(GmError::Usage("/teleport <x> <y> ou /teleport <nome>"))
+        );
+        assert_eq!(parse("/notice"), Err(GmError::Usage("/notice <mensagem>")));
+        assert_eq!(parse("/fly"), Err(GmError::Unknown));
+    }
+
+    #[test]
+    fn every_command_asks_for_its_level() {
+        let levels = [
+            ("/notice hi", GmLevel::Moderator),
+            ("/kick Bob", GmLevel::Moderator),
+            ("/summon Bob", GmLevel::GameMaster),
+            ("/teleport Bob", GmLevel::GameMaster),
+            ("/ban Bob", GmLevel::GameMaster),
+            ("/item 400", GmLevel::Admin),
+            ("/spawn 1", GmLevel::Admin),
+            ("/setlevel 10", GmLevel::Admin),
+            ("/reload", GmLevel::Admin),
+        ];
+
+        for (line, level) in levels {
+            assert_eq!(
+                parse(line).map(|command| command.level()),
+                Ok(level),
+                "{}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn audit_fields_stay_on_one_line() {
+        assert_eq!(audit_field("/notice a\tb\nc\r"), "/notice a b c ");
+    }
+}
@@ -0,0 +1,76 @@
+use packets::structs::packets::{p3c0::P3C0, p3c1::P3C1, p3c3::P3C3, p3c4::P3C4, p3c6::P3C6};
+
+use crate::{
+    handlers::items::slot,
+    repository::auctions::{AuctionError, Search},
+    session::{Session, SessionState},
+    statics::WORLD,
+    structs::inventory::SlotType,
+};
+
+pub async fn list(session: &Session, packet: P3C0) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    let result = match (
+        slot(SlotType::Inventory.index() as i32, packet.slot as i32),
+        u32::try_from(packet.price),
+    ) {
+        (Ok(slot), Ok(price)) => WORLD.list_auction(*session.id, slot, price).await,
+        (Err(error), _) => Err(error.into()),
+        (_, Err(_)) => Err(AuctionError::InvalidPrice),
+    };
+
+    if let Err(error) = result {
+        session.send_message(&error.to_string());
+    }
+}
+
+// zero on any of the fields leaves it out of the search
+
+pub async fn search(session: &Session, packet: P3C1) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    let search = Search {
+        item: (packet.item_index > 0).then_some(packet.item_index),
+        min_price: packet.min_price.max(0) as u32,
+        max_price: (packet.max_price > 0).then_some(packet.max_price as u32),
+    };
+
+    WORLD
+        .search_auctions(*session.id, search, packet.page)
+        .await;
+}
+
+pub async fn buy(session: &Session, packet: P3C3) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    if let Err(error) = WORLD.buy_auction(*session.id, packet.auction_id).await {
+        session.send_message(&error.to_string());
+    }
+}
+
+pub async fn cancel(session: &Session, packet: P3C4) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    if let Err(error) = WORLD.cancel_auction(*session.id, packet.auction_id).await {
+        session.send_message(&error.to_string());
+    }
+}
+
+pub async fn claim_mail(session: &Session, packet: P3C6) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    if let Err(error) = WORLD.claim_mail(*session.id, packet.mail_id).await {
+        session.send_message(&error.to_string());
+    }
+}
@@ -0,0 +1,155 @@
+use packets::structs::packets::{
+    p10a::P10A, p110::P110, p112::P112, p116::P116, p20f::P20F, p211::P211, p213::P213,
+};
+
+use crate::{
+    repository::{accounts::Account, characters::CharacterError},
+    security::password,
+    session::{Session, SessionState},
+    statics::{REPOSITORY, WORLD},
+    world::player::Player,
+};
+
+pub fn send_characters(session: &Session, account: &Account) {
+    session.send(&P10A::new(account.characters_struct(), &account.username));
+}
+
+pub async fn create_character(session: &Session, packet: P20F) {
+    if session.get_state().await != SessionState::CharacterList {
+        return;
+    }
+
+    let username = match session.get_account().await {
+        Some(username) => username,
+        None => return,
+    };
+
+    let slot = match usize::try_from(packet.slot) {
+        Ok(slot) => slot,
+        Err(_) => {
+            session.send_message(&CharacterError::InvalidSlot.to_string());
+            return;
+        }
+    };
+
+    match REPOSITORY
+        .create_character(&username, slot, &packet.get_name(), packet.class)
+        .await
+    {
+        Ok(account) => {
+            println!("Character {} created by {}", packet.get_name(), username);
+            session.send(&P110::new(account.characters_struct()));
+        }
+        Err(error) => session.send_message(&error.to_string()),
+    };
+}
+
+pub async fn delete_character(session: &Session, packet: P211) {
+    if session.get_state().await != SessionState::CharacterList {
+        return;
+    }
+
+    let account = match session.get_account().await {
+        Some(username) => match REPOSITORY.get_account(&username).await {
+            Some(account) => account,
+            None => return,
+        },
+        None => return,
+    };
+
+    let slot = match usize::try_from(packet.slot) {
+        Ok(slot) => slot,
+        Err(_) => {
+            session.send_message(&CharacterError::InvalidSlot.to_string());
+            return;
+        }
+    };
+
+    if !password::verify_async(packet.get_password(), account.password.clone()).await {
+        session.send_message("Senha incorreta.");
+        return;
+    }
+
+    match REPOSITORY
+        .delete_character(&account.username, slot, &packet.get_name())
+        .await
+    {
+        Ok(account) => {
+            println!(
+                "Character {} deleted by {}",
+                packet.get_name(),
+                account.username
+            );
+            session.send(&P112::new(account.characters_struct()));
+        }
+        Err(error) => session.send_message(&error.to_string()),
+    };
+}
+
+pub async fn character_login(session: &Session, packet: P213) {
+    if session.get_state().await != SessionState::CharacterList {
+        return;
+    }
+
+    let account = match session.get_account().await {
+        Some(username) => match REPOSITORY.get_account(&username).await {
+            Some(account) => account,
+            None => return,
+        },
+        None => return,
+    };
+
+    let slot = packet.slot as usize;
+
+    let character = match account.get_character(slot) {
+        Some(character) => character.clone(),
+        None => {
+            session.send_message(&CharacterError::NotFound.to_string());
+            return;
+        }
+    };
+
+    session.set_state(SessionState::World).await;
+
+    println!("Character {} entered the world", character.name);
+
+    let mut player = Player::new(
+        session.clone(),
+        account.username,
+        slot,
+        character,
+        account.storage,
+        WORLD.now(),
+    );
+    player.muted_until = account.muted_until;
+    player.gm_level = account.gm_level;
+
+    WORLD.enter(player).await;
+}
+
+pub async fn character_logout(session: &Session) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    leave_world(session).await;
+
+    session.set_state(SessionState::CharacterList).await;
+    session.send(&P116::new());
+}
+
+pub async fn leave_world(session: &Session) {
+    if let Some(player) = WORLD.leave(*session.id).await {
+        println!("Character {} left the world", player.character.name);
+
+        if !REPOSITORY
+            .save_character(&player.username, player.slot, player.character)
+            .await
+        {
+            println!(
+                "characters.leave_world.error: {} not saved",
+                player.username
+            );
+        }
+    }
+}
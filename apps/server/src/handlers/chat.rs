@@ -0,0 +1,55 @@
+use packets::structs::packets::{p333::P333, p334::P334};
+
+use crate::{
+    consts::{GM_COMMAND_PREFIX, GUILD_CHAT_TARGET, PARTY_CHAT_TARGET, SHOUT_CHAT_TARGET},
+    gm,
+    repository::accounts::GmLevel,
+    session::{Session, SessionState},
+    statics::WORLD,
+};
+
+// only staff lines starting with the command prefix go to the gm parser, for everyone
+// else they are plain chat and go through the mute and the rate limit
+
+pub async fn say(session: &Session, packet: P333) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    let message = packet.get_message();
+
+    if message.trim_start().starts_with(GM_COMMAND_PREFIX)
+        && WORLD.gm_level(*session.id).await > GmLevel::Player
+    {
+        gm::execute(session, &message).await;
+        return;
+    }
+
+    if let Err(error) = WORLD.say(*session.id, &message).await {
+        session.send_message(&error.to_string());
+    }
+}
+
+// the name a whisper is addressed to picks the channel, anything that is not one of the
+// channel targets is a character somewhere on the server
+
+pub async fn whisper(session: &Session, packet: P334) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    let id = *session.id;
+    let name = packet.get_name();
+    let message = packet.get_message();
+
+    let result = match name.trim() {
+        PARTY_CHAT_TARGET => WORLD.party_chat(id, &message).await,
+        GUILD_CHAT_TARGET => WORLD.guild_chat(id, &message).await,
+        SHOUT_CHAT_TARGET => WORLD.shout(id, &message).await,
+        name => WORLD.whisper(id, name, &message).await,
+    };
+
+    if let Err(error) = result {
+        session.send_message(&error.to_string());
+    }
+}
@@ -0,0 +1,54 @@
+use packets::structs::packets::p39d::P39D;
+
+use crate::{
+    combat::Attack,
+    consts::PLAYER_ATTACK_RANGE,
+    session::{Session, SessionState},
+    statics::WORLD,
+};
+
+// the client only says who it is hitting, everything else is decided here
+
+pub async fn attack(session: &Session, packet: P39D) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    if packet.skill_index >= 0 {
+        let result = WORLD
+            .cast(*session.id, packet.target_id, packet.skill_index as u16)
+            .await;
+
+        if let Err(error) = result {
+            if cfg!(debug_assertions) {
+                println!("combat.cast: {} rejected, {}", session.id, error);
+            }
+        }
+
+        return;
+    }
+
+    let result = WORLD
+        .attack(
+            *session.id,
+            packet.target_id,
+            Attack::basic(),
+            PLAYER_ATTACK_RANGE,
+            -1,
+        )
+        .await;
+
+    if let Err(error) = result {
+        if cfg!(debug_assertions) {
+            println!("combat.attack: {} rejected, {}", session.id, error);
+        }
+    }
+}
+
+pub async fn restart(session: &Session) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    WORLD.restart(*session.id).await;
+}
@@ -0,0 +1,57 @@
+use packets::structs::packets::{p373::P373, p3a6::P3A6};
+
+use crate::{
+    handlers::items::slot,
+    session::{Session, SessionState},
+    statics::WORLD,
+    structs::inventory::{ItemError, SlotType},
+};
+
+pub async fn refine_item(session: &Session, packet: P373) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    let result = match (
+        slot(packet.src_type, packet.src_slot),
+        slot(packet.dest_type, packet.dest_slot),
+    ) {
+        (Ok(material), Ok(target)) => WORLD
+            .refine_item(*session.id, material, target)
+            .await
+            .map(|outcome| outcome.to_string()),
+        (Err(error), _) | (_, Err(error)) => Err(error.into()),
+    };
+
+    match result {
+        Ok(message) => session.send_message(&message),
+        Err(error) => session.send_message(&error.to_string()),
+    }
+}
+
+// the client sends copies of the items too, only the slots are trusted
+
+pub async fn compose(session: &Session, packet: P3A6) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    let slots: Result<Vec<_>, ItemError> = packet
+        .slots
+        .iter()
+        .filter(|index| **index >= 0)
+        .map(|index| slot(SlotType::Inventory.index() as i32, *index as i32))
+        .collect();
+
+    let result = match slots {
+        Ok(slots) if !slots.is_empty() => WORLD.compose(*session.id, &slots).await,
+        Ok(_) => Err(ItemError::Empty.into()),
+        Err(error) => Err(error.into()),
+    };
+
+    match result {
+        Ok(true) => session.send_message("Combinação bem sucedida."),
+        Ok(false) => session.send_message("A combinação falhou."),
+        Err(error) => session.send_message(&error.to_string()),
+    }
+}
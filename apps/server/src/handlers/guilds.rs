@@ -0,0 +1,161 @@
+use packets::structs::packets::{
+    p3c8::P3C8,
+    p3ca::P3CA,
+    p3cb::P3CB,
+    p3cc::{GUILD_ACTION_KICK, GUILD_ACTION_LEAVE, GUILD_ACTION_RANK, P3CC},
+    p3cd::P3CD,
+    p3cf::{GUILD_STORAGE_DEPOSIT, GUILD_STORAGE_OPEN, GUILD_STORAGE_WITHDRAW, P3CF},
+    p3d0::P3D0,
+    p3d1::P3D1,
+    p3d2::P3D2,
+    p3d3::P3D3,
+};
+
+use crate::{
+    handlers::items::slot,
+    repository::guilds::{GuildError, GuildRank},
+    session::{Session, SessionState},
+    statics::WORLD,
+    structs::inventory::{ItemError, SlotType},
+};
+
+pub async fn create(session: &Session, packet: P3C8) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    if let Err(error) = WORLD.create_guild(*session.id, &packet.get_name()).await {
+        session.send_message(&error.to_string());
+    }
+}
+
+pub async fn invite(session: &Session, packet: P3CA) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    if let Err(error) = WORLD.invite_guild(*session.id, packet.target_id).await {
+        session.send_message(&error.to_string());
+    }
+}
+
+pub async fn accept(session: &Session, packet: P3CB) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    if let Err(error) = WORLD.accept_guild(*session.id, packet.guild_id).await {
+        session.send_message(&error.to_string());
+    }
+}
+
+pub async fn member(session: &Session, packet: P3CC) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    let id = *session.id;
+    let name = packet.get_name();
+
+    let result = match packet.action {
+        GUILD_ACTION_LEAVE => WORLD.leave_guild(id).await,
+        GUILD_ACTION_KICK => WORLD.kick_from_guild(id, &name).await,
+        GUILD_ACTION_RANK => match GuildRank::from_index(packet.rank) {
+            Some(rank) => WORLD.set_guild_rank(id, &name, rank).await,
+            None => Err(GuildError::NoPermission),
+        },
+        _ => return,
+    };
+
+    if let Err(error) = result {
+        session.send_message(&error.to_string());
+    }
+}
+
+pub async fn notice(session: &Session, packet: P3CD) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    if let Err(error) = WORLD
+        .set_guild_notice(*session.id, &packet.get_notice())
+        .await
+    {
+        session.send_message(&error.to_string());
+    }
+}
+
+// deposits come from the bags, withdrawals name the slot in the guild storage
+
+pub async fn storage(session: &Session, packet: P3CF) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    let id = *session.id;
+
+    let result = match packet.action {
+        GUILD_STORAGE_OPEN => WORLD.open_guild_storage(id).await,
+        GUILD_STORAGE_DEPOSIT => {
+            match slot(SlotType::Inventory.index() as i32, packet.slot as i32) {
+                Ok(slot) => WORLD.deposit_guild(id, slot).await,
+                Err(error) => Err(error.into()),
+            }
+        }
+        GUILD_STORAGE_WITHDRAW => match usize::try_from(packet.slot) {
+            Ok(stored) => WORLD.withdraw_guild(id, stored).await,
+            Err(_) => Err(ItemError::InvalidSlot.into()),
+        },
+        _ => return,
+    };
+
+    if let Err(error) = result {
+        session.send_message(&error.to_string());
+    }
+}
+
+pub async fn upload_mark(session: &Session, packet: P3D0) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    if let Err(error) = WORLD.set_guild_mark(*session.id, &packet.mark).await {
+        session.send_message(&error.to_string());
+    }
+}
+
+pub async fn request_mark(session: &Session, packet: P3D1) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    if let Err(error) = WORLD.send_guild_mark(*session.id, packet.guild_id).await {
+        session.send_message(&error.to_string());
+    }
+}
+
+pub async fn war(session: &Session, packet: P3D2) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    if let Err(error) = WORLD
+        .set_guild_war(*session.id, packet.guild_id, packet.declare != 0)
+        .await
+    {
+        session.send_message(&error.to_string());
+    }
+}
+
+pub async fn register_event(session: &Session, packet: P3D3) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    if let Err(error) = WORLD
+        .register_event(*session.id, packet.event as usize)
+        .await
+    {
+        session.send_message(&error.to_string());
+    }
+}
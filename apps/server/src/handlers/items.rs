@@ -0,0 +1,108 @@
+use packets::structs::packets::{p270::P270, p272::P272, p2e5::P2E5, p373::P373, p376::P376};
+
+use crate::{
+    handlers::crafting,
+    session::{Session, SessionState},
+    statics::WORLD,
+    structs::{
+        inventory::{ItemError, Slot, SlotType},
+        position::Position,
+    },
+};
+
+pub fn slot(kind: i32, index: i32) -> Result<Slot, ItemError> {
+    let kind = SlotType::from_index(kind).ok_or(ItemError::InvalidSlot)?;
+    let index = usize::try_from(index).map_err(|_| ItemError::InvalidSlot)?;
+
+    if index >= kind.slots() {
+        return Err(ItemError::InvalidSlot);
+    }
+
+    Ok(Slot::new(kind, index))
+}
+
+pub async fn move_item(session: &Session, packet: P376) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    let result = match (
+        slot(packet.src_type as i32, packet.src_slot as i32),
+        slot(packet.dest_type as i32, packet.dest_slot as i32),
+    ) {
+        (Ok(from), Ok(to)) => WORLD.move_item(*session.id, from, to).await,
+        _ => Err(ItemError::InvalidSlot),
+    };
+
+    if let Err(error) = result {
+        session.send_message(&error.to_string());
+    }
+}
+
+pub async fn split_item(session: &Session, packet: P2E5) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    let amount = u16::try_from(packet.amount).map_err(|_| ItemError::InvalidAmount);
+
+    let result = match (
+        slot(SlotType::Inventory.index() as i32, packet.slot),
+        amount,
+    ) {
+        (Ok(from), Ok(amount)) => WORLD.split_item(*session.id, from, amount).await,
+        (Err(error), _) | (_, Err(error)) => Err(error),
+    };
+
+    if let Err(error) = result {
+        session.send_message(&error.to_string());
+    }
+}
+
+pub async fn drop_item(session: &Session, packet: P272) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    let position = Position::new(packet.position.x, packet.position.y);
+
+    let result = match slot(packet.src_type, packet.src_slot) {
+        Ok(from) => WORLD.drop_item(*session.id, from, position).await,
+        Err(error) => Err(error),
+    };
+
+    if let Err(error) = result {
+        session.send_message(&error.to_string());
+    }
+}
+
+pub async fn pickup_item(session: &Session, packet: P270) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    if let Err(error) = WORLD.pickup_item(*session.id, packet.item_id).await {
+        session.send_message(&error.to_string());
+    }
+}
+
+// items with a script are used through it, anything else is taken as a refine
+
+pub async fn use_item(session: &Session, packet: P373) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    if let Ok(source) = slot(packet.src_type, packet.src_slot) {
+        match WORLD.use_item(*session.id, source).await {
+            Ok(true) => return,
+            Ok(false) => {}
+            Err(error) => {
+                session.send_message(&error.to_string());
+                return;
+            }
+        }
+    }
+
+    crafting::refine_item(session, packet).await
+}
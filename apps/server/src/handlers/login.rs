@@ -0,0 +1,73 @@
+use packets::structs::packets::p20d::P20D;
+
+use crate::{
+    handlers::characters,
+    repository::accounts::{normalize_username, AccountError},
+    security::password,
+    session::{Session, SessionState},
+    statics::{CONFIG, REPOSITORY, SESSIONS, WORLD},
+};
+
+pub async fn account_login(session: &Session, packet: P20D) {
+    if session.get_state().await != SessionState::Login {
+        return;
+    }
+
+    let username = normalize_username(&packet.get_username());
+    let password = packet.get_password();
+
+    let account = match REPOSITORY.get_account(&username).await {
+        Some(account) => {
+            if !password::verify_async(password, account.password.clone()).await {
+                session.send_message("Senha incorreta.");
+                return;
+            }
+
+            account
+        }
+
+        None if CONFIG.auto_register => {
+            match REPOSITORY.create_account(&username, &password).await {
+                Ok(account) => {
+                    println!(
+                        "Account {} registered from {}",
+                        account.username, session.ip
+                    );
+                    account
+                }
+                Err(error) => {
+                    session.send_message(&error.to_string());
+                    return;
+                }
+            }
+        }
+
+        None => {
+            session.send_message(&AccountError::NotFound.to_string());
+            return;
+        }
+    };
+
+    if account.banned_until > WORLD.clock.timestamp() {
+        session.send_message("Esta conta está bloqueada.");
+        return;
+    }
+
+    if !SESSIONS.login(session, &account.username).await {
+        // the older session is dropped so the owner can get back in
+
+        if let Some(other) = SESSIONS.find_by_account(&account.username).await {
+            other.send_message("Conta conectada em outro local.");
+            other.close().await;
+        }
+
+        session.send_message("Conta já conectada, tente novamente.");
+        return;
+    }
+
+    session.set_state(SessionState::CharacterList).await;
+
+    characters::send_characters(session, &account);
+
+    println!("Account {} connected from {}", account.username, session.ip);
+}
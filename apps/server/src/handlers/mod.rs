@@ -0,0 +1,330 @@
+use packets::{serializer::deserialize, structs::header::SHeader};
+
+use crate::{consts::HEADER_SIZE, session::Session};
+
+pub mod auctions;
+pub mod characters;
+pub mod chat;
+pub mod combat;
+pub mod crafting;
+pub mod guilds;
+pub mod items;
+pub mod login;
+pub mod movement;
+pub mod npcs;
+pub mod parties;
+pub mod progression;
+pub mod shops;
+pub mod trades;
+pub mod vendors;
+
+pub async fn handle(session: &Session, buf: Vec<u8>) {
+    let header = match buf.get(0..HEADER_SIZE).and_then(deserialize::<SHeader>) {
+        Some(header) => header,
+        None => return,
+    };
+
+    match header.packet_id {
+        0x20D => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                login::account_login(session, packet).await
+            }
+        }
+
+        0x20F => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                characters::create_character(session, packet).await
+            }
+        }
+
+        0x211 => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                characters::delete_character(session, packet).await
+            }
+        }
+
+        0x213 => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                characters::character_login(session, packet).await
+            }
+        }
+
+        0x215 => characters::character_logout(session).await,
+
+        0x270 => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                items::pickup_item(session, packet).await
+            }
+        }
+
+        0x272 => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                items::drop_item(session, packet).await
+            }
+        }
+
+        0x277 => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                progression::apply_bonus(session, packet).await
+            }
+        }
+
+        0x27B => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                shops::open_shop(session, packet).await
+            }
+        }
+
+        0x289 => combat::restart(session).await,
+
+        0x28B => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                npcs::talk(session, packet).await
+            }
+        }
+
+        0x2E5 => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                items::split_item(session, packet).await
+            }
+        }
+
+        0x333 => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                chat::say(session, packet).await
+            }
+        }
+
+        0x334 => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                chat::whisper(session, packet).await
+            }
+        }
+
+        0x36C => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                movement::move_player(session, packet).await
+            }
+        }
+
+        0x373 => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                items::use_item(session, packet).await
+            }
+        }
+
+        0x376 => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                items::move_item(session, packet).await
+            }
+        }
+
+        0x379 => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                shops::buy(session, packet).await
+            }
+        }
+
+        0x37A => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                shops::sell(session, packet).await
+            }
+        }
+
+        0x37E => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                parties::remove(session, packet).await
+            }
+        }
+
+        0x37F => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                parties::invite(session, packet).await
+            }
+        }
+
+        0x383 => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                trades::trade(session, packet).await
+            }
+        }
+
+        0x384 => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                trades::cancel(session, packet).await
+            }
+        }
+
+        0x397 => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                vendors::open_vendor(session, packet).await
+            }
+        }
+
+        0x398 => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                vendors::buy(session, packet).await
+            }
+        }
+
+        0x39A => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                vendors::view_vendor(session, packet).await
+            }
+        }
+
+        0x39D => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                combat::attack(session, packet).await
+            }
+        }
+
+        0x3A6 => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                crafting::compose(session, packet).await
+            }
+        }
+
+        0x3AB => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                parties::accept(session, packet).await
+            }
+        }
+
+        0x3C0 => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                auctions::list(session, packet).await
+            }
+        }
+
+        0x3C1 => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                auctions::search(session, packet).await
+            }
+        }
+
+        0x3C3 => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                auctions::buy(session, packet).await
+            }
+        }
+
+        0x3C4 => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                auctions::cancel(session, packet).await
+            }
+        }
+
+        0x3C6 => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                auctions::claim_mail(session, packet).await
+            }
+        }
+
+        0x3C7 => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                parties::transfer(session, packet).await
+            }
+        }
+
+        0x3C8 => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                guilds::create(session, packet).await
+            }
+        }
+
+        0x3CA => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                guilds::invite(session, packet).await
+            }
+        }
+
+        0x3CB => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                guilds::accept(session, packet).await
+            }
+        }
+
+        0x3CC => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                guilds::member(session, packet).await
+            }
+        }
+
+        0x3CD => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                guilds::notice(session, packet).await
+            }
+        }
+
+        0x3CF => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                guilds::storage(session, packet).await
+            }
+        }
+
+        0x3D0 => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                guilds::upload_mark(session, packet).await
+            }
+        }
+
+        0x3D1 => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                guilds::request_mark(session, packet).await
+            }
+        }
+
+        0x3D2 => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                guilds::war(session, packet).await
+            }
+        }
+
+        0x3D3 => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                guilds::register_event(session, packet).await
+            }
+        }
+
+        0x3D4 => {
+            if let Some(packet) = parse(session, &header, &buf).await {
+                parties::set_loot(session, packet).await
+            }
+        }
+
+        _ => unknown_packet(session, &header),
+    }
+}
+
+pub async fn disconnect(session: &Session) {
+    characters::leave_world(session).await;
+
+    if let Some(username) = session.get_account().await {
+        println!("Account {} disconnected from {}", username, session.ip);
+    }
+}
+
+async fn parse<T: Sized>(session: &Session, header: &SHeader, buf: &[u8]) -> Option<T> {
+    let packet = deserialize::<T>(buf);
+
+    if packet.is_none() {
+        println!(
+            "handlers.parse.error: 0x{:03X} with {} bytes from {}",
+            header.packet_id, header.size, session.ip
+        );
+
+        session.close().await;
+    }
+
+    packet
+}
+
+fn unknown_packet(session: &Session, header: &SHeader) {
+    if cfg!(debug_assertions) {
+        println!(
+            "handlers.unknown_packet: 0x{:03X} with {} bytes from {}",
+            header.packet_id, header.size, session.ip
+        );
+    }
+}
@@ -0,0 +1,18 @@
+use packets::structs::packets::p36c::P36C;
+
+use crate::{
+    session::{Session, SessionState},
+    statics::WORLD,
+};
+
+pub async fn move_player(session: &Session, packet: P36C) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    if let Err(error) = WORLD.move_player(*session.id, packet).await {
+        if cfg!(debug_assertions) {
+            println!("movement.move_player: {} rejected, {}", session.id, error);
+        }
+    }
+}
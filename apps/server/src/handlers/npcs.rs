@@ -0,0 +1,16 @@
+use packets::structs::packets::p28b::P28B;
+
+use crate::{
+    session::{Session, SessionState},
+    statics::WORLD,
+};
+
+pub async fn talk(session: &Session, packet: P28B) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    if let Err(error) = WORLD.talk(*session.id, packet.target_id).await {
+        session.send_message(&error.to_string());
+    }
+}
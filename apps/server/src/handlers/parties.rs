@@ -0,0 +1,56 @@
+use packets::structs::packets::{p37e::P37E, p37f::P37F, p3ab::P3AB, p3c7::P3C7, p3d4::P3D4};
+
+use crate::{
+    session::{Session, SessionState},
+    statics::WORLD,
+};
+
+pub async fn invite(session: &Session, packet: P37F) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    if let Err(error) = WORLD.invite_party(*session.id, packet.target_id).await {
+        session.send_message(&error.to_string());
+    }
+}
+
+pub async fn accept(session: &Session, packet: P3AB) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    if let Err(error) = WORLD.accept_party(*session.id, packet.leader_id).await {
+        session.send_message(&error.to_string());
+    }
+}
+
+pub async fn remove(session: &Session, packet: P37E) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    if let Err(error) = WORLD.remove_from_party(*session.id, packet.member_id).await {
+        session.send_message(&error.to_string());
+    }
+}
+
+pub async fn transfer(session: &Session, packet: P3C7) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    if let Err(error) = WORLD.transfer_party(*session.id, packet.target_id).await {
+        session.send_message(&error.to_string());
+    }
+}
+
+pub async fn set_loot(session: &Session, packet: P3D4) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    if let Err(error) = WORLD.set_party_loot(*session.id, packet.rule).await {
+        session.send_message(&error.to_string());
+    }
+}
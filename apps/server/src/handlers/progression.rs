@@ -0,0 +1,19 @@
+use packets::structs::packets::p277::P277;
+
+use crate::{
+    session::{Session, SessionState},
+    statics::WORLD,
+};
+
+pub async fn apply_bonus(session: &Session, packet: P277) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    if let Err(error) = WORLD
+        .apply_bonus(*session.id, packet.bonus_type, packet.detail)
+        .await
+    {
+        session.send_message(&error.to_string());
+    }
+}
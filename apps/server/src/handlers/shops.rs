@@ -0,0 +1,45 @@
+use packets::structs::packets::{p27b::P27B, p379::P379, p37a::P37A};
+
+use crate::{
+    handlers::items::slot,
+    session::{Session, SessionState},
+    statics::WORLD,
+};
+
+pub async fn open_shop(session: &Session, packet: P27B) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    if let Err(error) = WORLD.open_shop(*session.id, packet.target_id).await {
+        session.send_message(&error.to_string());
+    }
+}
+
+pub async fn buy(session: &Session, packet: P379) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    if let Err(error) = WORLD
+        .buy(*session.id, packet.target_id, packet.shop_slot as usize)
+        .await
+    {
+        session.send_message(&error.to_string());
+    }
+}
+
+pub async fn sell(session: &Session, packet: P37A) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    let result = match slot(packet.slot_type as i32, packet.slot as i32) {
+        Ok(slot) => WORLD.sell(*session.id, packet.target_id, slot).await,
+        Err(error) => Err(error.into()),
+    };
+
+    if let Err(error) = result {
+        session.send_message(&error.to_string());
+    }
+}
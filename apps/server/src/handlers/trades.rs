@@ -0,0 +1,69 @@
+use packets::structs::packets::{
+    p383::{P383, TRADE_CONFIRM, TRADE_LOCK, TRADE_OFFER},
+    p384::P384,
+};
+
+use crate::{
+    handlers::items::slot,
+    session::{Session, SessionState},
+    statics::WORLD,
+    structs::inventory::SlotType,
+    world::trade::{Offer, TradeAction, TradeError},
+};
+
+// the client sends copies of the items it offers, only the slots are trusted
+
+fn offer(packet: &P383) -> Result<Offer, TradeError> {
+    let slots = packet
+        .slots
+        .iter()
+        .filter(|index| **index >= 0)
+        .map(|index| slot(SlotType::Inventory.index() as i32, *index as i32))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let coin = u32::try_from(packet.coin).map_err(|_| TradeError::InvalidOffer)?;
+
+    Ok(Offer {
+        slots,
+        coin,
+        ..Default::default()
+    })
+}
+
+pub async fn trade(session: &Session, packet: P383) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    let action = match packet.state {
+        TRADE_OFFER => Some(TradeAction::Offer),
+        TRADE_LOCK => Some(TradeAction::Lock),
+        TRADE_CONFIRM => Some(TradeAction::Confirm),
+        _ => None,
+    };
+
+    let result = match (offer(&packet), action) {
+        (Ok(offer), Some(action)) => {
+            WORLD
+                .trade(*session.id, packet.target_id, offer, action)
+                .await
+        }
+        (Err(error), _) => Err(error),
+        (_, None) => Err(TradeError::InvalidOffer),
+    };
+
+    if let Err(error) = result {
+        session.send_message(&error.to_string());
+    }
+}
+
+pub async fn cancel(session: &Session, _packet: P384) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    // the same packet closes a personal shop
+
+    WORLD.cancel_trade(*session.id).await;
+    WORLD.close_vendor(*session.id).await;
+}
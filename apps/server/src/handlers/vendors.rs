@@ -0,0 +1,78 @@
+use packets::structs::packets::{p397::P397, p398::P398, p39a::P39A};
+
+use crate::{
+    handlers::items::slot,
+    session::{Session, SessionState},
+    statics::WORLD,
+    structs::inventory::{Slot, SlotType},
+    world::vendor::VendorError,
+};
+
+// the client sends copies of the items it lists, only the slots and prices are trusted
+
+fn entries(packet: &P397) -> Result<Vec<(usize, Slot, u32)>, VendorError> {
+    packet
+        .slots
+        .iter()
+        .enumerate()
+        .filter(|(_, index)| **index >= 0)
+        .map(|(position, index)| {
+            let slot = slot(SlotType::Inventory.index() as i32, *index as i32)?;
+            let price =
+                u32::try_from(packet.prices[position]).map_err(|_| VendorError::InvalidListing)?;
+
+            Ok((position, slot, price))
+        })
+        .collect()
+}
+
+pub async fn open_vendor(session: &Session, packet: P397) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    let result = match entries(&packet) {
+        Ok(entries) => {
+            WORLD
+                .open_vendor(*session.id, &packet.get_title(), &entries)
+                .await
+        }
+        Err(error) => Err(error),
+    };
+
+    if let Err(error) = result {
+        session.send_message(&error.to_string());
+    }
+}
+
+pub async fn view_vendor(session: &Session, packet: P39A) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    if let Err(error) = WORLD.view_vendor(*session.id, packet.target_id).await {
+        session.send_message(&error.to_string());
+    }
+}
+
+pub async fn buy(session: &Session, packet: P398) {
+    if session.get_state().await != SessionState::World {
+        return;
+    }
+
+    let result = match (
+        usize::try_from(packet.vendor_slot),
+        u32::try_from(packet.price),
+    ) {
+        (Ok(position), Ok(price)) => {
+            WORLD
+                .buy_from_vendor(*session.id, packet.target_id, position, price)
+                .await
+        }
+        _ => Err(VendorError::Closed),
+    };
+
+    if let Err(error) = result {
+        session.send_message(&error.to_string());
+    }
+}
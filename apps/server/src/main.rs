@@ -1,4 +1,54 @@
+use once_cell::sync::Lazy;
+use tokio::time::interval;
+
+use crate::{
+    clock::Ticker,
+    consts::{MAX_CATCH_UP_TICKS, TICK_INTERVAL},
+    statics::WORLD,
+};
+
+pub mod clock;
+pub mod combat;
+pub mod commands;
+pub mod config;
+pub mod connection;
+pub mod consts;
+pub mod crafting;
+pub mod gm;
+pub mod handlers;
+pub mod repository;
+pub mod scripting;
+pub mod security;
+pub mod session;
+pub mod statics;
+pub mod structs;
+pub mod world;
+
 #[tokio::main]
 async fn main() {
-    println!("Future game server.")
+    println!("W2.Rust GameServer");
+
+    statics::REPOSITORY.load().await;
+    Lazy::force(&WORLD);
+    WORLD.load_guild_wars().await;
+    WORLD.load_castles().await;
+
+    tokio::spawn(commands::listen());
+    tokio::spawn(commands::data::watch());
+    tokio::spawn(tick());
+
+    connection::listen().await;
+}
+
+async fn tick() {
+    let mut interval = interval(TICK_INTERVAL);
+    let mut ticker = Ticker::new(TICK_INTERVAL, MAX_CATCH_UP_TICKS, WORLD.now());
+
+    loop {
+        interval.tick().await;
+
+        for now in ticker.due(WORLD.now()) {
+            WORLD.tick(now).await;
+        }
+    }
 }
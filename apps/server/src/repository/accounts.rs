@@ -0,0 +1,139 @@
+use packets::structs::characters::SCharacters;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+use crate::{
+    consts::{
+        CHARACTERS_PER_ACCOUNT, EQUIP_SLOTS, PASSWORD_MAX_LEN, PASSWORD_MIN_LEN, USERNAME_MAX_LEN,
+        USERNAME_MIN_LEN,
+    },
+    structs::{character::Character, mail::Mail, storage::Storage},
+};
+
+// what an account may do through gm commands, each level can do everything below it
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum GmLevel {
+    #[default]
+    Player,
+    Moderator,
+    GameMaster,
+    Admin,
+}
+
+impl GmLevel {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "player" => Some(GmLevel::Player),
+            "moderator" => Some(GmLevel::Moderator),
+            "gamemaster" | "gm" => Some(GmLevel::GameMaster),
+            "admin" => Some(GmLevel::Admin),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Account {
+    pub username: String,
+    pub password: String,
+    #[serde(default)]
+    pub characters: [Option<Character>; CHARACTERS_PER_ACCOUNT],
+    #[serde(default)]
+    pub storage: Storage,
+    #[serde(default)]
+    pub mail: Vec<Mail>,
+    #[serde(default)]
+    pub next_mail_id: u32,
+    #[serde(default)]
+    pub muted_until: u64,
+    #[serde(default)]
+    pub banned_until: u64,
+    #[serde(default)]
+    pub gm_level: GmLevel,
+}
+
+impl Account {
+    pub fn new(username: String, password: String) -> Self {
+        Self {
+            username,
+            password,
+            characters: Default::default(),
+            storage: Storage::default(),
+            mail: Vec::new(),
+            next_mail_id: 0,
+            muted_until: 0,
+            banned_until: 0,
+            gm_level: GmLevel::Player,
+        }
+    }
+
+    pub fn deliver(&mut self, mut mail: Mail) {
+        self.next_mail_id = self.next_mail_id.wrapping_add(1).max(1);
+        mail.id = self.next_mail_id;
+        self.mail.push(mail);
+    }
+
+    pub fn get_character(&self, slot: usize) -> Option<&Character> {
+        self.characters.get(slot)?.as_ref()
+    }
+
+    pub fn characters_struct(&self) -> SCharacters {
+        let mut characters = SCharacters::new();
+
+        for (slot, character) in self.characters.iter().enumerate() {
+            if let Some(character) = character {
+                let score = character.score();
+
+                characters.positions[slot] = character.position.to_struct();
+                characters.set_name(slot, &character.name);
+                characters.scores[slot] = score;
+                characters.coins[slot] = character.coin as i32;
+                characters.exps[slot] = character.exp as i64;
+
+                for (index, item) in character.equip.iter().take(EQUIP_SLOTS).enumerate() {
+                    characters.equips[slot][index] = item.to_struct();
+                }
+            }
+        }
+
+        characters
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountError {
+    InvalidUsername,
+    InvalidPassword,
+    AlreadyExists,
+    NotFound,
+    Hashing,
+    Storage,
+}
+
+impl Display for AccountError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AccountError::InvalidUsername => write!(f, "Usuário inválido."),
+            AccountError::InvalidPassword => write!(f, "Senha inválida."),
+            AccountError::AlreadyExists => write!(f, "Conta já existente."),
+            AccountError::NotFound => write!(f, "Conta não encontrada."),
+            AccountError::Hashing => write!(f, "Falha ao proteger a senha."),
+            AccountError::Storage => write!(f, "Falha ao salvar a conta."),
+        }
+    }
+}
+
+pub fn normalize_username(username: &str) -> String {
+    username.trim().to_lowercase()
+}
+
+pub fn is_valid_username(username: &str) -> bool {
+    (USERNAME_MIN_LEN..=USERNAME_MAX_LEN).contains(&username.len())
+        && username.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+pub fn is_valid_password(password: &str) -> bool {
+    (PASSWORD_MIN_LEN..=PASSWORD_MAX_LEN).contains(&password.len())
+        && password.chars().all(|c| c.is_ascii_graphic())
+}
@@ -0,0 +1,165 @@
+use packets::structs::auction::SAuction;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+use crate::{
+    consts::AUCTION_FEE_PERCENT,
+    structs::{inventory::ItemError, item::Item},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuctionError {
+    Item(ItemError),
+    NotFound,
+    InvalidPrice,
+    TooMany,
+    OwnAuction,
+    NotSeller,
+    NotEnoughCoin,
+    Storage,
+}
+
+impl Display for AuctionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AuctionError::Item(error) => write!(f, "{}", error),
+            AuctionError::NotFound => write!(f, "Este item não está mais no leilão."),
+            AuctionError::InvalidPrice => write!(f, "Preço inválido."),
+            AuctionError::TooMany => write!(f, "Você já tem itens demais no leilão."),
+            AuctionError::OwnAuction => write!(f, "Você não pode comprar o seu próprio item."),
+            AuctionError::NotSeller => write!(f, "Este item não é seu."),
+            AuctionError::NotEnoughCoin => write!(f, "Gold insuficiente."),
+            AuctionError::Storage => write!(f, "Falha ao salvar o leilão."),
+        }
+    }
+}
+
+impl From<ItemError> for AuctionError {
+    fn from(error: ItemError) -> Self {
+        AuctionError::Item(error)
+    }
+}
+
+// the seller is kept by account, whatever comes back from the auction goes to its mailbox
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Auction {
+    pub id: u32,
+    pub seller: String,
+    pub seller_name: String,
+    pub item: Item,
+    pub price: u32,
+    pub expires_at: u64,
+}
+
+impl Auction {
+    pub fn to_struct(&self, now: u64) -> SAuction {
+        SAuction::new(
+            self.id,
+            self.item.to_struct(),
+            self.price as i32,
+            self.expires_at.saturating_sub(now).min(u32::MAX as u64) as u32,
+            &self.seller_name,
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Search {
+    pub item: Option<i16>,
+    pub min_price: u32,
+    pub max_price: Option<u32>,
+}
+
+impl Search {
+    pub fn matches(&self, auction: &Auction) -> bool {
+        self.item.is_none_or(|index| auction.item.index == index)
+            && auction.price >= self.min_price
+            && self.max_price.is_none_or(|max| auction.price <= max)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuctionHouse {
+    next_id: u32,
+    auctions: Vec<Auction>,
+}
+
+impl AuctionHouse {
+    pub fn len(&self) -> usize {
+        self.auctions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.auctions.is_empty()
+    }
+
+    pub fn get(&self, id: u32) -> Option<&Auction> {
+        self.auctions.iter().find(|auction| auction.id == id)
+    }
+
+    pub fn count(&self, seller: &str) -> usize {
+        self.auctions
+            .iter()
+            .filter(|auction| auction.seller == seller)
+            .count()
+    }
+
+    pub fn add(&mut self, mut auction: Auction) -> u32 {
+        self.next_id = self.next_id.wrapping_add(1).max(1);
+        auction.id = self.next_id;
+        self.auctions.push(auction);
+        self.next_id
+    }
+
+    pub fn take(&mut self, id: u32) -> Option<Auction> {
+        let position = self.auctions.iter().position(|auction| auction.id == id)?;
+        Some(self.auctions.remove(position))
+    }
+
+    // puts back an auction that was taken out, keeping the id it was shown with
+
+    pub fn restore(&mut self, auction: Auction) {
+        if self.get(auction.id).is_none() {
+            self.auctions.push(auction);
+        }
+    }
+
+    // cheapest first, ties go to whatever was listed first
+
+    pub fn search(&self, search: &Search, now: u64) -> Vec<Auction> {
+        let mut found = self
+            .auctions
+            .iter()
+            .filter(|auction| auction.expires_at > now && search.matches(auction))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        found.sort_by_key(|auction| (auction.price, auction.id));
+
+        found
+    }
+
+    pub fn has_expired(&self, now: u64) -> bool {
+        self.auctions
+            .iter()
+            .any(|auction| auction.expires_at <= now)
+    }
+
+    pub fn expire(&mut self, now: u64) -> Vec<Auction> {
+        let (expired, kept) = self
+            .auctions
+            .drain(..)
+            .partition(|auction| auction.expires_at <= now);
+
+        self.auctions = kept;
+
+        expired
+    }
+}
+
+// charged when the item is listed and kept whether it sells or not
+
+pub fn listing_fee(price: u32) -> u32 {
+    (price as u64 * AUCTION_FEE_PERCENT as u64 / 100).clamp(1, u32::MAX as u64) as u32
+}
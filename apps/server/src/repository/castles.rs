@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+// a castle is kept by its event name, the taxes it gathered wait here until the guild holding
+// it is paid at the end of a siege
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Castle {
+    #[serde(default)]
+    pub owner: Option<u16>,
+    #[serde(default)]
+    pub taxes: u64,
+}
@@ -0,0 +1,28 @@
+use std::fmt::Display;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharacterError {
+    InvalidName,
+    NameTaken,
+    InvalidSlot,
+    SlotInUse,
+    InvalidClass,
+    NotFound,
+    InGuild,
+    Storage,
+}
+
+impl Display for CharacterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CharacterError::InvalidName => write!(f, "Nome inválido."),
+            CharacterError::NameTaken => write!(f, "Nome já está em uso."),
+            CharacterError::InvalidSlot => write!(f, "Posição inválida."),
+            CharacterError::SlotInUse => write!(f, "Posição já ocupada."),
+            CharacterError::InvalidClass => write!(f, "Classe inválida."),
+            CharacterError::NotFound => write!(f, "Personagem não encontrado."),
+            CharacterError::InGuild => write!(f, "Saia da guilda antes de apagar o personagem."),
+            CharacterError::Storage => write!(f, "Falha ao salvar o personagem."),
+        }
+    }
+}
@@ -0,0 +1,301 @@
+use packets::structs::packets::p3ce::GUILD_STORAGE_LEN;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+use crate::{
+    consts::GUILD_MAX_MEMBERS,
+    structs::{character::normalize_name, inventory::ItemError, item::Item},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuildError {
+    Item(ItemError),
+    NotFound,
+    PlayerNotFound,
+    InvalidName,
+    NameTaken,
+    InGuild,
+    NotInGuild,
+    NotMember,
+    NoPermission,
+    Outranked,
+    NotInvited,
+    Full,
+    LeaderCannotLeave,
+    StorageNotEmpty,
+    NotEnoughCoin,
+    InvalidMark,
+    OwnGuild,
+    AtWar,
+    NotAtWar,
+    Storage,
+}
+
+impl Display for GuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GuildError::Item(error) => write!(f, "{}", error),
+            GuildError::NotFound => write!(f, "Guilda não encontrada."),
+            GuildError::PlayerNotFound => write!(f, "Jogador não encontrado."),
+            GuildError::InvalidName => write!(f, "Nome de guilda inválido."),
+            GuildError::NameTaken => write!(f, "Já existe uma guilda com esse nome."),
+            GuildError::InGuild => write!(f, "O jogador já está em uma guilda."),
+            GuildError::NotInGuild => write!(f, "Você não está em uma guilda."),
+            GuildError::NotMember => write!(f, "O jogador não é da sua guilda."),
+            GuildError::NoPermission => write!(f, "Você não tem permissão para isso."),
+            GuildError::Outranked => write!(f, "O jogador tem um cargo igual ou maior."),
+            GuildError::NotInvited => write!(f, "Convite não encontrado."),
+            GuildError::Full => write!(f, "A guilda está cheia."),
+            GuildError::LeaderCannotLeave => {
+                write!(f, "Passe a liderança antes de sair da guilda.")
+            }
+            GuildError::StorageNotEmpty => {
+                write!(f, "Esvazie o baú da guilda antes de desfazê-la.")
+            }
+            GuildError::NotEnoughCoin => write!(f, "Gold insuficiente."),
+            GuildError::InvalidMark => write!(f, "Marca inválida."),
+            GuildError::OwnGuild => write!(f, "Esta é a sua própria guilda."),
+            GuildError::AtWar => write!(f, "As guildas já estão em guerra."),
+            GuildError::NotAtWar => write!(f, "As guildas não estão em guerra."),
+            GuildError::Storage => write!(f, "Falha ao salvar a guilda."),
+        }
+    }
+}
+
+impl From<ItemError> for GuildError {
+    fn from(error: ItemError) -> Self {
+        GuildError::Item(error)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    Invite,
+    Kick,
+    Notice,
+    Deposit,
+    Withdraw,
+    Promote,
+    Mark,
+    War,
+}
+
+// ranks are ordered from the top, a lower value outranks a higher one
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum GuildRank {
+    Leader,
+    Officer,
+    Member,
+    Recruit,
+}
+
+impl GuildRank {
+    pub fn from_index(index: u8) -> Option<Self> {
+        match index {
+            0 => Some(GuildRank::Leader),
+            1 => Some(GuildRank::Officer),
+            2 => Some(GuildRank::Member),
+            3 => Some(GuildRank::Recruit),
+            _ => None,
+        }
+    }
+
+    pub fn index(&self) -> u8 {
+        match self {
+            GuildRank::Leader => 0,
+            GuildRank::Officer => 1,
+            GuildRank::Member => 2,
+            GuildRank::Recruit => 3,
+        }
+    }
+
+    pub fn can(&self, permission: Permission) -> bool {
+        match permission {
+            Permission::Deposit => *self <= GuildRank::Member,
+            Permission::Invite | Permission::Kick | Permission::Notice | Permission::Withdraw => {
+                *self <= GuildRank::Officer
+            }
+            Permission::Promote | Permission::Mark | Permission::War => *self == GuildRank::Leader,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuildMember {
+    pub name: String,
+    pub rank: GuildRank,
+}
+
+// members are kept by character name, so they belong to the guild whether online or not
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Guild {
+    pub id: u16,
+    pub name: String,
+    pub members: Vec<GuildMember>,
+    #[serde(default)]
+    pub notice: String,
+    #[serde(default = "empty_storage")]
+    pub storage: Vec<Item>,
+    #[serde(default)]
+    pub mark: Vec<u8>,
+    #[serde(default)]
+    pub wars: Vec<u16>,
+}
+
+fn empty_storage() -> Vec<Item> {
+    vec![Item::default(); GUILD_STORAGE_LEN]
+}
+
+impl Guild {
+    pub fn new(id: u16, name: String, leader: &str) -> Self {
+        Self {
+            id,
+            name,
+            members: vec![GuildMember {
+                name: leader.to_string(),
+                rank: GuildRank::Leader,
+            }],
+            notice: String::new(),
+            storage: empty_storage(),
+            mark: Vec::new(),
+            wars: Vec::new(),
+        }
+    }
+
+    pub fn member(&self, name: &str) -> Option<&GuildMember> {
+        let name = normalize_name(name);
+
+        self.members
+            .iter()
+            .find(|member| normalize_name(&member.name) == name)
+    }
+
+    fn member_mut(&mut self, name: &str) -> Option<&mut GuildMember> {
+        let name = normalize_name(name);
+
+        self.members
+            .iter_mut()
+            .find(|member| normalize_name(&member.name) == name)
+    }
+
+    pub fn check(&self, name: &str, permission: Permission) -> Result<GuildRank, GuildError> {
+        let rank = self.member(name).ok_or(GuildError::NotInGuild)?.rank;
+
+        match rank.can(permission) {
+            true => Ok(rank),
+            false => Err(GuildError::NoPermission),
+        }
+    }
+
+    pub fn add(&mut self, name: &str) -> Result<(), GuildError> {
+        if self.member(name).is_some() {
+            return Err(GuildError::InGuild);
+        }
+
+        if self.members.len() >= GUILD_MAX_MEMBERS {
+            return Err(GuildError::Full);
+        }
+
+        self.members.push(GuildMember {
+            name: name.to_string(),
+            rank: GuildRank::Recruit,
+        });
+
+        Ok(())
+    }
+
+    // the leader has to hand the guild over first, unless nobody else is left
+
+    pub fn leave(&mut self, name: &str) -> Result<(), GuildError> {
+        let rank = self.member(name).ok_or(GuildError::NotInGuild)?.rank;
+
+        if rank == GuildRank::Leader && self.members.len() > 1 {
+            return Err(GuildError::LeaderCannotLeave);
+        }
+
+        let name = normalize_name(name);
+        self.members
+            .retain(|member| normalize_name(&member.name) != name);
+
+        Ok(())
+    }
+
+    // only someone below you can be removed
+
+    pub fn kick(&mut self, by: &str, name: &str) -> Result<(), GuildError> {
+        let rank = self.check(by, Permission::Kick)?;
+        let target = self.member(name).ok_or(GuildError::NotMember)?.rank;
+
+        if target <= rank {
+            return Err(GuildError::Outranked);
+        }
+
+        self.leave(name)
+    }
+
+    // handing out the leader rank steps the current leader down to officer
+
+    pub fn set_rank(&mut self, by: &str, name: &str, rank: GuildRank) -> Result<(), GuildError> {
+        self.check(by, Permission::Promote)?;
+
+        if normalize_name(by) == normalize_name(name) {
+            return Err(GuildError::Outranked);
+        }
+
+        self.member_mut(name).ok_or(GuildError::NotMember)?.rank = rank;
+
+        if rank == GuildRank::Leader {
+            if let Some(leader) = self.member_mut(by) {
+                leader.rank = GuildRank::Officer;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn is_storage_empty(&self) -> bool {
+        self.storage.iter().all(|item| item.is_empty())
+    }
+
+    pub fn at_war(&self, other: u16) -> bool {
+        self.wars.contains(&other)
+    }
+
+    // deposits go to the first free slot, the guild storage does not stack
+
+    pub fn deposit(&mut self, item: Item) -> Result<usize, GuildError> {
+        let slot = self
+            .storage
+            .iter()
+            .position(|item| item.is_empty())
+            .ok_or(ItemError::NoSpace)?;
+
+        self.storage[slot] = item;
+
+        Ok(slot)
+    }
+
+    // puts back an item that was taken out, as long as its slot is still free
+
+    pub fn restore(&mut self, slot: usize, item: Item) -> Result<(), GuildError> {
+        match self.storage.get_mut(slot) {
+            Some(stored) if stored.is_empty() => {
+                *stored = item;
+                Ok(())
+            }
+            _ => self.deposit(item).map(|_| ()),
+        }
+    }
+
+    pub fn withdraw(&mut self, slot: usize) -> Result<Item, GuildError> {
+        let stored = self.storage.get_mut(slot).ok_or(ItemError::InvalidSlot)?;
+
+        if stored.is_empty() {
+            return Err(ItemError::Empty.into());
+        }
+
+        Ok(std::mem::take(stored))
+    }
+}
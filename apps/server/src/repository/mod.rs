@@ -0,0 +1,829 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio::{fs, io::AsyncWriteExt, sync::Mutex};
+
+use crate::{
+    consts::{
+        ACCOUNTS_FOLDER, AUCTIONS_FILE, AUDIT_FILE, CASTLES_FILE, CHARACTERS_PER_ACCOUNT,
+        GUILDS_FOLDER,
+    },
+    security::password,
+    structs::{
+        character::{is_valid_name, normalize_name, Character},
+        class::Class,
+        mail::Mail,
+        storage::Storage,
+    },
+};
+
+use self::{
+    accounts::{is_valid_password, is_valid_username, normalize_username, Account, AccountError},
+    auctions::{Auction, AuctionHouse, Search},
+    castles::Castle,
+    characters::CharacterError,
+    guilds::{Guild, GuildError, Permission},
+};
+
+pub mod accounts;
+pub mod auctions;
+pub mod castles;
+pub mod characters;
+pub mod guilds;
+
+pub struct Repository {
+    folder: PathBuf,
+    accounts: Arc<Mutex<HashMap<String, Account>>>,
+    names: Arc<Mutex<HashMap<String, String>>>,
+    auctions: Arc<Mutex<AuctionHouse>>,
+    guilds: Arc<Mutex<HashMap<u16, Guild>>>,
+    castles: Arc<Mutex<HashMap<String, Castle>>>,
+}
+
+impl Repository {
+    pub fn new(folder: PathBuf) -> Self {
+        Self {
+            folder,
+            accounts: Arc::new(Mutex::new(HashMap::new())),
+            names: Arc::new(Mutex::new(HashMap::new())),
+            auctions: Arc::new(Mutex::new(AuctionHouse::default())),
+            guilds: Arc::new(Mutex::new(HashMap::new())),
+            castles: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    // character names are unique across every account, so they are indexed upfront
+
+    pub async fn load(&self) {
+        self.load_auctions().await;
+        self.load_guilds().await;
+        self.load_castles().await;
+
+        let mut names = self.names.lock().await;
+
+        let mut entries = match fs::read_dir(self.folder.join(ACCOUNTS_FOLDER)).await {
+            Ok(entries) => entries,
+            Err(_error) => return,
+        };
+
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+
+            let username = match path.file_stem().and_then(|s| s.to_str()) {
+                Some(username) => username.to_string(),
+                None => continue,
+            };
+
+            if let Some(account) = self.load_account(&username).await {
+                for character in account.characters.iter().flatten() {
+                    names.insert(normalize_name(&character.name), account.username.clone());
+                }
+            }
+        }
+
+        println!("Loaded {} characters", names.len());
+    }
+
+    // accounts
+
+    pub async fn get_account(&self, username: &str) -> Option<Account> {
+        let username = normalize_username(username);
+
+        if !is_valid_username(&username) {
+            return None;
+        }
+
+        let mut accounts = self.accounts.lock().await;
+
+        match accounts.get(&username) {
+            Some(account) => Some(account.clone()),
+            None => {
+                let account = self.load_account(&username).await?;
+                accounts.insert(username, account.clone());
+                Some(account)
+            }
+        }
+    }
+
+    pub async fn create_account(
+        &self,
+        username: &str,
+        password: &str,
+    ) -> Result<Account, AccountError> {
+        let username = normalize_username(username);
+
+        if !is_valid_username(&username) {
+            return Err(AccountError::InvalidUsername);
+        }
+
+        if !is_valid_password(password) {
+            return Err(AccountError::InvalidPassword);
+        }
+
+        let password = password::hash_async(password.to_string())
+            .await
+            .ok_or(AccountError::Hashing)?;
+
+        let mut accounts = self.accounts.lock().await;
+
+        if accounts.contains_key(&username) || self.load_account(&username).await.is_some() {
+            return Err(AccountError::AlreadyExists);
+        }
+
+        let account = Account::new(username, password);
+
+        if !self.write_account(&account).await {
+            return Err(AccountError::Storage);
+        }
+
+        accounts.insert(account.username.clone(), account.clone());
+
+        Ok(account)
+    }
+
+    pub async fn update_account<R>(
+        &self,
+        username: &str,
+        update: impl FnOnce(&mut Account) -> Option<R>,
+    ) -> Option<R> {
+        let username = normalize_username(username);
+
+        let mut accounts = self.accounts.lock().await;
+
+        let mut account = match accounts.get(&username) {
+            Some(account) => account.clone(),
+            None => self.load_account(&username).await?,
+        };
+
+        // changes are applied to a copy and only kept once they hit the disk
+
+        let result = update(&mut account)?;
+
+        if !self.write_account(&account).await {
+            return None;
+        }
+
+        accounts.insert(username, account);
+
+        Some(result)
+    }
+
+    // characters
+
+    pub async fn create_character(
+        &self,
+        username: &str,
+        slot: usize,
+        name: &str,
+        class: i32,
+    ) -> Result<Account, CharacterError> {
+        let name = name.trim().to_string();
+
+        if slot >= CHARACTERS_PER_ACCOUNT {
+            return Err(CharacterError::InvalidSlot);
+        }
+
+        if !is_valid_name(&name) {
+            return Err(CharacterError::InvalidName);
+        }
+
+        let class = Class::from_index(class).ok_or(CharacterError::InvalidClass)?;
+
+        let mut names = self.names.lock().await;
+        let key = normalize_name(&name);
+
+        if names.contains_key(&key) {
+            return Err(CharacterError::NameTaken);
+        }
+
+        let mut slot_in_use = false;
+
+        let account = self
+            .update_account(username, |account| {
+                if account.characters[slot].is_some() {
+                    slot_in_use = true;
+                    return None;
+                }
+
+                account.characters[slot] = Some(Character::new(name, class));
+
+                Some(account.clone())
+            })
+            .await;
+
+        match account {
+            Some(account) => {
+                names.insert(key, account.username.clone());
+                Ok(account)
+            }
+            None if slot_in_use => Err(CharacterError::SlotInUse),
+            None => Err(CharacterError::Storage),
+        }
+    }
+
+    pub async fn delete_character(
+        &self,
+        username: &str,
+        slot: usize,
+        name: &str,
+    ) -> Result<Account, CharacterError> {
+        if slot >= CHARACTERS_PER_ACCOUNT {
+            return Err(CharacterError::InvalidSlot);
+        }
+
+        // guild members are kept by name, so the name cannot be freed while still listed
+
+        if self.guild_of(name).await.is_some() {
+            return Err(CharacterError::InGuild);
+        }
+
+        let mut names = self.names.lock().await;
+        let key = normalize_name(name);
+
+        let mut found = false;
+
+        let account = self
+            .update_account(username, |account| {
+                match &account.characters[slot] {
+                    Some(character) if normalize_name(&character.name) == key => {}
+                    _ => return None,
+                };
+
+                found = true;
+                account.characters[slot] = None;
+
+                Some(account.clone())
+            })
+            .await;
+
+        match account {
+            Some(account) => {
+                names.remove(&key);
+                Ok(account)
+            }
+            None if found => Err(CharacterError::Storage),
+            None => Err(CharacterError::NotFound),
+        }
+    }
+
+    pub async fn save_character(&self, username: &str, slot: usize, character: Character) -> bool {
+        self.update_account(username, |account| {
+            let current = account.characters.get_mut(slot)?;
+
+            match current {
+                Some(current) if current.name == character.name => {
+                    *current = character;
+                    Some(())
+                }
+                _ => None,
+            }
+        })
+        .await
+        .is_some()
+    }
+
+    // the bags and the account storage go together, so an item moved between them is
+    // never saved in both or in neither
+
+    pub async fn save_items(
+        &self,
+        username: &str,
+        slot: usize,
+        character: &Character,
+        storage: &Storage,
+    ) -> bool {
+        self.update_account(username, |account| {
+            replace_items(account, slot, character, storage)
+        })
+        .await
+        .is_some()
+    }
+
+    pub async fn find_character_owner(&self, name: &str) -> Option<String> {
+        self.names.lock().await.get(&normalize_name(name)).cloned()
+    }
+
+    // mail
+
+    pub async fn send_mail(&self, username: &str, mail: Mail) -> bool {
+        self.update_account(username, |account| {
+            account.deliver(mail);
+            Some(())
+        })
+        .await
+        .is_some()
+    }
+
+    // the mail leaves the mailbox in the same write that puts what it carried in the bags
+
+    pub async fn claim_mail(
+        &self,
+        username: &str,
+        slot: usize,
+        character: &Character,
+        storage: &Storage,
+        id: u32,
+    ) -> bool {
+        self.update_account(username, |account| {
+            let position = account.mail.iter().position(|mail| mail.id == id)?;
+
+            replace_items(account, slot, character, storage)?;
+            account.mail.remove(position);
+
+            Some(())
+        })
+        .await
+        .is_some()
+    }
+
+    // auctions
+
+    pub async fn get_auction(&self, id: u32) -> Option<Auction> {
+        self.auctions.lock().await.get(id).cloned()
+    }
+
+    pub async fn count_auctions(&self, seller: &str) -> usize {
+        self.auctions.lock().await.count(seller)
+    }
+
+    pub async fn search_auctions(&self, search: &Search, now: u64) -> Vec<Auction> {
+        self.auctions.lock().await.search(search, now)
+    }
+
+    pub async fn add_auction(&self, auction: Auction) -> Option<u32> {
+        self.update_auctions(|auctions| Some(auctions.add(auction)))
+            .await
+    }
+
+    pub async fn take_auction(&self, id: u32) -> Option<Auction> {
+        self.update_auctions(|auctions| auctions.take(id)).await
+    }
+
+    pub async fn restore_auction(&self, auction: Auction) -> bool {
+        self.update_auctions(|auctions| {
+            auctions.restore(auction);
+            Some(())
+        })
+        .await
+        .is_some()
+    }
+
+    // checked every tick, so the house is only copied when something did expire
+
+    pub async fn expire_auctions(&self, now: u64) -> Vec<Auction> {
+        if !self.auctions.lock().await.has_expired(now) {
+            return Vec::new();
+        }
+
+        self.update_auctions(|auctions| {
+            let expired = auctions.expire(now);
+            (!expired.is_empty()).then_some(expired)
+        })
+        .await
+        .unwrap_or_default()
+    }
+
+    async fn update_auctions<R>(
+        &self,
+        update: impl FnOnce(&mut AuctionHouse) -> Option<R>,
+    ) -> Option<R> {
+        let mut auctions = self.auctions.lock().await;
+
+        let mut changed = auctions.clone();
+        let result = update(&mut changed)?;
+
+        if !self.write_auctions(&changed).await {
+            return None;
+        }
+
+        *auctions = changed;
+
+        Some(result)
+    }
+
+    // guilds
+
+    pub async fn get_guild(&self, id: u16) -> Option<Guild> {
+        self.guilds.lock().await.get(&id).cloned()
+    }
+
+    pub async fn guild_of(&self, name: &str) -> Option<Guild> {
+        self.guilds
+            .lock()
+            .await
+            .values()
+            .find(|guild| guild.member(name).is_some())
+            .cloned()
+    }
+
+    // every war is listed once, with the lowest guild id first
+
+    pub async fn guild_wars(&self) -> Vec<(u16, u16)> {
+        let guilds = self.guilds.lock().await;
+
+        let mut wars = guilds
+            .values()
+            .flat_map(|guild| guild.wars.iter().map(|enemy| (guild.id, *enemy)))
+            .filter(|(id, enemy)| id < enemy)
+            .collect::<Vec<_>>();
+
+        wars.sort();
+
+        wars
+    }
+
+    pub async fn create_guild(&self, name: &str, leader: &str) -> Result<Guild, GuildError> {
+        let name = name.trim();
+
+        if !is_valid_name(name) {
+            return Err(GuildError::InvalidName);
+        }
+
+        let mut guilds = self.guilds.lock().await;
+
+        if guilds
+            .values()
+            .any(|guild| normalize_name(&guild.name) == normalize_name(name))
+        {
+            return Err(GuildError::NameTaken);
+        }
+
+        if guilds.values().any(|guild| guild.member(leader).is_some()) {
+            return Err(GuildError::InGuild);
+        }
+
+        // ids are shown to the client as marks, so freed ones are handed out again
+
+        let id = (1..=u16::MAX)
+            .find(|id| !guilds.contains_key(id))
+            .ok_or(GuildError::Storage)?;
+
+        let guild = Guild::new(id, name.to_string(), leader);
+
+        if !self.write_guild(&guild).await {
+            return Err(GuildError::Storage);
+        }
+
+        guilds.insert(id, guild.clone());
+
+        Ok(guild)
+    }
+
+    pub async fn update_guild<R>(
+        &self,
+        id: u16,
+        update: impl FnOnce(&mut Guild) -> Result<R, GuildError>,
+    ) -> Result<R, GuildError> {
+        let mut guilds = self.guilds.lock().await;
+
+        let mut guild = guilds.get(&id).cloned().ok_or(GuildError::NotFound)?;
+        let result = update(&mut guild)?;
+
+        if !self.write_guild(&guild).await {
+            return Err(GuildError::Storage);
+        }
+
+        guilds.insert(id, guild);
+
+        Ok(result)
+    }
+
+    // a war is kept on both sides, if the second write fails the first one is put back
+
+    pub async fn set_guild_war(
+        &self,
+        id: u16,
+        by: &str,
+        enemy: u16,
+        at_war: bool,
+    ) -> Result<(Guild, Guild), GuildError> {
+        let mut guilds = self.guilds.lock().await;
+
+        if id == enemy {
+            return Err(GuildError::OwnGuild);
+        }
+
+        let guild = guilds.get(&id).cloned().ok_or(GuildError::NotInGuild)?;
+        let other = guilds.get(&enemy).cloned().ok_or(GuildError::NotFound)?;
+
+        guild.check(by, Permission::War)?;
+
+        match (at_war, guild.at_war(enemy)) {
+            (true, true) => return Err(GuildError::AtWar),
+            (false, false) => return Err(GuildError::NotAtWar),
+            _ => {}
+        }
+
+        let mut changed = guild.clone();
+        let mut changed_other = other.clone();
+
+        changed.wars.retain(|war| *war != enemy);
+        changed_other.wars.retain(|war| *war != id);
+
+        if at_war {
+            changed.wars.push(enemy);
+            changed_other.wars.push(id);
+        }
+
+        if !self.write_guild(&changed).await {
+            return Err(GuildError::Storage);
+        }
+
+        if !self.write_guild(&changed_other).await {
+            self.write_guild(&guild).await;
+            return Err(GuildError::Storage);
+        }
+
+        guilds.insert(id, changed.clone());
+        guilds.insert(enemy, changed_other.clone());
+
+        Ok((changed, changed_other))
+    }
+
+    // whoever was at war with it is set free and its castles are left without an owner, those
+    // writes are best effort
+
+    pub async fn delete_guild(&self, id: u16) -> Option<Guild> {
+        let mut guilds = self.guilds.lock().await;
+
+        let guild = guilds.get(&id).cloned()?;
+
+        if let Err(error) = fs::remove_file(self.guild_file(id)).await {
+            println!("repository.delete_guild.error: {}", error);
+            return None;
+        }
+
+        guilds.remove(&id);
+
+        for other in guilds.values_mut().filter(|other| other.at_war(id)) {
+            other.wars.retain(|war| *war != id);
+            self.write_guild(other).await;
+        }
+
+        let mut castles = self.castles.lock().await;
+
+        if castles.values().any(|castle| castle.owner == Some(id)) {
+            castles
+                .values_mut()
+                .filter(|castle| castle.owner == Some(id))
+                .for_each(|castle| castle.owner = None);
+
+            self.write_castles(&castles).await;
+        }
+
+        Some(guild)
+    }
+
+    // castles
+
+    pub async fn castle_owner(&self, castle: &str) -> Option<u16> {
+        self.castles.lock().await.get(castle)?.owner
+    }
+
+    pub async fn set_castle_owner(&self, castle: &str, owner: Option<u16>) -> bool {
+        self.update_castle(castle, |castle| castle.owner = owner)
+            .await
+            .is_some()
+    }
+
+    pub async fn add_castle_taxes(&self, castle: &str, amount: u64) -> bool {
+        self.update_castle(castle, |castle| {
+            castle.taxes = castle.taxes.saturating_add(amount)
+        })
+        .await
+        .is_some()
+    }
+
+    // takes out no more than max, the rest stays for the next time
+
+    pub async fn take_castle_taxes(&self, castle: &str, max: u64) -> u64 {
+        self.update_castle(castle, |castle| {
+            let taken = castle.taxes.min(max);
+            castle.taxes -= taken;
+            taken
+        })
+        .await
+        .unwrap_or(0)
+    }
+
+    async fn update_castle<R>(
+        &self,
+        name: &str,
+        update: impl FnOnce(&mut Castle) -> R,
+    ) -> Option<R> {
+        let mut castles = self.castles.lock().await;
+
+        let mut changed = castles.clone();
+        let result = update(changed.entry(name.to_string()).or_default());
+
+        if !self.write_castles(&changed).await {
+            return None;
+        }
+
+        *castles = changed;
+
+        Some(result)
+    }
+
+    // audit
+
+    // one line per entry, only ever appended to
+
+    pub async fn audit(&self, entry: &str) -> bool {
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.folder.join(AUDIT_FILE))
+            .await;
+
+        let result = match file {
+            Ok(mut file) => file.write_all(format!("{}\n", entry).as_bytes()).await,
+            Err(error) => Err(error),
+        };
+
+        match result {
+            Ok(_) => true,
+            Err(error) => {
+                println!("repository.audit.error: {}", error);
+                false
+            }
+        }
+    }
+
+    // files
+
+    fn account_file(&self, username: &str) -> PathBuf {
+        self.folder
+            .join(ACCOUNTS_FOLDER)
+            .join(format!("{}.json", username))
+    }
+
+    async fn load_account(&self, username: &str) -> Option<Account> {
+        match fs::read_to_string(self.account_file(username)).await {
+            Ok(content) => match serde_json::from_str::<Account>(&content) {
+                Ok(account) => Some(account),
+                Err(error) => {
+                    println!("repository.load_account.error: {}", error);
+                    None
+                }
+            },
+            Err(_error) => None,
+        }
+    }
+
+    async fn write_account(&self, account: &Account) -> bool {
+        match serde_json::to_string_pretty(account) {
+            Ok(content) => write_file(&self.account_file(&account.username), content).await,
+            Err(error) => {
+                println!("repository.write_account.error: {}", error);
+                false
+            }
+        }
+    }
+
+    fn guild_file(&self, id: u16) -> PathBuf {
+        self.folder.join(GUILDS_FOLDER).join(format!("{}.json", id))
+    }
+
+    async fn load_guilds(&self) {
+        let mut guilds = self.guilds.lock().await;
+
+        let mut entries = match fs::read_dir(self.folder.join(GUILDS_FOLDER)).await {
+            Ok(entries) => entries,
+            Err(_error) => return,
+        };
+
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+
+            match fs::read_to_string(&path).await {
+                Ok(content) => match serde_json::from_str::<Guild>(&content) {
+                    Ok(guild) => {
+                        guilds.insert(guild.id, guild);
+                    }
+                    Err(error) => println!("repository.load_guilds.error: {}", error),
+                },
+                Err(error) => println!("repository.load_guilds.error: {}", error),
+            }
+        }
+
+        println!("Loaded {} guilds", guilds.len());
+    }
+
+    async fn write_guild(&self, guild: &Guild) -> bool {
+        match serde_json::to_string_pretty(guild) {
+            Ok(content) => write_file(&self.guild_file(guild.id), content).await,
+            Err(error) => {
+                println!("repository.write_guild.error: {}", error);
+                false
+            }
+        }
+    }
+
+    async fn load_castles(&self) {
+        let castles = match fs::read_to_string(self.folder.join(CASTLES_FILE)).await {
+            Ok(content) => match serde_json::from_str::<HashMap<String, Castle>>(&content) {
+                Ok(castles) => castles,
+                Err(error) => {
+                    println!("repository.load_castles.error: {}", error);
+                    HashMap::new()
+                }
+            },
+            Err(_error) => HashMap::new(),
+        };
+
+        *self.castles.lock().await = castles;
+    }
+
+    async fn write_castles(&self, castles: &HashMap<String, Castle>) -> bool {
+        match serde_json::to_string_pretty(castles) {
+            Ok(content) => write_file(&self.folder.join(CASTLES_FILE), content).await,
+            Err(error) => {
+                println!("repository.write_castles.error: {}", error);
+                false
+            }
+        }
+    }
+
+    async fn load_auctions(&self) {
+        let auctions = match fs::read_to_string(self.folder.join(AUCTIONS_FILE)).await {
+            Ok(content) => match serde_json::from_str::<AuctionHouse>(&content) {
+                Ok(auctions) => auctions,
+                Err(error) => {
+                    println!("repository.load_auctions.error: {}", error);
+                    AuctionHouse::default()
+                }
+            },
+            Err(_error) => AuctionHouse::default(),
+        };
+
+        println!("Loaded {} auctions", auctions.len());
+
+        *self.auctions.lock().await = auctions;
+    }
+
+    async fn write_auctions(&self, auctions: &AuctionHouse) -> bool {
+        match serde_json::to_string_pretty(auctions) {
+            Ok(content) => write_file(&self.folder.join(AUCTIONS_FILE), content).await,
+            Err(error) => {
+                println!("repository.write_auctions.error: {}", error);
+                false
+            }
+        }
+    }
+}
+
+fn replace_items(
+    account: &mut Account,
+    slot: usize,
+    character: &Character,
+    storage: &Storage,
+) -> Option<()> {
+    match account.characters.get_mut(slot)? {
+        Some(current) if current.name == character.name => {
+            *current = character.clone();
+        }
+        _ => return None,
+    };
+
+    account.storage = storage.clone();
+
+    Some(())
+}
+
+// write aside and rename so a crash never leaves a half written file
+
+async fn write_file(file: &Path, content: String) -> bool {
+    let temp_file = file.with_extension("json.tmp");
+
+    if let Some(folder) = file.parent() {
+        if let Err(error) = fs::create_dir_all(folder).await {
+            println!("repository.write_file.error: {}", error);
+            return false;
+        }
+    }
+
+    match fs::write(&temp_file, content).await {
+        Ok(_) => match fs::rename(&temp_file, file).await {
+            Ok(_) => true,
+            Err(error) => {
+                println!("repository.write_file.error: {}", error);
+                false
+            }
+        },
+        Err(error) => {
+            println!("repository.write_file.error: {}", error);
+            false
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use rhai::{Engine, EvalAltResult};
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    consts::{MAP_SIZE, MAX_COIN, QUEST_FLAGS_MAX, SCRIPT_MAX_ACTIONS},
+    structs::{character::Character, position::Position},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    Say(String),
+    GiveItem(i16, u16),
+    TakeItem(i16, u16),
+    GiveCoin(u32),
+    Teleport(Position),
+}
+
+// the player as a script sees it, a copy taken before it runs, what the script asks for is
+// only queued here and handed back to the world
+
+#[derive(Debug, Clone, Default)]
+pub struct Context {
+    pub name: String,
+    pub level: u16,
+    pub class: u8,
+    pub position: Position,
+    pub coin: u32,
+    pub guild: Option<u16>,
+    pub flags: BTreeMap<String, i64>,
+    pub items: BTreeMap<i16, u32>,
+    pub actions: Vec<Action>,
+}
+
+impl Context {
+    pub fn new(character: &Character, guild: Option<u16>) -> Self {
+        let mut items = BTreeMap::new();
+
+        for item in character.inventory.iter().filter(|item| !item.is_empty()) {
+            *items.entry(item.index).or_insert(0) += item.amount() as u32;
+        }
+
+        Self {
+            name: character.name.clone(),
+            level: character.level,
+            class: character.class.index(),
+            position: character.position,
+            coin: character.coin,
+            guild,
+            flags: character.quests.clone(),
+            items,
+            actions: Vec::new(),
+        }
+    }
+}
+
+// scripts pass the player around by value, every copy points at the same context
+
+#[derive(Clone)]
+pub struct ScriptPlayer(Arc<Mutex<Context>>);
+
+impl ScriptPlayer {
+    pub fn new(context: Context) -> Self {
+        Self(Arc::new(Mutex::new(context)))
+    }
+
+    pub fn context(&self) -> Context {
+        self.0.lock().unwrap().clone()
+    }
+
+    fn read<T>(&mut self, read: impl FnOnce(&Context) -> T) -> T {
+        read(&self.0.lock().unwrap())
+    }
+
+    fn write<T>(&mut self, write: impl FnOnce(&mut Context) -> T) -> T {
+        write(&mut self.0.lock().unwrap())
+    }
+
+    fn queue(context: &mut Context, action: Action) -> Result<(), Box<EvalAltResult>> {
+        if context.actions.len() >= SCRIPT_MAX_ACTIONS {
+            return Err("too many actions".into());
+        }
+
+        context.actions.push(action);
+
+        Ok(())
+    }
+
+    // a flag set back to zero is gone, so only what is in use is kept with the character
+
+    fn set_flag(&mut self, name: &str, value: i64) -> Result<(), Box<EvalAltResult>> {
+        self.write(|context| {
+            if value == 0 {
+                context.flags.remove(name);
+                return Ok(());
+            }
+
+            if !context.flags.contains_key(name) && context.flags.len() >= QUEST_FLAGS_MAX {
+                return Err("too many quest flags".into());
+            }
+
+            context.flags.insert(name.to_string(), value);
+
+            Ok(())
+        })
+    }
+
+    fn give_item(&mut self, index: i64, amount: i64) -> Result<(), Box<EvalAltResult>> {
+        let (index, amount) = item_arguments(index, amount)?;
+
+        self.write(|context| {
+            Self::queue(context, Action::GiveItem(index, amount))?;
+            *context.items.entry(index).or_insert(0) += amount as u32;
+
+            Ok(())
+        })
+    }
+
+    fn take_item(&mut self, index: i64, amount: i64) -> Result<(), Box<EvalAltResult>> {
+        let (index, amount) = item_arguments(index, amount)?;
+
+        self.write(|context| {
+            let count = context.items.entry(index).or_insert(0);
+
+            if *count < amount as u32 {
+                return Err(format!("not enough of item {}", index).into());
+            }
+
+            *count -= amount as u32;
+
+            Self::queue(context, Action::TakeItem(index, amount))
+        })
+    }
+
+    fn has_item(&mut self, index: i64, amount: i64) -> bool {
+        let index = match i16::try_from(index) {
+            Ok(index) => index,
+            Err(_error) => return false,
+        };
+
+        self.read(|context| context.items.get(&index).copied().unwrap_or(0) as i64 >= amount)
+    }
+
+    fn give_coin(&mut self, amount: i64) -> Result<(), Box<EvalAltResult>> {
+        if !(1..=MAX_COIN as i64).contains(&amount) {
+            return Err(format!("invalid coin amount {}", amount).into());
+        }
+
+        self.write(|context| Self::queue(context, Action::GiveCoin(amount as u32)))
+    }
+
+    fn teleport(&mut self, x: i64, y: i64) -> Result<(), Box<EvalAltResult>> {
+        if !(0..MAP_SIZE as i64).contains(&x) || !(0..MAP_SIZE as i64).contains(&y) {
+            return Err(format!("invalid position {} {}", x, y).into());
+        }
+
+        let position = Position::new(x as u16, y as u16);
+
+        self.write(|context| {
+            Self::queue(context, Action::Teleport(position))?;
+            context.position = position;
+
+            Ok(())
+        })
+    }
+}
+
+fn item_arguments(index: i64, amount: i64) -> Result<(i16, u16), Box<EvalAltResult>> {
+    let index = i16::try_from(index)
+        .ok()
+        .filter(|index| *index > 0)
+        .ok_or_else(|| format!("invalid item {}", index))?;
+    let amount = u16::try_from(amount)
+        .ok()
+        .filter(|amount| *amount > 0)
+        .ok_or_else(|| format!("invalid amount {}", amount))?;
+
+    Ok((index, amount))
+}
+
+// everything a script can reach, there is no way out to files, the network or the rest of
+// the server
+
+pub fn register(engine: &mut Engine) {
+    engine
+        .register_type_with_name::<ScriptPlayer>("Player")
+        .register_get("name", |player: &mut ScriptPlayer| {
+            player.read(|context| context.name.clone())
+        })
+        .register_get("level", |player: &mut ScriptPlayer| {
+            player.read(|context| context.level as i64)
+        })
+        .register_get("class", |player: &mut ScriptPlayer| {
+            player.read(|context| context.class as i64)
+        })
+        .register_get("x", |player: &mut ScriptPlayer| {
+            player.read(|context| context.position.x as i64)
+        })
+        .register_get("y", |player: &mut ScriptPlayer| {
+            player.read(|context| context.position.y as i64)
+        })
+        .register_get("coin", |player: &mut ScriptPlayer| {
+            player.read(|context| context.coin as i64)
+        })
+        .register_get("guild", |player: &mut ScriptPlayer| {
+            player.read(|context| context.guild.unwrap_or(0) as i64)
+        })
+        .register_fn("flag", |player: &mut ScriptPlayer, name: &str| {
+            player.read(|context| context.flags.get(name).copied().unwrap_or(0))
+        })
+        .register_fn("set_flag", ScriptPlayer::set_flag)
+        .register_fn("say", |player: &mut ScriptPlayer, text: &str| {
+            player.write(|context| ScriptPlayer::queue(context, Action::Say(text.to_string())))
+        })
+        .register_fn("has_item", ScriptPlayer::has_item)
+        .register_fn("give_item", ScriptPlayer::give_item)
+        .register_fn("take_item", ScriptPlayer::take_item)
+        .register_fn("give_coin", ScriptPlayer::give_coin)
+        .register_fn("teleport", ScriptPlayer::teleport);
+}
@@ -0,0 +1,265 @@
+use rhai::{module_resolvers::DummyModuleResolver, CallFnOptions, Dynamic, Engine, Scope, AST};
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
+
+use crate::{
+    consts::{
+        EVENT_SCRIPTS_FOLDER, ITEM_SCRIPTS_FOLDER, NPC_SCRIPTS_FOLDER, SCRIPTS_FOLDER,
+        SCRIPT_EXTENSION, SCRIPT_MAX_CALL_LEVELS, SCRIPT_MAX_COLLECTION_SIZE,
+        SCRIPT_MAX_OPERATIONS, SCRIPT_MAX_STRING_SIZE,
+    },
+    structs::{character::normalize_name, inventory::ItemError},
+    world::data::DataError,
+};
+
+use self::api::{Context, ScriptPlayer};
+
+pub mod api;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptError {
+    Item(ItemError),
+    NotFound,
+    TooFar,
+    CoinLimit,
+    Failed,
+}
+
+impl Display for ScriptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScriptError::Item(error) => write!(f, "{}", error),
+            ScriptError::NotFound => write!(f, "Alvo não encontrado."),
+            ScriptError::TooFar => write!(f, "Muito longe."),
+            ScriptError::CoinLimit => write!(f, "Você não pode carregar mais gold."),
+            ScriptError::Failed => write!(f, "Não foi possível concluir a ação."),
+        }
+    }
+}
+
+impl From<ItemError> for ScriptError {
+    fn from(error: ItemError) -> Self {
+        ScriptError::Item(error)
+    }
+}
+
+// where a script is called from, each one is looked up by its own key and has to define
+// the function that goes with it
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Hook {
+    Talk(String),
+    Use(i16),
+    Reward(String),
+}
+
+impl Hook {
+    fn function(&self) -> &'static str {
+        match self {
+            Hook::Talk(_) => "talk",
+            Hook::Use(_) => "use_item",
+            Hook::Reward(_) => "reward",
+        }
+    }
+}
+
+impl Display for Hook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Hook::Talk(name) => write!(f, "{}/{}", NPC_SCRIPTS_FOLDER, name),
+            Hook::Use(index) => write!(f, "{}/{}", ITEM_SCRIPTS_FOLDER, index),
+            Hook::Reward(name) => write!(f, "{}/{}", EVENT_SCRIPTS_FOLDER, name),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Library {
+    npcs: HashMap<String, AST>,
+    items: HashMap<i16, AST>,
+    events: HashMap<String, AST>,
+}
+
+impl Library {
+    fn get(&self, hook: &Hook) -> Option<&AST> {
+        match hook {
+            Hook::Talk(name) => self.npcs.get(&normalize_name(name)),
+            Hook::Use(index) => self.items.get(index),
+            Hook::Reward(name) => self.events.get(&normalize_name(name)),
+        }
+    }
+
+    pub fn item_indexes(&self) -> impl Iterator<Item = i16> + '_ {
+        self.items.keys().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.npcs.len() + self.items.len() + self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// scripts run to the end in one go, so they are kept on a short leash
+
+pub struct Scripts {
+    engine: Engine,
+    library: RwLock<Arc<Library>>,
+}
+
+impl Default for Scripts {
+    fn default() -> Self {
+        let mut engine = Engine::new();
+
+        engine.set_module_resolver(DummyModuleResolver::new());
+        engine.disable_symbol("eval");
+        engine.set_max_operations(SCRIPT_MAX_OPERATIONS);
+        engine.set_max_call_levels(SCRIPT_MAX_CALL_LEVELS);
+        engine.set_max_string_size(SCRIPT_MAX_STRING_SIZE);
+        engine.set_max_array_size(SCRIPT_MAX_COLLECTION_SIZE);
+        engine.set_max_map_size(SCRIPT_MAX_COLLECTION_SIZE);
+        engine.on_print(|text| println!("scripting.print: {}", text));
+        engine.on_debug(|text, _, _| println!("scripting.debug: {}", text));
+
+        api::register(&mut engine);
+
+        Self {
+            engine,
+            library: RwLock::new(Arc::new(Library::default())),
+        }
+    }
+}
+
+impl Scripts {
+    pub fn library(&self) -> Arc<Library> {
+        self.library.read().unwrap().clone()
+    }
+
+    pub fn has(&self, hook: &Hook) -> bool {
+        self.library().get(hook).is_some()
+    }
+
+    // an item script whose function hands back false keeps the item it was used from
+
+    pub fn run(&self, hook: &Hook, context: Context) -> Result<(Context, bool), ScriptError> {
+        let library = self.library();
+        let ast = library.get(hook).ok_or(ScriptError::NotFound)?;
+
+        let player = ScriptPlayer::new(context);
+        let options = CallFnOptions::new().eval_ast(false).rewind_scope(true);
+
+        let result = self
+            .engine
+            .call_fn_with_options::<Dynamic>(
+                options,
+                &mut Scope::new(),
+                ast,
+                hook.function(),
+                (player.clone(),),
+            )
+            .map_err(|error| {
+                println!("scripting.run.error: {}: {}", hook, error);
+                ScriptError::Failed
+            })?;
+
+        Ok((player.context(), result.as_bool().unwrap_or(true)))
+    }
+
+    pub fn load(&self, folder: PathBuf) {
+        if let Ok(library) = self.compile(&folder, false) {
+            self.swap(library);
+        }
+    }
+
+    pub fn compile(&self, folder: &Path, strict: bool) -> Result<Library, DataError> {
+        let folder = folder.join(SCRIPTS_FOLDER);
+
+        let library = Library {
+            npcs: self.load_folder(&folder.join(NPC_SCRIPTS_FOLDER), "talk", strict, |name| {
+                Some(normalize_name(name))
+            })?,
+            items: self.load_folder(
+                &folder.join(ITEM_SCRIPTS_FOLDER),
+                "use_item",
+                strict,
+                |name| name.parse().ok(),
+            )?,
+            events: self.load_folder(
+                &folder.join(EVENT_SCRIPTS_FOLDER),
+                "reward",
+                strict,
+                |name| Some(normalize_name(name)),
+            )?,
+        };
+
+        println!("Loaded {} scripts", library.len());
+
+        Ok(library)
+    }
+
+    pub fn swap(&self, library: Library) {
+        *self.library.write().unwrap() = Arc::new(library);
+    }
+
+    // every file is named after what it belongs to, the ones that do not compile or lack
+    // their function are left out, or refuse the whole folder on a strict load
+
+    fn load_folder<K: Eq + std::hash::Hash>(
+        &self,
+        folder: &Path,
+        function: &str,
+        strict: bool,
+        key: impl Fn(&str) -> Option<K>,
+    ) -> Result<HashMap<K, AST>, DataError> {
+        let mut scripts = HashMap::new();
+
+        let entries = match fs::read_dir(folder) {
+            Ok(entries) => entries,
+            Err(_error) => return Ok(scripts),
+        };
+
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.extension().and_then(|e| e.to_str()) != Some(SCRIPT_EXTENSION) {
+                continue;
+            }
+
+            let key = path.file_stem().and_then(|s| s.to_str()).and_then(&key);
+
+            let error = match (key, self.engine.compile_file(path.clone())) {
+                (None, _) => "invalid name".to_string(),
+                (Some(_), Err(error)) => error.to_string(),
+                (Some(_), Ok(ast))
+                    if !ast
+                        .iter_functions()
+                        .any(|f| f.name == function && f.params.len() == 1) =>
+                {
+                    format!("no {}(player)", function)
+                }
+                (Some(key), Ok(ast)) => {
+                    scripts.insert(key, ast);
+                    continue;
+                }
+            };
+
+            let name = path
+                .file_name()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default();
+
+            if strict {
+                return Err(DataError::Invalid(format!("{} ({})", name, error)));
+            }
+
+            println!("scripting.load.error: {:?}: {}", path, error);
+        }
+
+        Ok(scripts)
+    }
+}
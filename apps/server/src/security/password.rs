@@ -0,0 +1,42 @@
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+
+pub fn hash(password: &str) -> Option<String> {
+    let salt = SaltString::generate(&mut OsRng);
+
+    match Argon2::default().hash_password(password.as_bytes(), &salt) {
+        Ok(hash) => Some(hash.to_string()),
+        Err(error) => {
+            println!("password.hash.error: {}", error);
+            None
+        }
+    }
+}
+
+pub fn verify(password: &str, hash: &str) -> bool {
+    match PasswordHash::new(hash) {
+        Ok(hash) => Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .is_ok(),
+        Err(error) => {
+            println!("password.verify.error: {}", error);
+            false
+        }
+    }
+}
+
+// argon2 is intentionally slow, keep it away from the async workers
+
+pub async fn hash_async(password: String) -> Option<String> {
+    tokio::task::spawn_blocking(move || hash(&password))
+        .await
+        .unwrap_or(None)
+}
+
+pub async fn verify_async(password: String, hash: String) -> bool {
+    tokio::task::spawn_blocking(move || verify(&password, &hash))
+        .await
+        .unwrap_or(false)
+}
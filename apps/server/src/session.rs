@@ -0,0 +1,170 @@
+use enc_dec::encode;
+use packets::{serializer::serialize, structs::packets::p101::P101};
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::{mpsc::UnboundedSender, Mutex, Notify};
+
+use crate::consts::MAX_USERS;
+
+// state of the session
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionState {
+    Login,
+    CharacterList,
+    World,
+    Closed,
+}
+
+// session
+
+#[derive(Clone)]
+pub struct Session {
+    pub id: Arc<u16>,
+    pub ip: Arc<String>,
+    state: Arc<Mutex<SessionState>>,
+    account: Arc<Mutex<Option<String>>>,
+    sender: Arc<UnboundedSender<Vec<u8>>>,
+    closer: Arc<Notify>,
+}
+
+impl PartialEq for Session {
+    fn eq(&self, other: &Self) -> bool {
+        *self.id == *other.id
+    }
+}
+
+impl Session {
+    pub fn new(id: u16, ip: String, sender: UnboundedSender<Vec<u8>>) -> Self {
+        Self {
+            id: Arc::new(id),
+            ip: Arc::new(ip),
+            state: Arc::new(Mutex::new(SessionState::Login)),
+            account: Arc::new(Mutex::new(None)),
+            sender: Arc::new(sender),
+            closer: Arc::new(Notify::new()),
+        }
+    }
+
+    // state
+
+    pub async fn get_state(&self) -> SessionState {
+        *self.state.lock().await
+    }
+
+    pub async fn set_state(&self, state: SessionState) {
+        *self.state.lock().await = state;
+    }
+
+    // account
+
+    pub async fn get_account(&self) -> Option<String> {
+        self.account.lock().await.clone()
+    }
+
+    pub async fn set_account(&self, account: Option<String>) {
+        *self.account.lock().await = account;
+    }
+
+    // closing
+
+    pub async fn close(&self) {
+        self.set_state(SessionState::Closed).await;
+        self.closer.notify_one();
+    }
+
+    pub async fn closed(&self) {
+        self.closer.notified().await
+    }
+
+    // sending
+
+    pub fn send<T: Sized>(&self, packet: &T) {
+        let mut buf = serialize(packet);
+        encode(&mut buf);
+        self.send_raw(buf);
+    }
+
+    pub fn send_raw(&self, buf: Vec<u8>) {
+        // the receiver only goes away once the connection is closing
+        let _ = self.sender.send(buf);
+    }
+
+    pub fn send_message(&self, message: &str) {
+        self.send(&P101::new(message));
+    }
+}
+
+// sessions
+
+pub struct Sessions {
+    sessions: Arc<Mutex<HashMap<u16, Session>>>,
+    accounts: Arc<Mutex<HashMap<String, u16>>>,
+}
+
+impl Default for Sessions {
+    fn default() -> Self {
+        Self {
+            sessions: Arc::new(Mutex::new(HashMap::new())),
+            accounts: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+impl Sessions {
+    pub async fn add(&self, ip: String, sender: UnboundedSender<Vec<u8>>) -> Option<Session> {
+        let mut sessions = self.sessions.lock().await;
+
+        let id = (1..=MAX_USERS).find(|id| !sessions.contains_key(id))?;
+        let session = Session::new(id, ip, sender);
+
+        sessions.insert(id, session.clone());
+
+        Some(session)
+    }
+
+    pub async fn remove(&self, session: &Session) {
+        if let Some(username) = session.get_account().await {
+            self.logout(session, &username).await;
+        }
+
+        self.sessions.lock().await.remove(&session.id);
+    }
+
+    pub async fn get(&self, id: u16) -> Option<Session> {
+        self.sessions.lock().await.get(&id).cloned()
+    }
+
+    pub async fn all(&self) -> Vec<Session> {
+        self.sessions.lock().await.values().cloned().collect()
+    }
+
+    // accounts
+
+    pub async fn login(&self, session: &Session, username: &str) -> bool {
+        let mut accounts = self.accounts.lock().await;
+
+        if accounts.contains_key(username) {
+            return false;
+        }
+
+        accounts.insert(username.to_string(), *session.id);
+        session.set_account(Some(username.to_string())).await;
+
+        true
+    }
+
+    pub async fn logout(&self, session: &Session, username: &str) {
+        let mut accounts = self.accounts.lock().await;
+
+        if accounts.get(username) == Some(&session.id) {
+            accounts.remove(username);
+        }
+
+        session.set_account(None).await;
+    }
+
+    pub async fn find_by_account(&self, username: &str) -> Option<Session> {
+        let id = *self.accounts.lock().await.get(username)?;
+        self.get(id).await
+    }
+}
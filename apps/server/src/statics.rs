@@ -0,0 +1,41 @@
+use once_cell::sync::Lazy;
+use server_data::data_folder;
+use std::sync::Arc;
+
+use crate::{
+    clock::SystemClock,
+    config::Config,
+    crafting::compose,
+    repository::Repository,
+    session::Sessions,
+    structs::item_table,
+    world::{events, generators, map::Map, skills, World},
+};
+
+pub static CONFIG: Lazy<Config> = Lazy::new(Config::load);
+
+pub static REPOSITORY: Lazy<Repository> = Lazy::new(|| Repository::new(data_folder()));
+
+pub static SESSIONS: Lazy<Sessions> = Lazy::new(Sessions::default);
+
+pub static WORLD: Lazy<World> = Lazy::new(|| {
+    let folder = data_folder();
+
+    let world = World::new(
+        Map::load(folder.clone()),
+        generators::load(folder.clone()),
+        skills::load(folder.clone()),
+        item_table::load(folder.clone()),
+        compose::load(folder.clone()),
+        events::load(folder.clone()),
+        Arc::new(SystemClock),
+    );
+
+    world.rates.set_drop(CONFIG.drop_rate);
+    world.rates.set_coin(CONFIG.coin_rate);
+    world.taxes.set_rate(CONFIG.shop_tax);
+    world.filter.set_words(&CONFIG.chat_filter);
+    world.scripts.load(folder);
+
+    world
+});
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+// what is left of an affect when the character logs out, the time only runs while online
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedAffect {
+    pub kind: u8,
+    pub value: i32,
+    pub stacks: u8,
+    pub remaining: u64,
+}
@@ -0,0 +1,309 @@
+use encoding_rs::WINDOWS_1252;
+use packets::structs::{mob::SMob, score::SScore};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt::Display};
+
+use crate::{
+    combat::Combatant,
+    consts::{
+        BASE_SPEED, CHARACTER_NAME_MAX_LEN, CHARACTER_NAME_MIN_LEN, EQUIP_SLOTS, INVENTORY_SLOTS,
+        MAX_STAT, SPAWN_POSITION,
+    },
+    structs::{
+        affect::SavedAffect,
+        class::Class,
+        evolution::Evolution,
+        experience::{exp_for_level, level_for_exp},
+        item::Item,
+        position::Position,
+    },
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stats {
+    pub str: i16,
+    pub int: i16,
+    pub dex: i16,
+    pub con: i16,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Character {
+    pub name: String,
+    pub class: Class,
+    #[serde(default)]
+    pub evolution: Evolution,
+    pub level: u16,
+    pub exp: u64,
+    pub coin: u32,
+    pub position: Position,
+    pub stats: Stats,
+    #[serde(default)]
+    pub stat_points: u16,
+    #[serde(default)]
+    pub skill_points: u16,
+    pub hp: i32,
+    pub mp: i32,
+    pub equip: Vec<Item>,
+    pub inventory: Vec<Item>,
+    #[serde(default)]
+    pub learned_skills: u32,
+    #[serde(default)]
+    pub affects: Vec<SavedAffect>,
+    #[serde(default)]
+    pub quests: BTreeMap<String, i64>,
+}
+
+impl Character {
+    pub fn new(name: String, class: Class) -> Self {
+        let [str, int, dex, con] = class.base_stats();
+
+        let mut equip = vec![Item::default(); EQUIP_SLOTS];
+        equip[0] = Item::new(class.face());
+
+        let mut character = Self {
+            name,
+            class,
+            evolution: Evolution::Mortal,
+            level: 1,
+            exp: 0,
+            coin: 0,
+            position: Position::new(SPAWN_POSITION.0, SPAWN_POSITION.1),
+            stats: Stats { str, int, dex, con },
+            stat_points: 0,
+            skill_points: 0,
+            hp: 0,
+            mp: 0,
+            equip,
+            inventory: vec![Item::default(); INVENTORY_SLOTS],
+            learned_skills: 0,
+            affects: Vec::new(),
+            quests: BTreeMap::new(),
+        };
+
+        character.refill();
+        character
+    }
+
+    pub fn score(&self) -> SScore {
+        let [base_hp, base_mp] = self.class.base_points();
+        let [hp_per_level, mp_per_level] = self.class.points_per_level();
+        let level = self.level as i32;
+
+        let max_hp = base_hp + hp_per_level * (level - 1) + self.stats.con as i32 * 2;
+        let max_mp = base_mp + mp_per_level * (level - 1) + self.stats.int as i32 * 2;
+
+        SScore {
+            level,
+            defense: self.stats.con as i32 + level,
+            damage: self.stats.str as i32 + level,
+            speed: BASE_SPEED,
+            max_hp,
+            max_mp,
+            hp: self.hp.clamp(0, max_hp),
+            mp: self.mp.clamp(0, max_mp),
+            str: self.stats.str,
+            int: self.stats.int,
+            dex: self.stats.dex,
+            con: self.stats.con,
+            ..SScore::default()
+        }
+    }
+
+    // progression
+
+    pub fn gain_exp(&mut self, exp: u64) -> u16 {
+        let cap = exp_for_level(self.evolution, self.evolution.max_level());
+        self.exp = self.exp.saturating_add(exp).min(cap);
+
+        let level = level_for_exp(self.evolution, self.exp);
+
+        if level <= self.level {
+            return 0;
+        }
+
+        let gained = level - self.level;
+
+        self.level = level;
+        self.stat_points += gained * self.evolution.stat_points_per_level();
+        self.skill_points += gained * self.evolution.skill_points_per_level();
+        self.refill();
+
+        gained
+    }
+
+    // going up hands out the points as if the levels were earned, going down keeps them
+
+    pub fn set_level(&mut self, level: u16) -> u16 {
+        let level = level.clamp(1, self.evolution.max_level());
+
+        if level > self.level {
+            self.gain_exp(exp_for_level(self.evolution, level).saturating_sub(self.exp));
+        } else {
+            self.level = level;
+            self.exp = exp_for_level(self.evolution, level);
+            self.refill();
+        }
+
+        self.level
+    }
+
+    pub fn spend_stat_point(&mut self, stat: i16) -> Result<(), ProgressError> {
+        if self.stat_points == 0 {
+            return Err(ProgressError::NoPoints);
+        }
+
+        let value = match stat {
+            0 => &mut self.stats.str,
+            1 => &mut self.stats.int,
+            2 => &mut self.stats.dex,
+            3 => &mut self.stats.con,
+            _ => return Err(ProgressError::InvalidStat),
+        };
+
+        if *value >= MAX_STAT {
+            return Err(ProgressError::StatLimit);
+        }
+
+        *value += 1;
+        self.stat_points -= 1;
+
+        Ok(())
+    }
+
+    pub fn learn_skill(&mut self, bit: u32, cost: u16) -> Result<(), ProgressError> {
+        if self.learned_skills & bit != 0 {
+            return Err(ProgressError::AlreadyLearned);
+        }
+
+        if self.skill_points < cost {
+            return Err(ProgressError::NoPoints);
+        }
+
+        self.skill_points -= cost;
+        self.learned_skills |= bit;
+
+        Ok(())
+    }
+
+    // evolving starts the levels over, stats, points and skills are kept
+
+    pub fn evolve(&mut self) -> Result<Evolution, ProgressError> {
+        let next = self.evolution.next().ok_or(ProgressError::LastEvolution)?;
+
+        if self.level < self.evolution.max_level() {
+            return Err(ProgressError::NotMaxLevel);
+        }
+
+        self.evolution = next;
+        self.level = 1;
+        self.exp = 0;
+        self.refill();
+
+        Ok(next)
+    }
+
+    pub fn refill(&mut self) {
+        let score = self.score();
+        self.hp = score.max_hp;
+        self.mp = score.max_mp;
+    }
+
+    pub fn combatant(&self) -> Combatant {
+        let score = self.score();
+
+        Combatant {
+            level: score.level,
+            damage: score.damage,
+            magic: self.stats.int as i32 + score.level,
+            defense: score.defense,
+            dex: self.stats.dex as i32,
+            critical: self.stats.dex as i32 / 20,
+            resist: [0; 4],
+        }
+    }
+
+    pub fn to_mob(&self) -> SMob {
+        let mut mob = SMob::new();
+
+        mob.set_name(&self.name);
+        mob.class = self.class.index();
+        mob.coin = self.coin as i32;
+        mob.exp = self.exp as i64;
+        mob.last_position = self.position.to_struct();
+        mob.base_score = self.score();
+        mob.current_score = mob.base_score;
+        mob.learned_skill = self.learned_skills;
+        mob.score_bonus = self.stat_points;
+        mob.skill_bonus = self.skill_points;
+
+        for (slot, item) in self.equip.iter().take(EQUIP_SLOTS).enumerate() {
+            mob.equip[slot] = item.to_struct();
+        }
+
+        for (slot, item) in self.inventory.iter().take(INVENTORY_SLOTS).enumerate() {
+            mob.inventory[slot] = item.to_struct();
+        }
+
+        mob
+    }
+
+    pub fn equip_indexes(&self) -> [u16; EQUIP_SLOTS] {
+        let mut indexes = [0u16; EQUIP_SLOTS];
+
+        for (slot, item) in self.equip.iter().take(EQUIP_SLOTS).enumerate() {
+            indexes[slot] = item.index.max(0) as u16;
+        }
+
+        indexes
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressError {
+    NoPoints,
+    InvalidStat,
+    StatLimit,
+    UnknownSkill,
+    WrongClass,
+    AlreadyLearned,
+    NotMaxLevel,
+    LastEvolution,
+}
+
+impl Display for ProgressError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProgressError::NoPoints => write!(f, "Pontos insuficientes."),
+            ProgressError::InvalidStat => write!(f, "Atributo inválido."),
+            ProgressError::StatLimit => write!(f, "Atributo no limite."),
+            ProgressError::UnknownSkill => write!(f, "Habilidade inexistente."),
+            ProgressError::WrongClass => write!(f, "Habilidade de outra classe."),
+            ProgressError::AlreadyLearned => write!(f, "Habilidade já aprendida."),
+            ProgressError::NotMaxLevel => write!(f, "É preciso alcançar o nível máximo."),
+            ProgressError::LastEvolution => write!(f, "Não há mais evoluções."),
+        }
+    }
+}
+
+// names go to the client as fixed windows-1252 fields, they must survive the round trip
+
+pub fn normalize_name(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+pub fn is_valid_name(name: &str) -> bool {
+    let len = name.chars().count();
+
+    if !(CHARACTER_NAME_MIN_LEN..=CHARACTER_NAME_MAX_LEN).contains(&len) {
+        return false;
+    }
+
+    if !name.chars().all(|c| c.is_alphanumeric()) {
+        return false;
+    }
+
+    let (encoded, _, had_errors) = WINDOWS_1252.encode(name);
+
+    !had_errors && encoded.len() <= CHARACTER_NAME_MAX_LEN
+}
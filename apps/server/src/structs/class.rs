@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Class {
+    TransKnight,
+    Foema,
+    BeastMaster,
+    Huntress,
+}
+
+impl Display for Class {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Class::TransKnight => write!(f, "TransKnight"),
+            Class::Foema => write!(f, "Foema"),
+            Class::BeastMaster => write!(f, "BeastMaster"),
+            Class::Huntress => write!(f, "Huntress"),
+        }
+    }
+}
+
+impl Class {
+    pub fn from_index(index: i32) -> Option<Self> {
+        match index {
+            0 => Some(Class::TransKnight),
+            1 => Some(Class::Foema),
+            2 => Some(Class::BeastMaster),
+            3 => Some(Class::Huntress),
+            _ => None,
+        }
+    }
+
+    pub fn index(&self) -> u8 {
+        match self {
+            Class::TransKnight => 0,
+            Class::Foema => 1,
+            Class::BeastMaster => 2,
+            Class::Huntress => 3,
+        }
+    }
+
+    // the face item is what the client uses to draw the character
+
+    pub fn face(&self) -> i16 {
+        match self {
+            Class::TransKnight => 1,
+            Class::Foema => 11,
+            Class::BeastMaster => 21,
+            Class::Huntress => 31,
+        }
+    }
+
+    // str, int, dex, con
+
+    pub fn base_stats(&self) -> [i16; 4] {
+        match self {
+            Class::TransKnight => [8, 4, 7, 6],
+            Class::Foema => [5, 8, 5, 5],
+            Class::BeastMaster => [6, 6, 9, 5],
+            Class::Huntress => [8, 9, 13, 6],
+        }
+    }
+
+    // hp, mp
+
+    pub fn base_points(&self) -> [i32; 2] {
+        match self {
+            Class::TransKnight => [80, 45],
+            Class::Foema => [60, 65],
+            Class::BeastMaster => [70, 55],
+            Class::Huntress => [75, 60],
+        }
+    }
+
+    pub fn points_per_level(&self) -> [i32; 2] {
+        match self {
+            Class::TransKnight => [3, 1],
+            Class::Foema => [1, 3],
+            Class::BeastMaster => [2, 2],
+            Class::Huntress => [2, 1],
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Evolution {
+    #[default]
+    Mortal,
+    Arch,
+    Celestial,
+}
+
+impl Display for Evolution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Evolution::Mortal => write!(f, "Mortal"),
+            Evolution::Arch => write!(f, "Arch"),
+            Evolution::Celestial => write!(f, "Celestial"),
+        }
+    }
+}
+
+impl Evolution {
+    pub fn next(&self) -> Option<Self> {
+        match self {
+            Evolution::Mortal => Some(Evolution::Arch),
+            Evolution::Arch => Some(Evolution::Celestial),
+            Evolution::Celestial => None,
+        }
+    }
+
+    pub fn max_level(&self) -> u16 {
+        match self {
+            Evolution::Mortal => 400,
+            Evolution::Arch => 400,
+            Evolution::Celestial => 200,
+        }
+    }
+
+    pub fn stat_points_per_level(&self) -> u16 {
+        match self {
+            Evolution::Mortal => 5,
+            Evolution::Arch => 6,
+            Evolution::Celestial => 8,
+        }
+    }
+
+    pub fn skill_points_per_level(&self) -> u16 {
+        match self {
+            Evolution::Mortal => 1,
+            Evolution::Arch => 2,
+            Evolution::Celestial => 3,
+        }
+    }
+
+    // every evolution asks for more exp on the same level
+
+    pub fn exp_multiplier(&self) -> u64 {
+        match self {
+            Evolution::Mortal => 1,
+            Evolution::Arch => 2,
+            Evolution::Celestial => 4,
+        }
+    }
+}
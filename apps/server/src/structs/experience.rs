@@ -0,0 +1,35 @@
+use once_cell::sync::Lazy;
+
+use crate::{
+    consts::{EXP_TABLE_BASE, EXP_TABLE_FACTOR, MAX_LEVEL},
+    structs::evolution::Evolution,
+};
+
+// total exp needed to be at each level as a mortal, index 0 is never used
+
+static EXP_TABLE: Lazy<Vec<u64>> = Lazy::new(|| {
+    let mut table = vec![0u64; MAX_LEVEL as usize + 1];
+
+    for level in 2..=MAX_LEVEL as usize {
+        let previous = (level - 1) as u64;
+        table[level] = table[level - 1] + previous * previous * EXP_TABLE_FACTOR + EXP_TABLE_BASE;
+    }
+
+    table
+});
+
+pub fn exp_for_level(evolution: Evolution, level: u16) -> u64 {
+    let level = level.clamp(1, MAX_LEVEL) as usize;
+    EXP_TABLE[level] * evolution.exp_multiplier()
+}
+
+// the level a given amount of exp reaches, never above what the evolution allows
+
+pub fn level_for_exp(evolution: Evolution, exp: u64) -> u16 {
+    let max = evolution.max_level();
+
+    (1..=max)
+        .rev()
+        .find(|level| exp >= exp_for_level(evolution, *level))
+        .unwrap_or(1)
+}
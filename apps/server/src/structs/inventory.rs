@@ -0,0 +1,562 @@
+use std::fmt::Display;
+
+use crate::{
+    consts::{EQUIP_SLOTS, INVENTORY_SLOTS, STORAGE_SLOTS},
+    structs::{character::Character, item::Item, item_table::ItemTable, storage::Storage},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemError {
+    InvalidSlot,
+    Empty,
+    Unknown,
+    CannotEquip,
+    LevelTooLow,
+    StatsTooLow,
+    WrongClass,
+    NoSpace,
+    NotStackable,
+    InvalidAmount,
+    TooFar,
+    NotOwner,
+    NotFound,
+    Busy,
+    Storage,
+}
+
+impl Display for ItemError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ItemError::InvalidSlot => write!(f, "Espaço inválido."),
+            ItemError::Empty => write!(f, "Não há item nesse espaço."),
+            ItemError::Unknown => write!(f, "Item desconhecido."),
+            ItemError::CannotEquip => write!(f, "Não é possível equipar este item aqui."),
+            ItemError::LevelTooLow => write!(f, "Nível insuficiente."),
+            ItemError::StatsTooLow => write!(f, "Atributos insuficientes."),
+            ItemError::WrongClass => write!(f, "Este item é de outra classe."),
+            ItemError::NoSpace => write!(f, "Não há espaço disponível."),
+            ItemError::NotStackable => write!(f, "Este item não pode ser dividido."),
+            ItemError::InvalidAmount => write!(f, "Quantidade inválida."),
+            ItemError::TooFar => write!(f, "Muito longe."),
+            ItemError::NotOwner => write!(f, "Este item pertence a outro jogador."),
+            ItemError::NotFound => write!(f, "Item não encontrado."),
+            ItemError::Busy => write!(f, "Aguarde, seus itens ainda estão sendo salvos."),
+            ItemError::Storage => write!(f, "Falha ao salvar os itens."),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SlotType {
+    Equip,
+    Inventory,
+    Storage,
+}
+
+impl SlotType {
+    pub fn from_index(index: i32) -> Option<Self> {
+        match index {
+            0 => Some(SlotType::Equip),
+            1 => Some(SlotType::Inventory),
+            2 => Some(SlotType::Storage),
+            _ => None,
+        }
+    }
+
+    pub fn index(&self) -> i16 {
+        match self {
+            SlotType::Equip => 0,
+            SlotType::Inventory => 1,
+            SlotType::Storage => 2,
+        }
+    }
+
+    pub fn slots(&self) -> usize {
+        match self {
+            SlotType::Equip => EQUIP_SLOTS,
+            SlotType::Inventory => INVENTORY_SLOTS,
+            SlotType::Storage => STORAGE_SLOTS,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Slot {
+    pub kind: SlotType,
+    pub index: usize,
+}
+
+impl Slot {
+    pub fn new(kind: SlotType, index: usize) -> Self {
+        Self { kind, index }
+    }
+}
+
+// every item operation works on copies of the bags, nothing is kept unless the whole thing
+// succeeds and gets saved, so a half done move can never leave an item in two places
+
+#[derive(Clone)]
+pub struct ItemTransaction {
+    pub character: Character,
+    pub storage: Storage,
+    changes: Vec<Slot>,
+}
+
+impl ItemTransaction {
+    pub fn new(character: Character, storage: Storage) -> Self {
+        Self {
+            character,
+            storage,
+            changes: Vec::new(),
+        }
+    }
+
+    pub fn changes(&self) -> &[Slot] {
+        &self.changes
+    }
+
+    pub fn get(&self, slot: Slot) -> Result<Item, ItemError> {
+        let items = match slot.kind {
+            SlotType::Equip => &self.character.equip,
+            SlotType::Inventory => &self.character.inventory,
+            SlotType::Storage => &self.storage.items,
+        };
+
+        items.get(slot.index).copied().ok_or(ItemError::InvalidSlot)
+    }
+
+    fn set(&mut self, slot: Slot, item: Item) -> Result<(), ItemError> {
+        let items = match slot.kind {
+            SlotType::Equip => &mut self.character.equip,
+            SlotType::Inventory => &mut self.character.inventory,
+            SlotType::Storage => &mut self.storage.items,
+        };
+
+        *items.get_mut(slot.index).ok_or(ItemError::InvalidSlot)? = item;
+
+        if !self.changes.contains(&slot) {
+            self.changes.push(slot);
+        }
+
+        Ok(())
+    }
+
+    pub fn take(&mut self, slot: Slot) -> Result<Item, ItemError> {
+        let item = self.get(slot)?;
+
+        if item.is_empty() {
+            return Err(ItemError::Empty);
+        }
+
+        // the face is part of the character, it never leaves the first equip slot
+
+        if slot == Slot::new(SlotType::Equip, 0) {
+            return Err(ItemError::InvalidSlot);
+        }
+
+        self.set(slot, Item::default())?;
+
+        Ok(item)
+    }
+
+    // uses up part of a stack, the slot is emptied once nothing is left
+
+    pub fn consume(&mut self, slot: Slot, amount: u16) -> Result<(), ItemError> {
+        let mut item = self.get(slot)?;
+
+        if item.is_empty() {
+            return Err(ItemError::Empty);
+        }
+
+        if amount == 0 || amount > item.amount() {
+            return Err(ItemError::InvalidAmount);
+        }
+
+        if amount == item.amount() {
+            return self.set(slot, Item::default());
+        }
+
+        item.set_amount(item.amount() - amount);
+        self.set(slot, item)
+    }
+
+    pub fn count(&self, index: i16) -> u32 {
+        self.character
+            .inventory
+            .iter()
+            .filter(|item| !item.is_empty() && item.index == index)
+            .map(|item| item.amount() as u32)
+            .sum()
+    }
+
+    // takes the amount out of the stacks of the item in the inventory, in slot order
+
+    pub fn remove(&mut self, index: i16, amount: u16) -> Result<(), ItemError> {
+        if amount == 0 {
+            return Err(ItemError::InvalidAmount);
+        }
+
+        if self.count(index) < amount as u32 {
+            return Err(ItemError::NotFound);
+        }
+
+        let mut left = amount;
+
+        for slot in (0..INVENTORY_SLOTS).map(|index| Slot::new(SlotType::Inventory, index)) {
+            let item = self.get(slot)?;
+
+            if item.is_empty() || item.index != index {
+                continue;
+            }
+
+            let taken = left.min(item.amount());
+            self.consume(slot, taken)?;
+            left -= taken;
+
+            if left == 0 {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn modify(&mut self, slot: Slot, change: impl FnOnce(&mut Item)) -> Result<(), ItemError> {
+        let mut item = self.get(slot)?;
+
+        if item.is_empty() {
+            return Err(ItemError::Empty);
+        }
+
+        change(&mut item);
+        self.set(slot, item)
+    }
+
+    // stacks onto what is already there first, then uses the first free inventory slot, and
+    // nothing is touched unless all of it fits
+
+    pub fn give(&mut self, mut item: Item, items: &ItemTable) -> Result<Slot, ItemError> {
+        let max_stack = items
+            .get(item.index)
+            .map(|info| info.max_stack)
+            .unwrap_or(1);
+
+        let stacked: u32 = match max_stack > 1 {
+            true => self
+                .character
+                .inventory
+                .iter()
+                .filter(|current| !current.is_empty() && current.index == item.index)
+                .map(|current| max_stack.saturating_sub(current.amount()) as u32)
+                .sum(),
+            false => 0,
+        };
+
+        let free = self.character.inventory.iter().any(|item| item.is_empty());
+
+        if stacked < item.amount() as u32 && !free {
+            return Err(ItemError::NoSpace);
+        }
+
+        if max_stack > 1 {
+            for index in 0..INVENTORY_SLOTS {
+                let slot = Slot::new(SlotType::Inventory, index);
+                let mut current = self.get(slot)?;
+
+                if current.index != item.index || current.amount() >= max_stack {
+                    continue;
+                }
+
+                let moved = item.amount().min(max_stack - current.amount());
+
+                current.set_amount(current.amount() + moved);
+                self.set(slot, current)?;
+
+                if moved == item.amount() {
+                    return Ok(slot);
+                }
+
+                item.set_amount(item.amount() - moved);
+            }
+        }
+
+        let slot = (0..INVENTORY_SLOTS)
+            .map(|index| Slot::new(SlotType::Inventory, index))
+            .find(|slot| self.get(*slot).map(|item| item.is_empty()).unwrap_or(false))
+            .ok_or(ItemError::NoSpace)?;
+
+        self.set(slot, item)?;
+
+        Ok(slot)
+    }
+
+    pub fn check_equip(
+        &self,
+        item: &Item,
+        slot: usize,
+        items: &ItemTable,
+    ) -> Result<(), ItemError> {
+        let info = items.get(item.index).ok_or(ItemError::Unknown)?;
+
+        if !info.fits(slot) {
+            return Err(ItemError::CannotEquip);
+        }
+
+        if !info.allows_class(self.character.class.index()) {
+            return Err(ItemError::WrongClass);
+        }
+
+        if self.character.level < info.level {
+            return Err(ItemError::LevelTooLow);
+        }
+
+        let stats = &self.character.stats;
+        let [str, int, dex, con] = info.stats;
+
+        if stats.str < str || stats.int < int || stats.dex < dex || stats.con < con {
+            return Err(ItemError::StatsTooLow);
+        }
+
+        Ok(())
+    }
+
+    // moving onto the same kind of stackable item merges them, anything else swaps places
+
+    pub fn move_item(&mut self, from: Slot, to: Slot, items: &ItemTable) -> Result<(), ItemError> {
+        if from == to {
+            return Ok(());
+        }
+
+        let source = self.get(from)?;
+        let target = self.get(to)?;
+
+        if source.is_empty() {
+            return Err(ItemError::Empty);
+        }
+
+        if from == Slot::new(SlotType::Equip, 0) || to == Slot::new(SlotType::Equip, 0) {
+            return Err(ItemError::InvalidSlot);
+        }
+
+        let max_stack = items
+            .get(source.index)
+            .map(|info| info.max_stack)
+            .unwrap_or(1);
+
+        if !target.is_empty() && target.index == source.index && max_stack > 1 {
+            let moved = source
+                .amount()
+                .min(max_stack.saturating_sub(target.amount()));
+
+            if moved > 0 {
+                let mut merged = target;
+                merged.set_amount(target.amount() + moved);
+                self.set(to, merged)?;
+
+                let mut rest = source;
+
+                if moved == source.amount() {
+                    rest = Item::default();
+                } else {
+                    rest.set_amount(source.amount() - moved);
+                }
+
+                return self.set(from, rest);
+            }
+        }
+
+        if to.kind == SlotType::Equip {
+            self.check_equip(&source, to.index, items)?;
+        }
+
+        if from.kind == SlotType::Equip && !target.is_empty() {
+            self.check_equip(&target, from.index, items)?;
+        }
+
+        self.set(to, source)?;
+        self.set(from, target)
+    }
+
+    pub fn split(&mut self, from: Slot, amount: u16, items: &ItemTable) -> Result<Slot, ItemError> {
+        let mut source = self.get(from)?;
+
+        if source.is_empty() {
+            return Err(ItemError::Empty);
+        }
+
+        if !items
+            .get(source.index)
+            .is_some_and(|info| info.is_stackable())
+        {
+            return Err(ItemError::NotStackable);
+        }
+
+        if amount == 0 || amount >= source.amount() {
+            return Err(ItemError::InvalidAmount);
+        }
+
+        let to = (0..INVENTORY_SLOTS)
+            .map(|index| Slot::new(SlotType::Inventory, index))
+            .find(|slot| self.get(*slot).map(|item| item.is_empty()).unwrap_or(false))
+            .ok_or(ItemError::NoSpace)?;
+
+        let mut part = source;
+        part.set_amount(amount);
+        source.set_amount(source.amount() - amount);
+
+        self.set(from, source)?;
+        self.set(to, part)?;
+
+        Ok(to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::structs::{class::Class, item_table::ItemInfo};
+
+    const POTION: i16 = 400;
+    const SWORD: i16 = 800;
+
+    fn table() -> ItemTable {
+        let potion = ItemInfo {
+            max_stack: 10,
+            ..Default::default()
+        };
+        let sword = ItemInfo {
+            max_stack: 1,
+            ..Default::default()
+        };
+
+        ItemTable::new(HashMap::from([(POTION, potion), (SWORD, sword)]))
+    }
+
+    fn transaction() -> ItemTransaction {
+        ItemTransaction::new(
+            Character::new("tester".to_string(), Class::TransKnight),
+            Storage::default(),
+        )
+    }
+
+    fn stack(index: i16, amount: u16) -> Item {
+        let mut item = Item::new(index);
+        item.set_amount(amount);
+        item
+    }
+
+    fn inventory(index: usize) -> Slot {
+        Slot::new(SlotType::Inventory, index)
+    }
+
+    #[test]
+    fn different_items_swap_places() {
+        let mut transaction = transaction();
+        transaction.set(inventory(0), Item::new(SWORD)).unwrap();
+        transaction.set(inventory(1), stack(POTION, 3)).unwrap();
+
+        transaction
+            .move_item(inventory(0), inventory(1), &table())
+            .unwrap();
+
+        assert_eq!(transaction.get(inventory(0)).unwrap(), stack(POTION, 3));
+        assert_eq!(transaction.get(inventory(1)).unwrap(), Item::new(SWORD));
+    }
+
+    #[test]
+    fn stacks_merge_up_to_the_max_and_keep_the_rest() {
+        let mut transaction = transaction();
+        transaction.set(inventory(0), stack(POTION, 7)).unwrap();
+        transaction.set(inventory(1), stack(POTION, 6)).unwrap();
+
+        transaction
+            .move_item(inventory(0), inventory(1), &table())
+            .unwrap();
+
+        assert_eq!(transaction.get(inventory(1)).unwrap().amount(), 10);
+        assert_eq!(transaction.get(inventory(0)).unwrap().amount(), 3);
+    }
+
+    #[test]
+    fn a_split_moves_part_of_the_stack_to_a_free_slot() {
+        let mut transaction = transaction();
+        transaction.set(inventory(0), stack(POTION, 7)).unwrap();
+        transaction.set(inventory(1), Item::new(SWORD)).unwrap();
+
+        let to = transaction.split(inventory(0), 3, &table()).unwrap();
+
+        assert_eq!(to, inventory(2));
+        assert_eq!(transaction.get(inventory(0)).unwrap().amount(), 4);
+        assert_eq!(transaction.get(to).unwrap(), stack(POTION, 3));
+
+        assert_eq!(
+            transaction.split(inventory(0), 4, &table()),
+            Err(ItemError::InvalidAmount)
+        );
+        assert_eq!(
+            transaction.split(inventory(1), 1, &table()),
+            Err(ItemError::NotStackable)
+        );
+    }
+
+    #[test]
+    fn a_give_that_does_not_fit_changes_nothing() {
+        let mut transaction = transaction();
+        transaction.set(inventory(0), stack(POTION, 8)).unwrap();
+
+        for index in 1..INVENTORY_SLOTS {
+            transaction.set(inventory(index), Item::new(SWORD)).unwrap();
+        }
+
+        let before = transaction.character.inventory.clone();
+
+        assert_eq!(
+            transaction.give(stack(POTION, 5), &table()),
+            Err(ItemError::NoSpace)
+        );
+        assert_eq!(transaction.character.inventory, before);
+
+        assert_eq!(
+            transaction.give(stack(POTION, 2), &table()),
+            Ok(inventory(0))
+        );
+        assert_eq!(transaction.get(inventory(0)).unwrap().amount(), 10);
+    }
+
+    #[test]
+    fn an_amount_past_a_byte_is_refused() {
+        let mut item = Item::new(POTION);
+
+        assert!(item.set_amount(255));
+        assert!(!item.set_amount(256));
+        assert_eq!(item.amount(), 255);
+    }
+
+    #[test]
+    fn consuming_the_whole_stack_empties_the_slot() {
+        let mut transaction = transaction();
+        transaction.set(inventory(0), stack(POTION, 3)).unwrap();
+
+        assert_eq!(
+            transaction.consume(inventory(0), 4),
+            Err(ItemError::InvalidAmount)
+        );
+
+        transaction.consume(inventory(0), 2).unwrap();
+        assert_eq!(transaction.get(inventory(0)).unwrap().amount(), 1);
+
+        transaction.consume(inventory(0), 1).unwrap();
+        assert!(transaction.get(inventory(0)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn the_face_never_moves() {
+        let mut transaction = transaction();
+        let face = Slot::new(SlotType::Equip, 0);
+
+        assert_eq!(transaction.take(face), Err(ItemError::InvalidSlot));
+        assert!(transaction.changes().is_empty());
+    }
+}
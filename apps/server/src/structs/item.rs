@@ -0,0 +1,104 @@
+use packets::structs::item::{SItem, SItemEffect};
+use serde::{Deserialize, Serialize};
+
+use crate::consts::{ITEM_EFFECT_AMOUNT, ITEM_EFFECT_SANC};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Item {
+    pub index: i16,
+    pub effects: [(u8, u8); 3],
+}
+
+impl Item {
+    pub fn new(index: i16) -> Self {
+        Self {
+            index,
+            effects: [(0, 0); 3],
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index <= 0
+    }
+
+    pub fn effect(&self, index: u8) -> Option<u8> {
+        self.effects
+            .iter()
+            .find(|(effect, _)| *effect == index)
+            .map(|(_, value)| *value)
+    }
+
+    // reuses the slot already holding the effect, otherwise takes the first free one
+
+    pub fn set_effect(&mut self, index: u8, value: u8) -> bool {
+        let slot = self
+            .effects
+            .iter()
+            .position(|(effect, _)| *effect == index)
+            .or_else(|| self.effects.iter().position(|(effect, _)| *effect == 0));
+
+        match slot {
+            Some(slot) => {
+                self.effects[slot] = (index, value);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn remove_effect(&mut self, index: u8) {
+        for effect in self
+            .effects
+            .iter_mut()
+            .filter(|(effect, _)| *effect == index)
+        {
+            *effect = (0, 0);
+        }
+    }
+
+    // stackable items keep how many they are in one of the effect slots
+
+    pub fn amount(&self) -> u16 {
+        self.effect(ITEM_EFFECT_AMOUNT)
+            .map(|value| (value as u16).max(1))
+            .unwrap_or(1)
+    }
+
+    // the amount lives in a single byte, anything bigger is refused rather than cut down
+
+    pub fn set_amount(&mut self, amount: u16) -> bool {
+        match u8::try_from(amount) {
+            Ok(amount) => self.set_effect(ITEM_EFFECT_AMOUNT, amount),
+            Err(_error) => false,
+        }
+    }
+
+    pub fn refine(&self) -> u8 {
+        self.effect(ITEM_EFFECT_SANC).unwrap_or(0)
+    }
+
+    pub fn set_refine(&mut self, level: u8) -> bool {
+        if level == 0 {
+            self.remove_effect(ITEM_EFFECT_SANC);
+            return true;
+        }
+
+        self.set_effect(ITEM_EFFECT_SANC, level)
+    }
+
+    pub fn from_struct(item: &SItem) -> Self {
+        Self {
+            index: item.index,
+            effects: item.effects.map(|effect| (effect.index, effect.value)),
+        }
+    }
+
+    pub fn to_struct(&self) -> SItem {
+        SItem {
+            index: self.index,
+            effects: self
+                .effects
+                .map(|(index, value)| SItemEffect { index, value }),
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use item_list::{
+    effects::{EF_AMOUNT, EF_CLASS},
+    SItemList, ITEM_LIST_FILE,
+};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use crate::{consts::EQUIP_SLOTS, world::data::DataError};
+
+// what the server needs to know about an item to decide what can be done with it
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ItemInfo {
+    pub level: u16,
+    pub stats: [i16; 4],
+    pub class_mask: u8,
+    pub slot_mask: u16,
+    pub max_stack: u16,
+    pub price: i32,
+}
+
+impl ItemInfo {
+    pub fn from_struct(item: &SItemList) -> Self {
+        Self {
+            level: item.req_level.max(0) as u16,
+            stats: [item.req_str, item.req_int, item.req_dex, item.req_con],
+            class_mask: item.effect(EF_CLASS).unwrap_or(0).clamp(0, u8::MAX as i16) as u8,
+            slot_mask: item.pos as u16,
+            max_stack: item.effect(EF_AMOUNT).unwrap_or(1).clamp(1, u8::MAX as i16) as u16,
+            price: item.price.max(0),
+        }
+    }
+
+    pub fn fits(&self, slot: usize) -> bool {
+        slot < EQUIP_SLOTS && self.slot_mask & (1 << slot) != 0
+    }
+
+    pub fn allows_class(&self, class: u8) -> bool {
+        self.class_mask == 0 || self.class_mask & (1 << class) != 0
+    }
+
+    pub fn is_stackable(&self) -> bool {
+        self.max_stack > 1
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ItemTable {
+    items: HashMap<i16, ItemInfo>,
+}
+
+impl ItemTable {
+    pub fn new(items: HashMap<i16, ItemInfo>) -> Self {
+        Self { items }
+    }
+
+    pub fn get(&self, index: i16) -> Option<&ItemInfo> {
+        self.items.get(&index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+// empty templates are holes in the client list, they are left out so unknown indexes are refused
+
+pub fn load(folder: PathBuf) -> ItemTable {
+    match read(&folder) {
+        Ok(items) => items,
+        Err(_error) => {
+            println!(
+                "item_table.load: {} not found or invalid, no items",
+                ITEM_LIST_FILE
+            );
+            ItemTable::default()
+        }
+    }
+}
+
+pub fn read(folder: &Path) -> Result<ItemTable, DataError> {
+    if !folder.join(ITEM_LIST_FILE).exists() {
+        return Err(DataError::Missing(ITEM_LIST_FILE.to_string()));
+    }
+
+    let templates =
+        item_list::load(folder).ok_or_else(|| DataError::Invalid(ITEM_LIST_FILE.to_string()))?;
+
+    let items: HashMap<i16, ItemInfo> = templates
+        .iter()
+        .enumerate()
+        .filter(|(index, item)| !item.is_empty() && *index <= i16::MAX as usize)
+        .map(|(index, item)| (index as i16, ItemInfo::from_struct(item)))
+        .collect();
+
+    println!("Loaded {} items", items.len());
+
+    Ok(ItemTable::new(items))
+}
@@ -0,0 +1,64 @@
+use packets::structs::auction::SMail;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+use crate::structs::{inventory::ItemError, item::Item};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MailError {
+    Item(ItemError),
+    NotFound,
+    CoinLimit,
+}
+
+impl Display for MailError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MailError::Item(error) => write!(f, "{}", error),
+            MailError::NotFound => write!(f, "Correspondência não encontrada."),
+            MailError::CoinLimit => write!(f, "Você não pode carregar mais gold."),
+        }
+    }
+}
+
+impl From<ItemError> for MailError {
+    fn from(error: ItemError) -> Self {
+        MailError::Item(error)
+    }
+}
+
+// whatever is sent to an account waits in its mailbox until claimed, the owner does not need
+// to be online for it to arrive
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Mail {
+    pub id: u32,
+    pub from: String,
+    pub text: String,
+    pub item: Item,
+    pub coin: u32,
+    pub sent_at: u64,
+}
+
+impl Mail {
+    pub fn new(from: &str, text: &str, item: Item, coin: u32, sent_at: u64) -> Self {
+        Self {
+            id: 0,
+            from: from.to_string(),
+            text: text.to_string(),
+            item,
+            coin,
+            sent_at,
+        }
+    }
+
+    pub fn to_struct(&self) -> SMail {
+        SMail::new(
+            self.id,
+            self.item.to_struct(),
+            self.coin as i32,
+            &self.from,
+            &self.text,
+        )
+    }
+}
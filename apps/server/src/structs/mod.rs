@@ -0,0 +1,11 @@
+pub mod affect;
+pub mod character;
+pub mod class;
+pub mod evolution;
+pub mod experience;
+pub mod inventory;
+pub mod item;
+pub mod item_table;
+pub mod mail;
+pub mod position;
+pub mod storage;
@@ -0,0 +1,25 @@
+use packets::structs::position::SPosition;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Position {
+    pub x: u16,
+    pub y: u16,
+}
+
+impl Position {
+    pub fn new(x: u16, y: u16) -> Self {
+        Self { x, y }
+    }
+
+    pub fn distance(&self, other: &Position) -> u16 {
+        let dx = (self.x as i32 - other.x as i32).unsigned_abs();
+        let dy = (self.y as i32 - other.y as i32).unsigned_abs();
+
+        dx.max(dy) as u16
+    }
+
+    pub fn to_struct(&self) -> SPosition {
+        SPosition::new(self.x, self.y)
+    }
+}
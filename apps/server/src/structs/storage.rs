@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{consts::STORAGE_SLOTS, structs::item::Item};
+
+// shared by every character of the account
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Storage {
+    pub items: Vec<Item>,
+    pub coin: u32,
+}
+
+impl Default for Storage {
+    fn default() -> Self {
+        Self {
+            items: vec![Item::default(); STORAGE_SLOTS],
+            coin: 0,
+        }
+    }
+}
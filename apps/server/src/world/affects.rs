@@ -0,0 +1,250 @@
+use packets::structs::{affect::SAffect, packets::p3b9::AFFECTS_LEN};
+use std::time::{Duration, Instant};
+
+use crate::{
+    consts::{
+        AFFECT_HASTE, AFFECT_POISON, AFFECT_SHIELD, AFFECT_SLOW, AFFECT_TICK_INTERVAL,
+        POISON_MAX_STACKS,
+    },
+    structs::affect::SavedAffect,
+};
+
+// how a new affect of a kind that is already active gets combined with it
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stacking {
+    Strongest,
+    Stack(u8),
+    Refresh,
+}
+
+pub fn stacking(kind: u8) -> Stacking {
+    match kind {
+        AFFECT_POISON => Stacking::Stack(POISON_MAX_STACKS),
+        AFFECT_HASTE | AFFECT_SLOW => Stacking::Strongest,
+        _ => Stacking::Refresh,
+    }
+}
+
+// only what helps the character survives a logout
+
+pub fn is_persistent(kind: u8) -> bool {
+    matches!(kind, AFFECT_HASTE | AFFECT_SHIELD)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Affect {
+    pub kind: u8,
+    pub value: i32,
+    pub stacks: u8,
+    pub until: Instant,
+    pub next_tick: Instant,
+}
+
+impl Affect {
+    pub fn new(kind: u8, value: i32, duration: Duration, now: Instant) -> Self {
+        Self {
+            kind,
+            value,
+            stacks: 1,
+            until: now + duration,
+            next_tick: now + AFFECT_TICK_INTERVAL,
+        }
+    }
+
+    pub fn power(&self) -> i32 {
+        self.value * self.stacks as i32
+    }
+}
+
+// changes are only flagged here, the tick is the one telling the client about them
+
+#[derive(Debug, Clone, Default)]
+pub struct Affects {
+    list: Vec<Affect>,
+    changed: bool,
+}
+
+impl Affects {
+    pub fn apply(&mut self, affect: Affect) {
+        self.changed = true;
+
+        let active = match self
+            .list
+            .iter_mut()
+            .find(|active| active.kind == affect.kind)
+        {
+            Some(active) => active,
+            None => {
+                if self.list.len() < AFFECTS_LEN {
+                    self.list.push(affect);
+                }
+
+                return;
+            }
+        };
+
+        match stacking(affect.kind) {
+            Stacking::Strongest => {
+                active.value = active.value.max(affect.value);
+                active.until = active.until.max(affect.until);
+            }
+            Stacking::Stack(max) => {
+                active.value = affect.value;
+                active.stacks = (active.stacks + 1).min(max);
+                active.until = affect.until;
+            }
+            Stacking::Refresh => *active = affect,
+        }
+    }
+
+    pub fn remove(&mut self, kind: u8) -> Option<Affect> {
+        let index = self.list.iter().position(|affect| affect.kind == kind)?;
+        self.changed = true;
+        Some(self.list.remove(index))
+    }
+
+    pub fn expire(&mut self, now: Instant) -> Vec<Affect> {
+        let (expired, active): (Vec<Affect>, Vec<Affect>) =
+            self.list.iter().partition(|affect| affect.until <= now);
+
+        self.list = active;
+        self.changed |= !expired.is_empty();
+
+        expired
+    }
+
+    // periodic effects, for now only poison hurts on every tick
+
+    pub fn tick(&mut self, now: Instant) -> i32 {
+        let mut damage = 0;
+
+        for affect in self.list.iter_mut() {
+            while affect.next_tick <= now && affect.next_tick <= affect.until {
+                if affect.kind == AFFECT_POISON {
+                    damage += affect.power();
+                }
+
+                affect.next_tick += AFFECT_TICK_INTERVAL;
+            }
+        }
+
+        damage
+    }
+
+    // shields soak damage until they run dry, whatever gets through is returned
+
+    pub fn absorb(&mut self, damage: i32) -> i32 {
+        let shield = match self
+            .list
+            .iter_mut()
+            .find(|affect| affect.kind == AFFECT_SHIELD)
+        {
+            Some(shield) => shield,
+            None => return damage,
+        };
+
+        let absorbed = damage.min(shield.value);
+        shield.value -= absorbed;
+        self.changed = true;
+
+        if shield.value <= 0 {
+            self.remove(AFFECT_SHIELD);
+        }
+
+        damage - absorbed
+    }
+
+    pub fn speed(&self, base: u8) -> u8 {
+        let haste = self
+            .get(AFFECT_HASTE)
+            .map(|affect| affect.value)
+            .unwrap_or(0);
+        let slow = self
+            .get(AFFECT_SLOW)
+            .map(|affect| affect.value)
+            .unwrap_or(0);
+
+        (base as i32 + haste - slow).clamp(1, u8::MAX as i32) as u8
+    }
+
+    pub fn get(&self, kind: u8) -> Option<&Affect> {
+        self.list.iter().find(|affect| affect.kind == kind)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Affect> {
+        self.list.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
+
+    // client side
+
+    pub fn to_struct(&self, now: Instant) -> [SAffect; AFFECTS_LEN] {
+        let mut affects = [SAffect::default(); AFFECTS_LEN];
+
+        for (slot, affect) in self.list.iter().take(AFFECTS_LEN).enumerate() {
+            affects[slot] = SAffect {
+                kind: affect.kind,
+                value: affect.value.clamp(0, u8::MAX as i32) as u8,
+                level: affect.stacks as u16,
+                time: affect.until.saturating_duration_since(now).as_secs() as u32,
+            };
+        }
+
+        affects
+    }
+
+    pub fn kinds(&self) -> [u8; AFFECTS_LEN] {
+        let mut kinds = [0; AFFECTS_LEN];
+
+        for (slot, affect) in self.list.iter().take(AFFECTS_LEN).enumerate() {
+            kinds[slot] = affect.kind;
+        }
+
+        kinds
+    }
+
+    // persistence
+
+    pub fn save(&self, now: Instant) -> Vec<SavedAffect> {
+        self.list
+            .iter()
+            .filter(|affect| is_persistent(affect.kind) && affect.until > now)
+            .map(|affect| SavedAffect {
+                kind: affect.kind,
+                value: affect.value,
+                stacks: affect.stacks,
+                remaining: affect.until.duration_since(now).as_secs(),
+            })
+            .collect()
+    }
+
+    pub fn restore(saved: &[SavedAffect], now: Instant) -> Self {
+        let list = saved
+            .iter()
+            .filter(|saved| is_persistent(saved.kind) && saved.remaining > 0)
+            .take(AFFECTS_LEN)
+            .map(|saved| Affect {
+                stacks: saved.stacks.max(1),
+                ..Affect::new(
+                    saved.kind,
+                    saved.value,
+                    Duration::from_secs(saved.remaining),
+                    now,
+                )
+            })
+            .collect();
+
+        Self {
+            list,
+            changed: true,
+        }
+    }
+}
@@ -0,0 +1,413 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    consts::{
+        MOB_AGGRO_RANGE, MOB_ATTACK_RANGE, MOB_FLEE_DURATION, MOB_FLEE_HP_PERCENT, MOB_LEASH_RANGE,
+    },
+    structs::position::Position,
+    world::generators::Waypoint,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MobState {
+    Idle { until: Instant },
+    Wandering,
+    Chasing { target: u16 },
+    Attacking { target: u16 },
+    Returning,
+    Fleeing { from: u16, until: Instant },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    Wait,
+    MoveTo(Position),
+    FleeFrom(Position),
+    Attack(u16),
+    Recover,
+}
+
+// everything the mob can see this tick, gathered by the world before thinking
+
+pub struct Perception<'a> {
+    pub position: Position,
+    pub home: Position,
+    pub hp: i32,
+    pub max_hp: i32,
+    pub aggressive: bool,
+    pub route: &'a [Waypoint],
+    pub route_type: u8,
+    pub targets: Vec<(u16, Position)>,
+}
+
+impl Perception<'_> {
+    fn target_position(&self, target: u16) -> Option<Position> {
+        self.targets
+            .iter()
+            .find(|(id, _)| *id == target)
+            .map(|(_, position)| *position)
+    }
+
+    fn nearest_target(&self) -> Option<u16> {
+        self.targets
+            .iter()
+            .filter(|(_, position)| self.position.distance(position) <= MOB_AGGRO_RANGE)
+            .min_by_key(|(_, position)| self.position.distance(position))
+            .map(|(id, _)| *id)
+    }
+
+    fn is_low_hp(&self) -> bool {
+        self.max_hp > 0 && self.hp * 100 < self.max_hp * MOB_FLEE_HP_PERCENT
+    }
+}
+
+// the state machine only depends on what it is given, the same perception at the same time
+// always leads to the same decision
+
+#[derive(Debug, Clone)]
+pub struct MobAi {
+    pub state: MobState,
+    pub waypoint: usize,
+    pub forward: bool,
+    pub next_think: Instant,
+    pub next_move: Instant,
+    pub next_attack: Instant,
+}
+
+impl MobAi {
+    pub fn new(now: Instant) -> Self {
+        Self {
+            state: MobState::Idle { until: now },
+            waypoint: 0,
+            forward: true,
+            next_think: now,
+            next_move: now,
+            next_attack: now,
+        }
+    }
+
+    // being hit wakes the mob up even when it would not pick a fight on its own
+
+    pub fn provoke(&mut self, attacker: u16) {
+        if matches!(self.state, MobState::Idle { .. } | MobState::Wandering) {
+            self.state = MobState::Chasing { target: attacker };
+        }
+    }
+
+    pub fn think(&mut self, perception: &Perception, now: Instant) -> Decision {
+        match self.state {
+            MobState::Returning => {
+                if perception.position.distance(&perception.home) <= 1 {
+                    self.state = MobState::Idle {
+                        until: now + self.waypoint_wait(perception),
+                    };
+
+                    return Decision::Recover;
+                }
+
+                Decision::MoveTo(perception.home)
+            }
+
+            MobState::Fleeing { from, until } => match perception.target_position(from) {
+                Some(position) if now < until => Decision::FleeFrom(position),
+                _ => {
+                    self.state = MobState::Returning;
+                    Decision::MoveTo(perception.home)
+                }
+            },
+
+            MobState::Chasing { target } | MobState::Attacking { target } => {
+                self.engage(perception, target, now)
+            }
+
+            MobState::Idle { until } => {
+                if let Some(target) = self.aggro(perception) {
+                    return self.engage(perception, target, now);
+                }
+
+                if now < until {
+                    return Decision::Wait;
+                }
+
+                self.advance_waypoint(perception);
+                self.state = MobState::Wandering;
+
+                Decision::MoveTo(self.current_waypoint(perception).position)
+            }
+
+            MobState::Wandering => {
+                if let Some(target) = self.aggro(perception) {
+                    return self.engage(perception, target, now);
+                }
+
+                let waypoint = self.current_waypoint(perception);
+
+                if perception.position.distance(&waypoint.position) <= waypoint.range.max(1) {
+                    self.state = MobState::Idle {
+                        until: now + self.waypoint_wait(perception),
+                    };
+
+                    return Decision::Wait;
+                }
+
+                Decision::MoveTo(waypoint.position)
+            }
+        }
+    }
+
+    fn aggro(&self, perception: &Perception) -> Option<u16> {
+        if !perception.aggressive {
+            return None;
+        }
+
+        perception.nearest_target()
+    }
+
+    fn engage(&mut self, perception: &Perception, target: u16, now: Instant) -> Decision {
+        if perception.position.distance(&perception.home) > MOB_LEASH_RANGE {
+            self.state = MobState::Returning;
+            return Decision::MoveTo(perception.home);
+        }
+
+        let target_position = match perception.target_position(target) {
+            Some(position) => position,
+            None => {
+                self.state = MobState::Returning;
+                return Decision::MoveTo(perception.home);
+            }
+        };
+
+        if perception.is_low_hp() {
+            self.state = MobState::Fleeing {
+                from: target,
+                until: now + MOB_FLEE_DURATION,
+            };
+
+            return Decision::FleeFrom(target_position);
+        }
+
+        if perception.position.distance(&target_position) <= MOB_ATTACK_RANGE {
+            self.state = MobState::Attacking { target };
+            return Decision::Attack(target);
+        }
+
+        self.state = MobState::Chasing { target };
+
+        Decision::MoveTo(target_position)
+    }
+
+    // route types: 0 walks the route back and forth, 1 loops it, anything else stays home
+
+    fn advance_waypoint(&mut self, perception: &Perception) {
+        let last = perception.route.len().saturating_sub(1);
+
+        if last == 0 {
+            self.waypoint = 0;
+            return;
+        }
+
+        match perception.route_type {
+            0 => {
+                if self.forward && self.waypoint >= last {
+                    self.forward = false;
+                } else if !self.forward && self.waypoint == 0 {
+                    self.forward = true;
+                }
+
+                self.waypoint = match self.forward {
+                    true => (self.waypoint + 1).min(last),
+                    false => self.waypoint.saturating_sub(1),
+                };
+            }
+            1 => self.waypoint = (self.waypoint + 1) % (last + 1),
+            _ => self.waypoint = 0,
+        }
+    }
+
+    fn current_waypoint(&self, perception: &Perception) -> Waypoint {
+        perception
+            .route
+            .get(self.waypoint)
+            .copied()
+            .unwrap_or(Waypoint {
+                position: perception.home,
+                range: 0,
+                wait: 0,
+            })
+    }
+
+    fn waypoint_wait(&self, perception: &Perception) -> Duration {
+        Duration::from_secs(self.current_waypoint(perception).wait.max(1) as u64)
+    }
+}
+
+// a point straight away from the threat, the walk itself is left to the pathing
+
+pub fn flee_target(position: Position, threat: Position, distance: u16) -> Position {
+    let away = |from: u16, to: u16| match from.cmp(&to) {
+        std::cmp::Ordering::Less => from.saturating_sub(distance),
+        std::cmp::Ordering::Greater => from.saturating_add(distance),
+        std::cmp::Ordering::Equal => from,
+    };
+
+    let mut target = Position::new(away(position.x, threat.x), away(position.y, threat.y));
+
+    if target == position {
+        target.x = position.x.saturating_add(distance);
+    }
+
+    target
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{Clock, ManualClock};
+
+    const HOME: Position = Position { x: 100, y: 100 };
+    const PLAYER: u16 = 1;
+
+    fn perception(
+        position: Position,
+        hp: i32,
+        targets: Vec<(u16, Position)>,
+    ) -> Perception<'static> {
+        Perception {
+            position,
+            home: HOME,
+            hp,
+            max_hp: 100,
+            aggressive: true,
+            route: &[],
+            route_type: 0,
+            targets,
+        }
+    }
+
+    #[test]
+    fn idle_waits_then_wanders_home() {
+        let clock = ManualClock::default();
+        let mut ai = MobAi::new(clock.now());
+        ai.state = MobState::Idle {
+            until: clock.now() + Duration::from_secs(2),
+        };
+
+        let alone = perception(HOME, 100, Vec::new());
+
+        assert_eq!(ai.think(&alone, clock.now()), Decision::Wait);
+
+        let now = clock.advance(Duration::from_secs(2));
+
+        assert_eq!(ai.think(&alone, now), Decision::MoveTo(HOME));
+        assert_eq!(ai.state, MobState::Wandering);
+    }
+
+    #[test]
+    fn aggro_chase_attack_leash_and_recover() {
+        let clock = ManualClock::default();
+        let mut ai = MobAi::new(clock.now());
+
+        // out of sight nothing happens
+
+        let far = Position::new(110, 100);
+        let seen = perception(HOME, 100, vec![(PLAYER, far)]);
+        ai.state = MobState::Idle {
+            until: clock.now() + Duration::from_secs(60),
+        };
+
+        assert_eq!(ai.think(&seen, clock.now()), Decision::Wait);
+
+        // in aggro range the mob goes after the player
+
+        let near = Position::new(104, 100);
+        let now = clock.advance(Duration::from_millis(500));
+        let seen = perception(HOME, 100, vec![(PLAYER, near)]);
+
+        assert_eq!(ai.think(&seen, now), Decision::MoveTo(near));
+        assert_eq!(ai.state, MobState::Chasing { target: PLAYER });
+
+        // next to the player it attacks
+
+        let now = clock.advance(Duration::from_millis(500));
+        let beside = perception(Position::new(103, 100), 100, vec![(PLAYER, near)]);
+
+        assert_eq!(ai.think(&beside, now), Decision::Attack(PLAYER));
+        assert_eq!(ai.state, MobState::Attacking { target: PLAYER });
+
+        // dragged too far from home it gives up and walks back
+
+        let now = clock.advance(Duration::from_millis(500));
+        let away = Position::new(100 + MOB_LEASH_RANGE + 1, 100);
+        let leashed = perception(away, 100, vec![(PLAYER, Position::new(away.x + 1, 100))]);
+
+        assert_eq!(ai.think(&leashed, now), Decision::MoveTo(HOME));
+        assert_eq!(ai.state, MobState::Returning);
+
+        // back home it heals and rests
+
+        let now = clock.advance(Duration::from_millis(500));
+        let home = perception(HOME, 40, Vec::new());
+
+        assert_eq!(ai.think(&home, now), Decision::Recover);
+        assert!(matches!(ai.state, MobState::Idle { until } if until > now));
+    }
+
+    #[test]
+    fn low_hp_flees_until_the_time_runs_out() {
+        let clock = ManualClock::default();
+        let mut ai = MobAi::new(clock.now());
+        ai.state = MobState::Attacking { target: PLAYER };
+
+        let threat = Position::new(101, 100);
+        let hurt = perception(HOME, MOB_FLEE_HP_PERCENT - 1, vec![(PLAYER, threat)]);
+
+        assert_eq!(ai.think(&hurt, clock.now()), Decision::FleeFrom(threat));
+        assert_eq!(
+            ai.state,
+            MobState::Fleeing {
+                from: PLAYER,
+                until: clock.now() + MOB_FLEE_DURATION
+            }
+        );
+
+        let now = clock.advance(MOB_FLEE_DURATION - Duration::from_millis(1));
+
+        assert_eq!(ai.think(&hurt, now), Decision::FleeFrom(threat));
+
+        let now = clock.advance(Duration::from_millis(1));
+
+        assert_eq!(ai.think(&hurt, now), Decision::MoveTo(HOME));
+        assert_eq!(ai.state, MobState::Returning);
+    }
+
+    #[test]
+    fn passive_mobs_only_fight_back() {
+        let clock = ManualClock::default();
+        let mut ai = MobAi::new(clock.now());
+
+        let near = Position::new(101, 100);
+        let mut seen = perception(HOME, 100, vec![(PLAYER, near)]);
+        seen.aggressive = false;
+        ai.state = MobState::Idle {
+            until: clock.now() + Duration::from_secs(60),
+        };
+
+        assert_eq!(ai.think(&seen, clock.now()), Decision::Wait);
+
+        ai.provoke(PLAYER);
+
+        assert_eq!(ai.think(&seen, clock.now()), Decision::Attack(PLAYER));
+    }
+
+    #[test]
+    fn lost_target_goes_home() {
+        let clock = ManualClock::default();
+        let mut ai = MobAi::new(clock.now());
+        ai.state = MobState::Chasing { target: PLAYER };
+
+        let gone = perception(Position::new(105, 100), 100, Vec::new());
+
+        assert_eq!(ai.think(&gone, clock.now()), Decision::MoveTo(HOME));
+        assert_eq!(ai.state, MobState::Returning);
+    }
+}
@@ -0,0 +1,246 @@
+use packets::structs::packets::p3c2::{AUCTION_PAGE_LEN, P3C2};
+
+use crate::{
+    consts::{AUCTION_DURATION, AUCTION_LIMIT, AUCTION_SENDER, MAX_COIN},
+    repository::auctions::{listing_fee, Auction, AuctionError, Search},
+    statics::REPOSITORY,
+    structs::{
+        inventory::{ItemError, Slot},
+        item::Item,
+        mail::Mail,
+    },
+    world::World,
+};
+
+impl World {
+    pub async fn list_auction(&self, id: u16, slot: Slot, price: u32) -> Result<(), AuctionError> {
+        if price == 0 || price > MAX_COIN {
+            return Err(AuctionError::InvalidPrice);
+        }
+
+        let (seller, seller_name) = match self.entities.lock().await.players.get(&id) {
+            Some(player) => (player.username.clone(), player.character.name.clone()),
+            None => return Err(ItemError::NotFound.into()),
+        };
+
+        if REPOSITORY.count_auctions(&seller).await >= AUCTION_LIMIT {
+            return Err(AuctionError::TooMany);
+        }
+
+        let mut entities = self.entities.lock().await;
+
+        let fee = listing_fee(price);
+        let mut transaction = entities.item_transaction(id)?;
+
+        let item = transaction.take(slot)?;
+
+        transaction.character.coin = transaction
+            .character
+            .coin
+            .checked_sub(fee)
+            .ok_or(AuctionError::NotEnoughCoin)?;
+
+        let auction = Auction {
+            id: 0,
+            seller,
+            seller_name,
+            item,
+            price,
+            expires_at: self.clock.timestamp() + AUCTION_DURATION.as_secs(),
+        };
+
+        // the auction is written first and taken back out when the bags fail to save
+
+        let pending = entities.reserve_items(vec![(id, transaction)])?;
+        drop(entities);
+
+        let auction_id = match REPOSITORY.add_auction(auction).await {
+            Some(auction_id) => auction_id,
+            None => {
+                drop(self.release_items(pending, false).await);
+                return Err(AuctionError::Storage);
+            }
+        };
+
+        let saved = pending.save().await;
+
+        if !saved && REPOSITORY.take_auction(auction_id).await.is_none() {
+            println!(
+                "world.list_auction.error: auction {} listed without its item",
+                auction_id
+            );
+        }
+
+        let entities = self.release_items(pending, saved).await;
+
+        if !saved {
+            return Err(ItemError::Storage.into());
+        }
+
+        self.collect_taxes(&entities, id, fee);
+
+        Ok(())
+    }
+
+    pub async fn search_auctions(&self, id: u16, search: Search, page: u16) {
+        let now = self.clock.timestamp();
+        let found = REPOSITORY.search_auctions(&search, now).await;
+
+        let pages = found.len().div_ceil(AUCTION_PAGE_LEN).max(1);
+        let page = (page as usize).min(pages - 1);
+
+        let mut packet = P3C2::new(id, page as u16, pages.min(u16::MAX as usize) as u16);
+
+        for (position, auction) in found
+            .iter()
+            .skip(page * AUCTION_PAGE_LEN)
+            .take(AUCTION_PAGE_LEN)
+            .enumerate()
+        {
+            packet.auctions[position] = auction.to_struct(now);
+        }
+
+        self.entities.lock().await.send_to(id, &packet);
+    }
+
+    // the auction leaves the house before the buyer is saved, the proceeds only go out once
+    // the item is safe in the bags
+
+    pub async fn buy_auction(&self, id: u16, auction_id: u32) -> Result<(), AuctionError> {
+        let data = self.data();
+
+        let username = match self.entities.lock().await.players.get(&id) {
+            Some(player) => player.username.clone(),
+            None => return Err(ItemError::NotFound.into()),
+        };
+
+        let auction = REPOSITORY
+            .get_auction(auction_id)
+            .await
+            .filter(|auction| auction.expires_at > self.clock.timestamp())
+            .ok_or(AuctionError::NotFound)?;
+
+        if auction.seller == username {
+            return Err(AuctionError::OwnAuction);
+        }
+
+        let mut entities = self.entities.lock().await;
+        let mut transaction = entities.item_transaction(id)?;
+
+        transaction.character.coin = transaction
+            .character
+            .coin
+            .checked_sub(auction.price)
+            .ok_or(AuctionError::NotEnoughCoin)?;
+
+        transaction.give(auction.item, &data.items)?;
+
+        let pending = entities.reserve_items(vec![(id, transaction)])?;
+        drop(entities);
+
+        let auction = match REPOSITORY.take_auction(auction_id).await {
+            Some(auction) => auction,
+            None => {
+                drop(self.release_items(pending, false).await);
+                return Err(AuctionError::Storage);
+            }
+        };
+
+        let saved = pending.save().await;
+
+        if !saved && !REPOSITORY.restore_auction(auction.clone()).await {
+            println!(
+                "world.buy_auction.error: auction {} of {} lost",
+                auction.id, auction.seller
+            );
+        }
+
+        drop(self.release_items(pending, saved).await);
+
+        if !saved {
+            return Err(ItemError::Storage.into());
+        }
+
+        let proceeds = Mail::new(
+            AUCTION_SENDER,
+            "Seu item foi vendido.",
+            Item::default(),
+            auction.price,
+            self.clock.timestamp(),
+        );
+
+        if !self.send_mail(&auction.seller, proceeds).await {
+            println!(
+                "world.buy_auction.error: {} gold of auction {} not delivered to {}",
+                auction.price, auction.id, auction.seller
+            );
+        }
+
+        Ok(())
+    }
+
+    pub async fn cancel_auction(&self, id: u16, auction_id: u32) -> Result<(), AuctionError> {
+        let username = match self.entities.lock().await.players.get(&id) {
+            Some(player) => player.username.clone(),
+            None => return Err(ItemError::NotFound.into()),
+        };
+
+        let auction = REPOSITORY
+            .get_auction(auction_id)
+            .await
+            .ok_or(AuctionError::NotFound)?;
+
+        if auction.seller != username {
+            return Err(AuctionError::NotSeller);
+        }
+
+        let auction = REPOSITORY
+            .take_auction(auction_id)
+            .await
+            .ok_or(AuctionError::Storage)?;
+
+        match self.return_auction(auction, "Leilão cancelado.").await {
+            true => Ok(()),
+            false => Err(AuctionError::Storage),
+        }
+    }
+
+    pub async fn process_auctions(&self) {
+        let expired = REPOSITORY.expire_auctions(self.clock.timestamp()).await;
+
+        if expired.is_empty() {
+            return;
+        }
+
+        for auction in expired {
+            self.return_auction(auction, "Seu item não foi vendido.")
+                .await;
+        }
+    }
+
+    // an item that leaves the house unsold goes back by mail, if even that fails it is put
+    // back in the house so nothing is lost
+
+    async fn return_auction(&self, auction: Auction, text: &str) -> bool {
+        let mail = Mail::new(
+            AUCTION_SENDER,
+            text,
+            auction.item,
+            0,
+            self.clock.timestamp(),
+        );
+
+        if self.send_mail(&auction.seller, mail).await {
+            return true;
+        }
+
+        if !REPOSITORY.restore_auction(auction.clone()).await {
+            println!(
+                "world.return_auction.error: auction {} of {} lost",
+                auction.id, auction.seller
+            );
+        }
+
+        false
+    }
+}
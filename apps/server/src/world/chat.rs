@@ -0,0 +1,376 @@
+use packets::structs::packets::{p333::P333, p334::P334};
+use std::{collections::VecDeque, fmt::Display, sync::RwLock, time::Instant};
+
+use crate::{
+    consts::{CHAT_BURST, CHAT_WINDOW, SHOUT_CHAT_TARGET, SHOUT_INTERVAL},
+    repository::guilds::GuildError,
+    statics::REPOSITORY,
+    world::{party::PartyError, Entities, World},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatError {
+    Party(PartyError),
+    Guild(GuildError),
+    NotFound,
+    Empty,
+    Muted(u64),
+    TooFast,
+}
+
+impl Display for ChatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChatError::Party(error) => write!(f, "{}", error),
+            ChatError::Guild(error) => write!(f, "{}", error),
+            ChatError::NotFound => write!(f, "Jogador não está conectado."),
+            ChatError::Empty => write!(f, "Mensagem vazia."),
+            ChatError::Muted(minutes) => {
+                write!(f, "Você está silenciado por mais {} minuto(s).", minutes)
+            }
+            ChatError::TooFast => write!(f, "Aguarde um pouco antes de falar novamente."),
+        }
+    }
+}
+
+impl From<PartyError> for ChatError {
+    fn from(error: PartyError) -> Self {
+        ChatError::Party(error)
+    }
+}
+
+impl From<GuildError> for ChatError {
+    fn from(error: GuildError) -> Self {
+        ChatError::Guild(error)
+    }
+}
+
+// a few messages can go out together, past that the oldest one has to leave the window
+// first, shouts have a wait of their own on top
+
+#[derive(Debug, Clone, Default)]
+pub struct ChatLimit {
+    sent: VecDeque<Instant>,
+    next_shout: Option<Instant>,
+}
+
+impl ChatLimit {
+    pub fn allow(&mut self, now: Instant) -> bool {
+        while self
+            .sent
+            .front()
+            .is_some_and(|sent| now.duration_since(*sent) >= CHAT_WINDOW)
+        {
+            self.sent.pop_front();
+        }
+
+        if self.sent.len() >= CHAT_BURST {
+            return false;
+        }
+
+        self.sent.push_back(now);
+
+        true
+    }
+
+    pub fn allow_shout(&mut self, now: Instant) -> bool {
+        if self.next_shout.is_some_and(|next| now < next) {
+            return false;
+        }
+
+        self.next_shout = Some(now + SHOUT_INTERVAL);
+
+        true
+    }
+}
+
+// words are matched without caring for case and masked letter by letter, the list can be
+// swapped while the server runs
+
+#[derive(Debug, Default)]
+pub struct ChatFilter {
+    words: RwLock<Vec<String>>,
+}
+
+impl ChatFilter {
+    pub fn set_words(&self, words: &[String]) {
+        let words = words
+            .iter()
+            .map(|word| word.trim().to_lowercase())
+            .filter(|word| !word.is_empty())
+            .collect();
+
+        if let Ok(mut current) = self.words.write() {
+            *current = words;
+        }
+    }
+
+    pub fn words(&self) -> Vec<String> {
+        self.words
+            .read()
+            .map(|words| words.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn apply(&self, message: &str) -> String {
+        let words = match self.words.read() {
+            Ok(words) => words,
+            Err(_error) => return message.to_string(),
+        };
+
+        let mut chars = message.chars().collect::<Vec<_>>();
+        let lower = chars
+            .iter()
+            .map(|c| c.to_lowercase().next().unwrap_or(*c))
+            .collect::<Vec<_>>();
+
+        for word in words.iter() {
+            let word = word.chars().collect::<Vec<_>>();
+            let mut start = 0;
+
+            while start + word.len() <= lower.len() {
+                if lower[start..start + word.len()] == word[..] {
+                    chars[start..start + word.len()].fill('*');
+                    start += word.len();
+                } else {
+                    start += 1;
+                }
+            }
+        }
+
+        chars.into_iter().collect()
+    }
+}
+
+// minutes left rounded up, so a mute never shows as over while it still holds
+
+pub fn muted_minutes(muted_until: u64, now: u64) -> Option<u64> {
+    (muted_until > now).then(|| (muted_until - now).div_ceil(60))
+}
+
+impl World {
+    // every message goes through the same checks, whoever is muted or talking too fast is
+    // stopped before anything goes out and what does go out is filtered
+
+    fn speak(
+        &self,
+        entities: &mut Entities,
+        id: u16,
+        message: &str,
+        shout: bool,
+    ) -> Result<(String, String), ChatError> {
+        let now = self.clock.now();
+
+        let player = entities.players.get_mut(&id).ok_or(ChatError::NotFound)?;
+
+        if let Some(minutes) = muted_minutes(player.muted_until, self.clock.timestamp()) {
+            return Err(ChatError::Muted(minutes));
+        }
+
+        let message = message.trim();
+
+        if message.is_empty() {
+            return Err(ChatError::Empty);
+        }
+
+        if !player.chat.allow(now) || (shout && !player.chat.allow_shout(now)) {
+            return Err(ChatError::TooFast);
+        }
+
+        Ok((player.character.name.clone(), self.filter.apply(message)))
+    }
+
+    pub async fn say(&self, id: u16, message: &str) -> Result<(), ChatError> {
+        let mut entities = self.entities.lock().await;
+
+        let (_, message) = self.speak(&mut entities, id, message, false)?;
+        let position = entities.grid.position(id).ok_or(ChatError::NotFound)?;
+
+        entities.send_in_view(&position, &P333::new(id, &message), Some(id));
+
+        Ok(())
+    }
+
+    // shouts reach the whole server, the name is marked so they stand apart from whispers
+
+    pub async fn shout(&self, id: u16, message: &str) -> Result<(), ChatError> {
+        let mut entities = self.entities.lock().await;
+
+        let (name, message) = self.speak(&mut entities, id, message, true)?;
+        let name = format!("{}{}", SHOUT_CHAT_TARGET, name);
+
+        for player in entities.players.values() {
+            player
+                .session
+                .send(&P334::new(player.id(), &name, &message));
+        }
+
+        Ok(())
+    }
+
+    pub async fn whisper(&self, id: u16, target: &str, message: &str) -> Result<(), ChatError> {
+        let mut entities = self.entities.lock().await;
+
+        let target = entities.find_player(target).ok_or(ChatError::NotFound)?;
+        let (name, message) = self.speak(&mut entities, id, message, false)?;
+
+        entities.send_to(target, &P334::new(target, &name, &message));
+
+        Ok(())
+    }
+
+    pub async fn party_chat(&self, id: u16, message: &str) -> Result<(), ChatError> {
+        let mut entities = self.entities.lock().await;
+
+        let members = entities
+            .parties
+            .get(id)
+            .ok_or(PartyError::NoParty)?
+            .members
+            .clone();
+
+        let (name, message) = self.speak(&mut entities, id, message, false)?;
+
+        for member in members.into_iter().filter(|member| *member != id) {
+            entities.send_to(member, &P334::new(member, &name, &message));
+        }
+
+        Ok(())
+    }
+
+    pub async fn guild_chat(&self, id: u16, message: &str) -> Result<(), ChatError> {
+        let mut entities = self.entities.lock().await;
+
+        let guild = entities
+            .players
+            .get(&id)
+            .ok_or(ChatError::NotFound)?
+            .guild
+            .ok_or(GuildError::NotInGuild)?;
+
+        let (name, message) = self.speak(&mut entities, id, message, false)?;
+
+        for member in entities
+            .guild_members(guild)
+            .into_iter()
+            .filter(|member| *member != id)
+        {
+            entities.send_to(member, &P334::new(member, &name, &message));
+        }
+
+        Ok(())
+    }
+
+    // a mute is kept with the account, so it holds for every character and across logins
+
+    pub async fn mute(&self, name: &str, minutes: u64) -> bool {
+        let username = match REPOSITORY.find_character_owner(name).await {
+            Some(username) => username,
+            None => return false,
+        };
+
+        let muted_until = match minutes {
+            0 => 0,
+            _ => self
+                .clock
+                .timestamp()
+                .saturating_add(minutes.saturating_mul(60)),
+        };
+
+        let saved = REPOSITORY
+            .update_account(&username, |account| {
+                account.muted_until = muted_until;
+                Some(())
+            })
+            .await
+            .is_some();
+
+        if !saved {
+            return false;
+        }
+
+        let mut entities = self.entities.lock().await;
+
+        for player in entities
+            .players
+            .values_mut()
+            .filter(|player| player.username == username)
+        {
+            player.muted_until = muted_until;
+
+            match minutes {
+                0 => player
+                    .session
+                    .send_message("Você não está mais silenciado."),
+                _ => player
+                    .session
+                    .send_message(&format!("Você foi silenciado por {} minuto(s).", minutes)),
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn filter(words: &[&str]) -> ChatFilter {
+        let filter = ChatFilter::default();
+        filter.set_words(
+            &words
+                .iter()
+                .map(|word| word.to_string())
+                .collect::<Vec<_>>(),
+        );
+        filter
+    }
+
+    #[test]
+    fn words_are_masked_whatever_their_case() {
+        let filter = filter(&["Noob"]);
+
+        assert_eq!(filter.apply("NoOb and noob"), "**** and ****");
+        assert_eq!(filter.apply("hello"), "hello");
+    }
+
+    #[test]
+    fn overlapping_words_are_all_masked() {
+        let filter = filter(&["abc", "bcd"]);
+
+        assert_eq!(filter.apply("xAbCdx"), "x****x");
+        assert_eq!(filter.apply("ababc"), "ab***");
+    }
+
+    #[test]
+    fn a_burst_fills_up_and_frees_as_the_window_moves() {
+        let start = Instant::now();
+        let mut limit = ChatLimit::default();
+
+        let second = Duration::from_secs(1);
+
+        for sent in 0..CHAT_BURST as u32 {
+            assert!(limit.allow(start + second * sent));
+        }
+
+        let full = start + second * CHAT_BURST as u32;
+
+        assert!(!limit.allow(full - Duration::from_millis(1)));
+        assert!(limit.allow(start + CHAT_WINDOW));
+        assert!(!limit.allow(start + CHAT_WINDOW));
+        assert!(limit.allow(start + CHAT_WINDOW + second));
+    }
+
+    #[test]
+    fn shouts_wait_for_their_own_interval() {
+        let start = Instant::now();
+        let mut limit = ChatLimit::default();
+
+        assert!(limit.allow_shout(start));
+        assert!(!limit.allow_shout(start + SHOUT_INTERVAL - Duration::from_secs(1)));
+        assert!(limit.allow_shout(start + SHOUT_INTERVAL));
+    }
+}
@@ -0,0 +1,113 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    crafting::{
+        compose,
+        refine::{self, RefineOutcome},
+        CraftError, EventRng,
+    },
+    structs::{
+        inventory::{ItemError, Slot},
+        item::Item,
+    },
+    world::World,
+};
+
+impl World {
+    pub async fn refine_item(
+        &self,
+        id: u16,
+        material: Slot,
+        target: Slot,
+    ) -> Result<RefineOutcome, CraftError> {
+        let entities = self.entities.lock().await;
+        let data = self.data();
+
+        let mut transaction = entities.item_transaction(id)?;
+
+        let item = transaction.get(target)?;
+        let stone = transaction.get(material)?;
+
+        if item.is_empty() || stone.is_empty() {
+            return Err(ItemError::Empty.into());
+        }
+
+        if material == target {
+            return Err(ItemError::InvalidSlot.into());
+        }
+
+        refine::check(&item, &stone, &data.items)?;
+
+        let mut rng = EventRng::new();
+        let outcome = refine::outcome(item.refine(), rng.percent());
+
+        transaction.consume(material, 1)?;
+        transaction.modify(target, |item| refine::apply(item, outcome))?;
+
+        println!(
+            "crafting.refine: {} item {} at +{} with seed {}: {:?}",
+            transaction.character.name,
+            item.index,
+            item.refine(),
+            rng.seed,
+            outcome
+        );
+
+        self.commit_items(entities, vec![(id, transaction)]).await?;
+
+        Ok(outcome)
+    }
+
+    // every selected slot is used up whole, the result only comes out when the roll succeeds
+
+    pub async fn compose(&self, id: u16, slots: &[Slot]) -> Result<bool, CraftError> {
+        let entities = self.entities.lock().await;
+        let data = self.data();
+
+        let mut transaction = entities.item_transaction(id)?;
+        let mut materials = BTreeMap::new();
+
+        for (position, slot) in slots.iter().enumerate() {
+            if slots[..position].contains(slot) {
+                return Err(ItemError::InvalidSlot.into());
+            }
+
+            let item = transaction.get(*slot)?;
+
+            if item.is_empty() {
+                return Err(ItemError::Empty.into());
+            }
+
+            *materials.entry(item.index).or_insert(0) += item.amount();
+        }
+
+        let recipe = compose::find(&data.recipes, &materials).ok_or(CraftError::UnknownRecipe)?;
+
+        if transaction.character.coin < recipe.coin {
+            return Err(CraftError::NotEnoughCoin);
+        }
+
+        for slot in slots {
+            let amount = transaction.get(*slot)?.amount();
+            transaction.consume(*slot, amount)?;
+        }
+
+        transaction.character.coin -= recipe.coin;
+
+        let mut rng = EventRng::new();
+        let success = rng.percent() < recipe.chance;
+
+        if success {
+            transaction.give(Item::new(recipe.result), &data.items)?;
+        }
+
+        println!(
+            "crafting.compose: {} item {} with seed {}: {}",
+            transaction.character.name, recipe.result, rng.seed, success
+        );
+
+        self.commit_items(entities, vec![(id, transaction)]).await?;
+
+        Ok(success)
+    }
+}
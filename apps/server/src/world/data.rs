@@ -0,0 +1,367 @@
+use server_data::data_folder;
+use std::{fmt::Display, path::Path, sync::Arc};
+
+use crate::{
+    consts::{COMPOSE_FILE, NPC_GENERATOR_FILE, SCRIPTS_FOLDER},
+    crafting::compose::{self, Recipe},
+    scripting::Library,
+    structs::{
+        item::Item,
+        item_table::{self, ItemTable},
+    },
+    world::{
+        generators::{self, Generator},
+        map::Map,
+        skills::{self, Skill},
+        World,
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DataError {
+    Missing(String),
+    Invalid(String),
+}
+
+impl Display for DataError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DataError::Missing(file) => write!(f, "Arquivo {} não encontrado.", file),
+            DataError::Invalid(reason) => write!(f, "Dados inválidos: {}.", reason),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataKind {
+    Items,
+    Mobs,
+    Skills,
+    Recipes,
+    Scripts,
+}
+
+impl DataKind {
+    pub const ALL: [DataKind; 5] = [
+        DataKind::Items,
+        DataKind::Mobs,
+        DataKind::Skills,
+        DataKind::Recipes,
+        DataKind::Scripts,
+    ];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "items" => Some(DataKind::Items),
+            "mobs" => Some(DataKind::Mobs),
+            "skills" => Some(DataKind::Skills),
+            "recipes" => Some(DataKind::Recipes),
+            "scripts" => Some(DataKind::Scripts),
+            _ => None,
+        }
+    }
+
+    // nothing named means everything
+
+    pub fn parse_list(names: &[&str]) -> Option<Vec<Self>> {
+        if names.is_empty() {
+            return Some(Self::ALL.to_vec());
+        }
+
+        let mut kinds = Vec::new();
+
+        for kind in names.iter().map(|name| Self::parse(name)) {
+            let kind = kind?;
+
+            if !kinds.contains(&kind) {
+                kinds.push(kind);
+            }
+        }
+
+        Some(kinds)
+    }
+}
+
+impl Display for DataKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DataKind::Items => write!(f, "items"),
+            DataKind::Mobs => write!(f, "mobs"),
+            DataKind::Skills => write!(f, "skills"),
+            DataKind::Recipes => write!(f, "recipes"),
+            DataKind::Scripts => write!(f, "scripts"),
+        }
+    }
+}
+
+// everything read from the data folder that can be swapped while the server runs, the
+// mobs carry their drops so reloading them covers the drop tables too
+
+#[derive(Clone, Default)]
+pub struct GameData {
+    pub generators: Arc<Vec<Generator>>,
+    pub skills: Arc<Vec<Skill>>,
+    pub items: Arc<ItemTable>,
+    pub recipes: Arc<Vec<Recipe>>,
+}
+
+impl GameData {
+    pub fn new(
+        generators: Vec<Generator>,
+        skills: Vec<Skill>,
+        items: ItemTable,
+        recipes: Vec<Recipe>,
+    ) -> Self {
+        Self {
+            generators: Arc::new(generators),
+            skills: Arc::new(skills),
+            items: Arc::new(items),
+            recipes: Arc::new(recipes),
+        }
+    }
+
+    // only what points at something that changed is checked, so a reload of one part is not
+    // held back by the others
+
+    pub fn validate(
+        &self,
+        changed: &[DataKind],
+        map: &Map,
+        library: &Library,
+    ) -> Result<(), DataError> {
+        let items_changed = changed.contains(&DataKind::Items);
+
+        if items_changed || changed.contains(&DataKind::Recipes) {
+            for recipe in self.recipes.iter() {
+                let missing = std::iter::once(&recipe.result)
+                    .chain(recipe.materials.keys())
+                    .find(|index| self.items.get(**index).is_none());
+
+                if let Some(index) = missing {
+                    return Err(DataError::Invalid(format!(
+                        "{} usa o item {}",
+                        COMPOSE_FILE, index
+                    )));
+                }
+            }
+        }
+
+        if items_changed || changed.contains(&DataKind::Mobs) {
+            for generator in self.generators.iter() {
+                let missing = std::iter::once(&generator.leader)
+                    .chain(generator.follower.iter())
+                    .flat_map(|template| template.inventory.iter())
+                    .map(Item::from_struct)
+                    .find(|item| !item.is_empty() && self.items.get(item.index).is_none());
+
+                if let Some(item) = missing {
+                    return Err(DataError::Invalid(format!(
+                        "{} entrada {} usa o item {}",
+                        NPC_GENERATOR_FILE, generator.index, item.index
+                    )));
+                }
+            }
+        }
+
+        if changed.contains(&DataKind::Mobs) {
+            for generator in self.generators.iter() {
+                if generator
+                    .route
+                    .iter()
+                    .any(|waypoint| !map.contains(&waypoint.position))
+                {
+                    return Err(DataError::Invalid(format!(
+                        "{} entrada {} fora do mapa",
+                        NPC_GENERATOR_FILE, generator.index
+                    )));
+                }
+            }
+        }
+
+        if items_changed || changed.contains(&DataKind::Scripts) {
+            if let Some(index) = library
+                .item_indexes()
+                .find(|index| self.items.get(*index).is_none())
+            {
+                return Err(DataError::Invalid(format!(
+                    "{} tem um script para o item {}",
+                    SCRIPTS_FOLDER, index
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl World {
+    // a reload swaps the whole set at once, whoever took it before keeps working on the old
+    // one until they are done
+
+    pub fn data(&self) -> Arc<GameData> {
+        self.data.read().unwrap().clone()
+    }
+
+    // every part asked for is read and checked before anything is swapped, a single bad file
+    // leaves the server running on what it had, the mobs out on the map are taken away and
+    // come back from the new definitions
+
+    pub async fn reload(&self, kinds: &[DataKind]) -> Result<(), DataError> {
+        self.reload_from(&data_folder(), kinds).await
+    }
+
+    pub async fn reload_from(&self, folder: &Path, kinds: &[DataKind]) -> Result<(), DataError> {
+        let items = match kinds.contains(&DataKind::Items) {
+            true => Some(item_table::read(folder)?),
+            false => None,
+        };
+        let generators = match kinds.contains(&DataKind::Mobs) {
+            true => Some(generators::read(folder, true)?),
+            false => None,
+        };
+        let skills = match kinds.contains(&DataKind::Skills) {
+            true => Some(skills::read(folder, true)?),
+            false => None,
+        };
+        let recipes = match kinds.contains(&DataKind::Recipes) {
+            true => Some(compose::read(folder, true)?),
+            false => None,
+        };
+        let library = match kinds.contains(&DataKind::Scripts) {
+            true => Some(self.scripts.compile(folder, true)?),
+            false => None,
+        };
+
+        let now = self.clock.now();
+        let mut entities = self.entities.lock().await;
+
+        let mut data = GameData::clone(&self.data());
+
+        if let Some(items) = items {
+            data.items = Arc::new(items);
+        }
+
+        if let Some(generators) = generators {
+            data.generators = Arc::new(generators);
+        }
+
+        if let Some(skills) = skills {
+            data.skills = Arc::new(skills);
+        }
+
+        if let Some(recipes) = recipes {
+            data.recipes = Arc::new(recipes);
+        }
+
+        match &library {
+            Some(library) => data.validate(kinds, &self.map, library)?,
+            None => data.validate(kinds, &self.map, &self.scripts.library())?,
+        }
+
+        if kinds.contains(&DataKind::Mobs) {
+            entities.reset_spawners(&data.generators, now);
+        }
+
+        *self.data.write().unwrap() = Arc::new(data);
+
+        if let Some(library) = library {
+            self.scripts.swap(library);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, env, fs, path::PathBuf, process};
+
+    use super::*;
+    use crate::{clock::ManualClock, structs::item_table::ItemInfo};
+
+    const POTION: i16 = 400;
+    const STONE: i16 = 412;
+
+    // every test gets a folder of its own so they can run side by side
+
+    fn folder(name: &str) -> PathBuf {
+        let folder = env::temp_dir().join(format!("w2_reload_{}_{}", process::id(), name));
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(&folder).unwrap();
+        folder
+    }
+
+    fn world() -> World {
+        let items = ItemTable::new(HashMap::from([
+            (POTION, ItemInfo::default()),
+            (STONE, ItemInfo::default()),
+        ]));
+
+        World::new(
+            Map::default(),
+            Vec::new(),
+            Vec::new(),
+            items,
+            Vec::new(),
+            Vec::new(),
+            Arc::new(ManualClock::default()),
+        )
+    }
+
+    async fn reload_recipes(name: &str, content: Option<&str>) -> (World, Result<(), DataError>) {
+        let folder = folder(name);
+
+        if let Some(content) = content {
+            fs::write(folder.join(COMPOSE_FILE), content).unwrap();
+        }
+
+        let world = world();
+        let result = world.reload_from(&folder, &[DataKind::Recipes]).await;
+
+        let _ = fs::remove_dir_all(&folder);
+
+        (world, result)
+    }
+
+    #[tokio::test]
+    async fn a_good_file_is_swapped_in() {
+        let (world, result) = reload_recipes("good", Some("// potion\n400 50 0 412:2\n")).await;
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(world.data().recipes.len(), 1);
+        assert_eq!(world.data().recipes[0].result, POTION);
+    }
+
+    #[tokio::test]
+    async fn a_missing_file_changes_nothing() {
+        let (world, result) = reload_recipes("missing", None).await;
+
+        assert_eq!(result, Err(DataError::Missing(COMPOSE_FILE.to_string())));
+        assert!(world.data().recipes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn a_broken_line_changes_nothing() {
+        let (world, result) = reload_recipes("broken", Some("400 50 0 412:2\n400 50\n")).await;
+
+        assert_eq!(
+            result,
+            Err(DataError::Invalid(format!("{} linha 2", COMPOSE_FILE)))
+        );
+        assert!(world.data().recipes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn a_recipe_for_an_unknown_item_changes_nothing() {
+        let (world, result) = reload_recipes("unknown", Some("999 50 0 412:2\n")).await;
+
+        assert_eq!(
+            result,
+            Err(DataError::Invalid(format!(
+                "{} usa o item 999",
+                COMPOSE_FILE
+            )))
+        );
+        assert!(world.data().recipes.is_empty());
+    }
+}
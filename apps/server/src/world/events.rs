@@ -0,0 +1,714 @@
+use serde::Deserialize;
+use std::{collections::HashSet, fmt::Display, fs, path::PathBuf};
+
+use crate::{
+    consts::{
+        CASTLE_SENDER, EVENTS_FILE, EVENT_MAX_MINUTES, MAX_COIN, SECONDS_PER_DAY,
+        TOWER_CAPTURE_TIME, TOWER_RANGE, UNIX_EPOCH_WEEKDAY,
+    },
+    repository::guilds::{GuildError, GuildRank, Permission},
+    scripting::Hook,
+    statics::REPOSITORY,
+    structs::{item::Item, mail::Mail, position::Position},
+    world::{Entities, World},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventError {
+    Guild(GuildError),
+    NotFound,
+    Closed,
+    Registered,
+}
+
+impl Display for EventError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EventError::Guild(error) => write!(f, "{}", error),
+            EventError::NotFound => write!(f, "Evento não encontrado."),
+            EventError::Closed => write!(f, "As inscrições para este evento estão fechadas."),
+            EventError::Registered => write!(f, "Sua guilda já está inscrita neste evento."),
+        }
+    }
+}
+
+impl From<GuildError> for EventError {
+    fn from(error: GuildError) -> Self {
+        EventError::Guild(error)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct Area {
+    pub min: Position,
+    pub max: Position,
+}
+
+impl Area {
+    pub fn contains(&self, position: &Position) -> bool {
+        (self.min.x..=self.max.x).contains(&position.x)
+            && (self.min.y..=self.max.y).contains(&position.y)
+    }
+}
+
+// one entry of the events file, times are in utc and minutes, weekdays start on sunday and
+// leaving the weekday out runs the event every day, a siege with taxes collects them from the
+// trade done inside that area
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Schedule {
+    pub name: String,
+    #[serde(default)]
+    pub weekday: Option<u8>,
+    pub hour: u8,
+    #[serde(default)]
+    pub minute: u8,
+    #[serde(default)]
+    pub registration: u64,
+    pub duration: u64,
+    pub area: Area,
+    #[serde(default)]
+    pub towers: Vec<Position>,
+    #[serde(default)]
+    pub taxes: Option<Area>,
+}
+
+impl Schedule {
+    fn is_valid(&self) -> bool {
+        !self.name.trim().is_empty()
+            && self.weekday.is_none_or(|weekday| weekday < 7)
+            && self.hour < 24
+            && self.minute < 60
+            && self.registration <= EVENT_MAX_MINUTES
+            && (1..=EVENT_MAX_MINUTES).contains(&self.duration)
+    }
+
+    fn registration_secs(&self) -> u64 {
+        self.registration * 60
+    }
+
+    fn duration_secs(&self) -> u64 {
+        self.duration * 60
+    }
+
+    // the first start after the last one that was run whose battle is not over yet
+
+    pub fn upcoming(&self, after: u64, now: u64) -> Option<u64> {
+        let day = now / SECONDS_PER_DAY;
+        let time = self.hour as u64 * 60 * 60 + self.minute as u64 * 60;
+
+        (day.saturating_sub(1)..=day + 7)
+            .map(|day| day * SECONDS_PER_DAY + time)
+            .filter(|start| {
+                self.weekday
+                    .is_none_or(|weekday| weekday_of(*start) == weekday)
+            })
+            .find(|start| *start > after && start + self.duration_secs() > now)
+    }
+}
+
+fn weekday_of(timestamp: u64) -> u8 {
+    ((timestamp / SECONDS_PER_DAY + UNIX_EPOCH_WEEKDAY) % 7) as u8
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Idle,
+    Registration { starts_at: u64 },
+    Battle { ends_at: u64 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Registration,
+    Battle,
+    Cancelled,
+    End {
+        previous: Option<u16>,
+        owner: Option<u16>,
+    },
+}
+
+// a tower only changes hands once a single guild has held it alone for a while, anyone
+// else showing up starts the count over
+
+#[derive(Debug, Clone)]
+pub struct Tower {
+    pub position: Position,
+    pub owner: Option<u16>,
+    capture: Option<(u16, u64)>,
+}
+
+impl Tower {
+    pub fn new(position: Position) -> Self {
+        Self {
+            position,
+            owner: None,
+            capture: None,
+        }
+    }
+
+    pub fn hold(&mut self, guilds: &[u16], now: u64) -> Option<u16> {
+        let guild = match guilds {
+            [guild] if self.owner != Some(*guild) => *guild,
+            _ => {
+                self.capture = None;
+                return None;
+            }
+        };
+
+        match self.capture {
+            Some((holder, since)) if holder == guild => {
+                if now < since + TOWER_CAPTURE_TIME.as_secs() {
+                    return None;
+                }
+
+                self.owner = Some(guild);
+                self.capture = None;
+
+                Some(guild)
+            }
+            _ => {
+                self.capture = Some((guild, now));
+                None
+            }
+        }
+    }
+}
+
+pub struct Event {
+    pub schedule: Schedule,
+    pub phase: Phase,
+    pub owner: Option<u16>,
+    pub guilds: HashSet<u16>,
+    pub towers: Vec<Tower>,
+    occurrence: u64,
+    last: u64,
+}
+
+impl Event {
+    pub fn new(schedule: Schedule) -> Self {
+        let towers = schedule.towers.iter().copied().map(Tower::new).collect();
+
+        Self {
+            schedule,
+            phase: Phase::Idle,
+            owner: None,
+            guilds: HashSet::new(),
+            towers,
+            occurrence: 0,
+            last: 0,
+        }
+    }
+
+    pub fn is_siege(&self) -> bool {
+        !self.towers.is_empty()
+    }
+
+    pub fn in_battle(&self, position: &Position) -> bool {
+        matches!(self.phase, Phase::Battle { .. }) && self.schedule.area.contains(position)
+    }
+
+    // takes at most one step towards the phase the schedule is in, a server started in the
+    // middle of a battle joins it for whatever time is left
+
+    pub fn advance(&mut self, now: u64) -> Option<Change> {
+        match self.phase {
+            Phase::Idle => {
+                let start = self.schedule.upcoming(self.last, now)?;
+
+                if now >= start {
+                    return self.begin(start, start + self.schedule.duration_secs());
+                }
+
+                if self.schedule.registration > 0
+                    && now + self.schedule.registration_secs() >= start
+                {
+                    self.occurrence = start;
+                    self.phase = Phase::Registration { starts_at: start };
+                    return Some(Change::Registration);
+                }
+
+                None
+            }
+            Phase::Registration { starts_at } if now >= starts_at => {
+                self.begin(starts_at, starts_at + self.schedule.duration_secs())
+            }
+            Phase::Battle { ends_at } if now >= ends_at => Some(self.finish()),
+            _ => None,
+        }
+    }
+
+    // started by hand, the battle runs its whole duration from now
+
+    pub fn start(&mut self, now: u64) -> Option<Change> {
+        match self.phase {
+            Phase::Battle { .. } => None,
+            _ => self.begin(now, now + self.schedule.duration_secs()),
+        }
+    }
+
+    // a battle stopped by hand is settled as it stands, an open registration is dropped
+
+    pub fn stop(&mut self) -> Option<Change> {
+        match self.phase {
+            Phase::Idle => None,
+            Phase::Registration { .. } => {
+                self.phase = Phase::Idle;
+                self.last = self.occurrence;
+                self.guilds.clear();
+
+                Some(Change::Cancelled)
+            }
+            Phase::Battle { .. } => Some(self.finish()),
+        }
+    }
+
+    pub fn register(&mut self, guild: u16) -> Result<(), EventError> {
+        if !matches!(self.phase, Phase::Registration { .. }) {
+            return Err(EventError::Closed);
+        }
+
+        if !self.guilds.insert(guild) {
+            return Err(EventError::Registered);
+        }
+
+        Ok(())
+    }
+
+    // a guild that is gone loses whatever it held
+
+    pub fn forget(&mut self, guild: u16) {
+        if self.owner == Some(guild) {
+            self.owner = None;
+        }
+
+        self.guilds.remove(&guild);
+
+        for tower in self.towers.iter_mut() {
+            if tower.owner == Some(guild) {
+                tower.owner = None;
+            }
+        }
+    }
+
+    // the castle owner defends it, every tower starts out as theirs
+
+    fn begin(&mut self, occurrence: u64, ends_at: u64) -> Option<Change> {
+        self.occurrence = occurrence;
+        self.phase = Phase::Battle { ends_at };
+
+        if let Some(owner) = self.owner.filter(|_| self.is_siege()) {
+            self.guilds.insert(owner);
+        }
+
+        for tower in self.towers.iter_mut() {
+            *tower = Tower::new(tower.position);
+            tower.owner = self.owner;
+        }
+
+        Some(Change::Battle)
+    }
+
+    // the guild holding the most towers takes the castle, a tie leaves it with whoever had it
+
+    fn finish(&mut self) -> Change {
+        let previous = self.owner;
+
+        let mut held = self
+            .guilds
+            .iter()
+            .map(|guild| {
+                let towers = self
+                    .towers
+                    .iter()
+                    .filter(|tower| tower.owner == Some(*guild))
+                    .count();
+                (towers, *guild)
+            })
+            .filter(|(towers, _)| *towers > 0)
+            .collect::<Vec<_>>();
+
+        held.sort_by(|a, b| b.cmp(a));
+
+        match held.as_slice() {
+            [(first, _), (second, _), ..] if first == second => {}
+            [(_, guild), ..] => self.owner = Some(*guild),
+            [] => {}
+        }
+
+        self.phase = Phase::Idle;
+        self.last = self.occurrence;
+        self.guilds.clear();
+
+        for tower in self.towers.iter_mut() {
+            *tower = Tower::new(tower.position);
+        }
+
+        Change::End {
+            previous,
+            owner: self.owner,
+        }
+    }
+}
+
+pub fn load(folder: PathBuf) -> Vec<Schedule> {
+    let content = match fs::read_to_string(folder.join(EVENTS_FILE)) {
+        Ok(content) => content,
+        Err(_error) => {
+            println!("events.load: {} not found, no events", EVENTS_FILE);
+            return Vec::new();
+        }
+    };
+
+    let schedules = match serde_json::from_str::<Vec<Schedule>>(&content) {
+        Ok(schedules) => schedules,
+        Err(error) => {
+            println!("events.load.error: {}", error);
+            return Vec::new();
+        }
+    };
+
+    let schedules = schedules
+        .into_iter()
+        .filter(|schedule| match schedule.is_valid() {
+            true => true,
+            false => {
+                println!("events.load.error: {} skipped", schedule.name);
+                false
+            }
+        })
+        .collect::<Vec<_>>();
+
+    println!("Loaded {} events", schedules.len());
+
+    schedules
+}
+
+impl World {
+    pub async fn load_castles(&self) {
+        let names = self
+            .entities
+            .lock()
+            .await
+            .events
+            .iter()
+            .map(|event| event.schedule.name.clone())
+            .collect::<Vec<_>>();
+
+        let mut owners = Vec::new();
+
+        for name in names {
+            let owner = REPOSITORY.castle_owner(&name).await;
+            owners.push((name, owner));
+        }
+
+        let mut entities = self.entities.lock().await;
+
+        for (name, owner) in owners {
+            if let Some(event) = entities
+                .events
+                .iter_mut()
+                .find(|event| event.schedule.name == name)
+            {
+                event.owner = owner;
+            }
+        }
+    }
+
+    pub async fn register_event(&self, id: u16, index: usize) -> Result<(), EventError> {
+        let (guild_id, name) = Self::guild_member(&*self.entities.lock().await, id)?;
+
+        let guild = REPOSITORY
+            .get_guild(guild_id)
+            .await
+            .ok_or(GuildError::NotFound)?;
+
+        guild.check(&name, Permission::War)?;
+
+        let mut entities = self.entities.lock().await;
+
+        let event = entities.events.get_mut(index).ok_or(EventError::NotFound)?;
+        event.register(guild_id)?;
+
+        let message = format!("Sua guilda foi inscrita em {}.", event.schedule.name);
+        entities.send_guild_message(guild_id, &message);
+
+        Ok(())
+    }
+
+    pub async fn events(&self) -> Vec<(String, Phase, Option<u16>)> {
+        self.entities
+            .lock()
+            .await
+            .events
+            .iter()
+            .map(|event| (event.schedule.name.clone(), event.phase, event.owner))
+            .collect()
+    }
+
+    pub async fn start_event(&self, index: usize) -> bool {
+        let now = self.clock.timestamp();
+
+        let change = self
+            .entities
+            .lock()
+            .await
+            .events
+            .get_mut(index)
+            .and_then(|event| event.start(now));
+
+        match change {
+            Some(change) => self.announce_event(index, change).await,
+            None => return false,
+        }
+
+        true
+    }
+
+    pub async fn stop_event(&self, index: usize) -> bool {
+        let change = self
+            .entities
+            .lock()
+            .await
+            .events
+            .get_mut(index)
+            .and_then(|event| event.stop());
+
+        match change {
+            Some(change) => self.announce_event(index, change).await,
+            None => return false,
+        }
+
+        true
+    }
+
+    pub async fn process_events(&self) {
+        let now = self.clock.timestamp();
+        let mut entities = self.entities.lock().await;
+
+        let captured = entities
+            .process_towers(now)
+            .into_iter()
+            .map(|(index, tower, guild)| {
+                (entities.events[index].schedule.name.clone(), tower, guild)
+            })
+            .collect::<Vec<_>>();
+
+        let changes = entities
+            .events
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(index, event)| event.advance(now).map(|change| (index, change)))
+            .collect::<Vec<_>>();
+
+        drop(entities);
+
+        for (event, tower, guild) in captured {
+            let message = format!(
+                "A guilda {} conquistou a torre {} de {}.",
+                guild_name(guild).await,
+                tower + 1,
+                event
+            );
+
+            self.entities.lock().await.notice(&message);
+        }
+
+        for (index, change) in changes {
+            self.announce_event(index, change).await;
+        }
+    }
+
+    // every phase is told to the whole server, the end of a siege also settles who keeps the
+    // castle and pays out the taxes to whoever held it until now
+
+    async fn announce_event(&self, index: usize, change: Change) {
+        let (name, siege, taxes, area) = match self.entities.lock().await.events.get(index) {
+            Some(event) => (
+                event.schedule.name.clone(),
+                event.is_siege(),
+                event.schedule.taxes.is_some(),
+                event.schedule.area,
+            ),
+            None => return,
+        };
+
+        let message = match change {
+            Change::Registration => format!("As inscrições para {} estão abertas.", name),
+            Change::Battle => format!("{} começou!", name),
+            Change::Cancelled => format!("{} foi cancelado.", name),
+            Change::End { .. } if !siege => format!("{} terminou.", name),
+            Change::End { previous, owner } => {
+                if previous != owner && !REPOSITORY.set_castle_owner(&name, owner).await {
+                    println!("world.announce_event.error: owner of {} not saved", name);
+                }
+
+                if taxes {
+                    self.pay_taxes(&name, previous).await;
+                }
+
+                match owner {
+                    Some(owner) => format!(
+                        "{} terminou, a guilda {} domina o castelo.",
+                        name,
+                        guild_name(owner).await
+                    ),
+                    None => format!("{} terminou sem vencedor.", name),
+                }
+            }
+        };
+
+        self.entities.lock().await.notice(&message);
+
+        if let Change::End { owner, .. } = change {
+            self.reward_event(&name, siege, area, owner).await;
+        }
+    }
+
+    // the guild holding the castle is rewarded after a siege, anyone still standing in the
+    // area after any other event
+
+    async fn reward_event(&self, name: &str, siege: bool, area: Area, owner: Option<u16>) {
+        let hook = Hook::Reward(name.to_string());
+
+        if !self.scripts.has(&hook) {
+            return;
+        }
+
+        let winners = self
+            .entities
+            .lock()
+            .await
+            .players
+            .values()
+            .filter(|player| match siege {
+                true => owner.is_some() && player.guild == owner,
+                false => area.contains(&player.character.position),
+            })
+            .map(|player| player.id())
+            .collect::<Vec<_>>();
+
+        for id in winners {
+            let entities = self.entities.lock().await;
+
+            if let Err(error) = self.run_script(entities, id, &hook, None, None).await {
+                if let Some(player) = self.entities.lock().await.players.get(&id) {
+                    player.session.send_message(&error.to_string());
+                }
+            }
+        }
+    }
+
+    // the taxes go by mail to the leader of the guild, whatever does not fit in one mail is
+    // kept for the next time
+
+    async fn pay_taxes(&self, castle: &str, guild: Option<u16>) {
+        let guild = match guild {
+            Some(guild) => REPOSITORY.get_guild(guild).await,
+            None => None,
+        };
+
+        let leader = match guild.as_ref().and_then(|guild| {
+            guild
+                .members
+                .iter()
+                .find(|member| member.rank == GuildRank::Leader)
+        }) {
+            Some(leader) => leader.name.clone(),
+            None => return,
+        };
+
+        let username = match REPOSITORY.find_character_owner(&leader).await {
+            Some(username) => username,
+            None => return,
+        };
+
+        let coin = REPOSITORY.take_castle_taxes(castle, MAX_COIN as u64).await as u32;
+
+        if coin == 0 {
+            return;
+        }
+
+        let mail = Mail::new(
+            CASTLE_SENDER,
+            &format!("Impostos de {}", castle),
+            Item::default(),
+            coin,
+            self.clock.timestamp(),
+        );
+
+        if !self.send_mail(&username, mail).await {
+            println!("world.pay_taxes.error: {} coin kept for {}", coin, castle);
+
+            if !REPOSITORY.add_castle_taxes(castle, coin as u64).await {
+                println!("world.pay_taxes.error: {} coin lost for {}", coin, castle);
+            }
+        }
+    }
+
+    // the castle keeps its taxes in the repository, saving them does not hold up the world
+
+    pub fn collect_taxes(&self, entities: &Entities, id: u16, amount: u32) {
+        let castle = match entities.castle_of(id) {
+            Some(castle) if amount > 0 => castle,
+            _ => return,
+        };
+
+        tokio::spawn(async move {
+            if !REPOSITORY.add_castle_taxes(&castle, amount as u64).await {
+                println!(
+                    "world.collect_taxes.error: {} coin lost for {}",
+                    amount, castle
+                );
+            }
+        });
+    }
+}
+
+impl Entities {
+    // towers go to whichever registered guild stands next to them alone long enough, the
+    // event, tower and guild of every capture are handed back
+
+    fn process_towers(&mut self, now: u64) -> Vec<(usize, usize, u16)> {
+        let mut captured = Vec::new();
+
+        for (index, event) in self.events.iter_mut().enumerate() {
+            if !matches!(event.phase, Phase::Battle { .. }) {
+                continue;
+            }
+
+            for (tower_index, tower) in event.towers.iter_mut().enumerate() {
+                let mut guilds = self
+                    .grid
+                    .query(&tower.position, TOWER_RANGE)
+                    .iter()
+                    .filter_map(|id| self.players.get(id))
+                    .filter(|player| player.character.hp > 0)
+                    .filter(|player| {
+                        player.character.position.distance(&tower.position) <= TOWER_RANGE
+                    })
+                    .filter_map(|player| player.guild)
+                    .filter(|guild| event.guilds.contains(guild))
+                    .collect::<Vec<_>>();
+
+                guilds.sort();
+                guilds.dedup();
+
+                if let Some(guild) = tower.hold(&guilds, now) {
+                    captured.push((index, tower_index, guild));
+                }
+            }
+        }
+
+        captured
+    }
+}
+
+async fn guild_name(id: u16) -> String {
+    REPOSITORY
+        .get_guild(id)
+        .await
+        .map(|guild| guild.name)
+        .unwrap_or_default()
+}
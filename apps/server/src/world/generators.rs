@@ -0,0 +1,228 @@
+use encoding_rs::WINDOWS_1252;
+use packets::{serializer::deserialize, structs::mob::SMob};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{
+    consts::{NPC_FOLDER, NPC_GENERATOR_FILE},
+    structs::position::Position,
+    world::data::DataError,
+};
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Waypoint {
+    pub position: Position,
+    pub range: u16,
+    pub wait: u16,
+}
+
+#[derive(Clone)]
+pub struct Generator {
+    pub index: usize,
+    pub leader: SMob,
+    pub follower: Option<SMob>,
+    pub min_group: u16,
+    pub max_group: u16,
+    pub max_groups: u16,
+    pub respawn: Option<Duration>,
+    pub route_type: u8,
+    pub route: Vec<Waypoint>,
+}
+
+impl Generator {
+    pub fn start(&self) -> Waypoint {
+        self.route.first().copied().unwrap_or_default()
+    }
+}
+
+// raw entry as written in the generator file, everything is resolved once the file is read
+
+#[derive(Default)]
+struct GeneratorEntry {
+    index: usize,
+    values: HashMap<String, String>,
+}
+
+impl GeneratorEntry {
+    fn text(&self, key: &str) -> Option<&str> {
+        self.values
+            .get(&key.to_lowercase())
+            .map(|v| v.as_str())
+            .filter(|v| !v.is_empty())
+    }
+
+    fn number(&self, key: &str) -> i32 {
+        self.text(key).and_then(|v| v.parse().ok()).unwrap_or(0)
+    }
+
+    fn waypoint(&self, prefix: &str) -> Option<Waypoint> {
+        let x = self.number(&format!("{}X", prefix));
+        let y = self.number(&format!("{}Y", prefix));
+
+        if x <= 0 || y <= 0 {
+            return None;
+        }
+
+        Some(Waypoint {
+            position: Position::new(x as u16, y as u16),
+            range: self.number(&format!("{}Range", prefix)).max(0) as u16,
+            wait: self.number(&format!("{}Wait", prefix)).max(0) as u16,
+        })
+    }
+}
+
+pub fn load(folder: PathBuf) -> Vec<Generator> {
+    match read(&folder, false) {
+        Ok(generators) => generators,
+        Err(_error) => {
+            println!("generators.load: {} not found, no mobs", NPC_GENERATOR_FILE);
+            Vec::new()
+        }
+    }
+}
+
+// a strict read refuses the whole file over one entry that cannot be resolved, otherwise
+// the entry is only left out
+
+pub fn read(folder: &Path, strict: bool) -> Result<Vec<Generator>, DataError> {
+    let content = match fs::read(folder.join(NPC_GENERATOR_FILE)) {
+        Ok(buf) => WINDOWS_1252.decode(&buf).0.to_string(),
+        Err(_error) => return Err(DataError::Missing(NPC_GENERATOR_FILE.to_string())),
+    };
+
+    let mut templates = HashMap::new();
+    let mut generators = Vec::new();
+
+    for entry in parse(&content) {
+        match resolve(folder, &mut templates, &entry) {
+            Some(generator) => generators.push(generator),
+            None if strict => {
+                return Err(DataError::Invalid(format!(
+                    "{} entrada {}",
+                    NPC_GENERATOR_FILE, entry.index
+                )))
+            }
+            None => println!("generators.load.error: entry {} skipped", entry.index),
+        }
+    }
+
+    println!("Loaded {} generators", generators.len());
+
+    Ok(generators)
+}
+
+fn parse(content: &str) -> Vec<GeneratorEntry> {
+    let mut entries = Vec::new();
+    let mut current: Option<GeneratorEntry> = None;
+
+    for line in content.lines() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with("//") {
+            continue;
+        }
+
+        // every entry starts with a "# [0001]" like header
+
+        if let Some(header) = line.strip_prefix('#') {
+            if let Some(entry) = current.take() {
+                entries.push(entry);
+            }
+
+            let index = header
+                .trim()
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .parse()
+                .unwrap_or(entries.len());
+
+            current = Some(GeneratorEntry {
+                index,
+                values: HashMap::new(),
+            });
+
+            continue;
+        }
+
+        if let (Some(entry), Some((key, value))) = (current.as_mut(), line.split_once(':')) {
+            entry
+                .values
+                .insert(key.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+
+    if let Some(entry) = current.take() {
+        entries.push(entry);
+    }
+
+    entries
+}
+
+fn resolve(
+    folder: &Path,
+    templates: &mut HashMap<String, Option<SMob>>,
+    entry: &GeneratorEntry,
+) -> Option<Generator> {
+    let leader = load_template(folder, templates, entry.text("Leader")?)?;
+
+    let follower = match entry.text("Follower") {
+        Some(name) => Some(load_template(folder, templates, name)?),
+        None => None,
+    };
+
+    let mut route = vec![entry.waypoint("Start")?];
+
+    for segment in 1..=5 {
+        if let Some(waypoint) = entry.waypoint(&format!("Segment{}", segment)) {
+            route.push(waypoint);
+        }
+    }
+
+    if let Some(waypoint) = entry.waypoint("Dest") {
+        route.push(waypoint);
+    }
+
+    let minutes = entry.number("MinuteGenerate");
+
+    Some(Generator {
+        index: entry.index,
+        leader,
+        follower,
+        min_group: entry.number("MinGroup").max(0) as u16,
+        max_group: entry.number("MaxGroup").max(0) as u16,
+        max_groups: entry.number("MaxNumMob").max(1) as u16,
+        respawn: (minutes > 0).then(|| Duration::from_secs(minutes as u64 * 60)),
+        route_type: entry.number("RouteType").clamp(0, u8::MAX as i32) as u8,
+        route,
+    })
+}
+
+fn load_template(
+    folder: &Path,
+    templates: &mut HashMap<String, Option<SMob>>,
+    name: &str,
+) -> Option<SMob> {
+    if let Some(template) = templates.get(name) {
+        return *template;
+    }
+
+    let template = match fs::read(folder.join(NPC_FOLDER).join(name)) {
+        Ok(buf) => deserialize::<SMob>(&buf),
+        Err(_error) => None,
+    };
+
+    if template.is_none() {
+        println!(
+            "generators.load_template.error: {} not found or invalid",
+            name
+        );
+    }
+
+    templates.insert(name.to_string(), template);
+
+    template
+}
@@ -0,0 +1,221 @@
+use crate::{
+    consts::{GM_SPAWN_MAX, SUMMONED_GROUP},
+    repository::accounts::GmLevel,
+    statics::{REPOSITORY, SESSIONS},
+    structs::{inventory::ItemError, item::Item, position::Position},
+    world::{generators::Waypoint, spawner::random_position, World},
+};
+
+impl World {
+    pub async fn gm_level(&self, id: u16) -> GmLevel {
+        self.entities
+            .lock()
+            .await
+            .players
+            .get(&id)
+            .map_or(GmLevel::Player, |player| player.gm_level)
+    }
+
+    // characters already in the world pick up the new level without logging in again
+
+    pub async fn set_gm_level(&self, username: &str, level: GmLevel) -> bool {
+        let saved = REPOSITORY
+            .update_account(username, |account| {
+                account.gm_level = level;
+                Some(())
+            })
+            .await
+            .is_some();
+
+        if !saved {
+            return false;
+        }
+
+        let mut entities = self.entities.lock().await;
+
+        for player in entities
+            .players
+            .values_mut()
+            .filter(|player| player.username == username)
+        {
+            player.gm_level = level;
+        }
+
+        true
+    }
+
+    pub async fn notice(&self, message: &str) {
+        self.entities.lock().await.notice(message);
+    }
+
+    pub async fn kick(&self, name: &str) -> bool {
+        let entities = self.entities.lock().await;
+
+        let player = match entities
+            .find_player(name)
+            .and_then(|id| entities.players.get(&id))
+        {
+            Some(player) => player,
+            None => return false,
+        };
+
+        player
+            .session
+            .send_message("Você foi desconectado por um GM.");
+        player.session.close().await;
+
+        true
+    }
+
+    // no hours is for good, the account is dropped right away wherever it is
+
+    pub async fn ban(&self, name: &str, hours: u64) -> bool {
+        let username = match REPOSITORY.find_character_owner(name).await {
+            Some(username) => username,
+            None => return false,
+        };
+
+        let banned_until = match hours {
+            0 => u64::MAX,
+            _ => self
+                .clock
+                .timestamp()
+                .saturating_add(hours.saturating_mul(60 * 60)),
+        };
+
+        let saved = REPOSITORY
+            .update_account(&username, |account| {
+                account.banned_until = banned_until;
+                Some(())
+            })
+            .await
+            .is_some();
+
+        if !saved {
+            return false;
+        }
+
+        if let Some(session) = SESSIONS.find_by_account(&username).await {
+            session.send_message("Sua conta foi bloqueada.");
+            session.close().await;
+        }
+
+        true
+    }
+
+    pub async fn teleport(&self, id: u16, to: Position) -> bool {
+        let mut entities = self.entities.lock().await;
+
+        if !self.map.contains(&to)
+            || self.map.is_blocked(&to)
+            || !entities.players.contains_key(&id)
+        {
+            return false;
+        }
+
+        entities.teleport(id, to, self.clock.now());
+
+        true
+    }
+
+    pub async fn position_of(&self, name: &str) -> Option<Position> {
+        let entities = self.entities.lock().await;
+
+        let id = entities.find_player(name)?;
+
+        entities.grid.position(id)
+    }
+
+    pub async fn summon(&self, id: u16, name: &str) -> bool {
+        let mut entities = self.entities.lock().await;
+
+        let (target, to) = match (entities.find_player(name), entities.grid.position(id)) {
+            (Some(target), Some(to)) if target != id => (target, to),
+            _ => return false,
+        };
+
+        entities.teleport(target, to, self.clock.now());
+
+        true
+    }
+
+    // groups brought in by hand stand around whoever called them and do not come back once
+    // killed
+
+    pub async fn spawn_at(&self, id: u16, index: usize, count: u16) -> usize {
+        let now = self.clock.now();
+        let mut entities = self.entities.lock().await;
+        let data = self.data();
+
+        let (generator, position) = match (data.generators.get(index), entities.grid.position(id)) {
+            (Some(generator), Some(position)) => (generator, position),
+            _ => return 0,
+        };
+
+        let around = Waypoint {
+            position,
+            range: 2,
+            wait: 0,
+        };
+
+        let mut spawned = 0;
+
+        for _ in 0..count.min(GM_SPAWN_MAX) {
+            if let Some(leader_position) = random_position(&self.map, &around) {
+                spawned += entities.spawn_members(
+                    &self.map,
+                    index,
+                    SUMMONED_GROUP,
+                    generator,
+                    leader_position,
+                    now,
+                );
+            }
+        }
+
+        spawned
+    }
+
+    pub async fn create_item(&self, id: u16, index: i16, amount: u16) -> Result<(), ItemError> {
+        let entities = self.entities.lock().await;
+        let data = self.data();
+
+        let info = data.items.get(index).ok_or(ItemError::NotFound)?;
+
+        let mut item = Item::new(index);
+
+        if amount == 0 || amount > info.max_stack {
+            return Err(ItemError::InvalidAmount);
+        }
+
+        if amount > 1 && !item.set_amount(amount) {
+            return Err(ItemError::InvalidAmount);
+        }
+
+        let mut transaction = entities.item_transaction(id)?;
+        transaction.give(item, &data.items)?;
+
+        self.commit_items(entities, vec![(id, transaction)]).await?;
+
+        Ok(())
+    }
+
+    pub async fn set_level(&self, name: &str, level: u16) -> Option<u16> {
+        let mut entities = self.entities.lock().await;
+
+        let id = entities.find_player(name)?;
+        let player = entities.players.get_mut(&id)?;
+
+        let level = player.character.set_level(level);
+
+        player.session.send(&player.etc_packet());
+        player.session.send(&player.score_packet());
+        player
+            .session
+            .send_message(&format!("Seu nível agora é {}.", level));
+
+        entities.send_points(id);
+
+        Some(level)
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use crate::{
+    consts::{GRID_CELL_SIZE, MAP_SIZE},
+    structs::position::Position,
+};
+
+const GRID_SIZE: usize = MAP_SIZE / GRID_CELL_SIZE;
+
+// every entity (players and mobs) lives in exactly one cell, view queries only look at the
+// cells overlapping the requested range
+
+pub struct Grid {
+    cells: Vec<Vec<u16>>,
+    positions: HashMap<u16, Position>,
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Self {
+            cells: vec![Vec::new(); GRID_SIZE * GRID_SIZE],
+            positions: HashMap::new(),
+        }
+    }
+}
+
+impl Grid {
+    fn cell_index(position: &Position) -> usize {
+        let x = (position.x as usize / GRID_CELL_SIZE).min(GRID_SIZE - 1);
+        let y = (position.y as usize / GRID_CELL_SIZE).min(GRID_SIZE - 1);
+
+        y * GRID_SIZE + x
+    }
+
+    pub fn insert(&mut self, id: u16, position: Position) {
+        self.remove(id);
+
+        self.cells[Self::cell_index(&position)].push(id);
+        self.positions.insert(id, position);
+    }
+
+    pub fn remove(&mut self, id: u16) -> Option<Position> {
+        let position = self.positions.remove(&id)?;
+        let cell = &mut self.cells[Self::cell_index(&position)];
+
+        if let Some(index) = cell.iter().position(|other| *other == id) {
+            cell.swap_remove(index);
+        }
+
+        Some(position)
+    }
+
+    pub fn update(&mut self, id: u16, position: Position) {
+        match self.positions.get_mut(&id) {
+            Some(current) => {
+                let from = Self::cell_index(current);
+                let to = Self::cell_index(&position);
+
+                *current = position;
+
+                if from != to {
+                    let cell = &mut self.cells[from];
+
+                    if let Some(index) = cell.iter().position(|other| *other == id) {
+                        cell.swap_remove(index);
+                    }
+
+                    self.cells[to].push(id);
+                }
+            }
+            None => self.insert(id, position),
+        }
+    }
+
+    pub fn position(&self, id: u16) -> Option<Position> {
+        self.positions.get(&id).copied()
+    }
+
+    pub fn contains(&self, id: u16) -> bool {
+        self.positions.contains_key(&id)
+    }
+
+    pub fn query(&self, position: &Position, range: u16) -> Vec<u16> {
+        let range_cells = (range as usize).div_ceil(GRID_CELL_SIZE);
+
+        let center_x = (position.x as usize / GRID_CELL_SIZE).min(GRID_SIZE - 1);
+        let center_y = (position.y as usize / GRID_CELL_SIZE).min(GRID_SIZE - 1);
+
+        let min_x = center_x.saturating_sub(range_cells);
+        let min_y = center_y.saturating_sub(range_cells);
+        let max_x = (center_x + range_cells).min(GRID_SIZE - 1);
+        let max_y = (center_y + range_cells).min(GRID_SIZE - 1);
+
+        let mut ids = Vec::new();
+
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                for id in self.cells[y * GRID_SIZE + x].iter() {
+                    if let Some(other) = self.positions.get(id) {
+                        if position.distance(other) <= range {
+                            ids.push(*id);
+                        }
+                    }
+                }
+            }
+        }
+
+        ids
+    }
+}
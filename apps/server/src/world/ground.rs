@@ -0,0 +1,209 @@
+use packets::structs::packets::{p16f::P16F, p26e::P26E};
+use std::{
+    collections::{HashMap, HashSet},
+    time::Instant,
+};
+
+use crate::{
+    consts::{FIRST_GROUND_ITEM_ID, LAST_GROUND_ITEM_ID, VIEW_RANGE},
+    structs::{item::Item, position::Position},
+    world::grid::Grid,
+};
+
+#[derive(Debug, Clone)]
+pub struct GroundItem {
+    pub id: u16,
+    pub item: Item,
+    pub position: Position,
+    pub owner: Option<u16>,
+    pub owner_until: Instant,
+    pub expires_at: Instant,
+}
+
+impl GroundItem {
+    // the owner gets a head start, after that anyone can take it
+
+    pub fn can_pick(&self, id: u16, now: Instant) -> bool {
+        match self.owner {
+            Some(owner) => owner == id || now >= self.owner_until,
+            None => true,
+        }
+    }
+
+    pub fn spawn_packet(&self) -> P26E {
+        P26E::new(self.id, self.position.to_struct(), self.item.to_struct())
+    }
+
+    pub fn remove_packet(&self) -> P16F {
+        P16F::new(self.id)
+    }
+}
+
+// items lying around have their own ids and index, they are never confused with entities
+
+#[derive(Default)]
+pub struct Ground {
+    items: HashMap<u16, GroundItem>,
+    reserved: HashSet<u16>,
+    grid: Grid,
+    next_id: u16,
+}
+
+impl Ground {
+    fn next_id(&mut self) -> Option<u16> {
+        let count = LAST_GROUND_ITEM_ID - FIRST_GROUND_ITEM_ID + 1;
+
+        for offset in 0..count {
+            let id = FIRST_GROUND_ITEM_ID + (self.next_id + offset) % count;
+
+            if !self.items.contains_key(&id) && !self.reserved.contains(&id) {
+                self.next_id = (id - FIRST_GROUND_ITEM_ID + 1) % count;
+                return Some(id);
+            }
+        }
+
+        None
+    }
+
+    pub fn has_room(&self) -> bool {
+        self.items.len() + self.reserved.len()
+            < (LAST_GROUND_ITEM_ID - FIRST_GROUND_ITEM_ID + 1) as usize
+    }
+
+    // holds an id for an item that is still being saved out of the bags, nothing else can
+    // fill the ground meanwhile
+
+    pub fn reserve(&mut self) -> Option<u16> {
+        let id = self.next_id()?;
+        self.reserved.insert(id);
+        Some(id)
+    }
+
+    pub fn release(&mut self, id: u16) {
+        self.reserved.remove(&id);
+    }
+
+    pub fn add(
+        &mut self,
+        item: Item,
+        position: Position,
+        owner: Option<(u16, Instant)>,
+        expires_at: Instant,
+        reserved: Option<u16>,
+    ) -> Option<&GroundItem> {
+        let id = match reserved {
+            Some(id) if self.reserved.remove(&id) => id,
+            Some(_) => return None,
+            None => self.next_id()?,
+        };
+
+        let ground_item = GroundItem {
+            id,
+            item,
+            position,
+            owner: owner.map(|(owner, _)| owner),
+            owner_until: owner.map(|(_, until)| until).unwrap_or(expires_at),
+            expires_at,
+        };
+
+        self.grid.insert(id, position);
+        self.items.insert(id, ground_item);
+
+        self.items.get(&id)
+    }
+
+    pub fn get(&self, id: u16) -> Option<&GroundItem> {
+        self.items.get(&id)
+    }
+
+    pub fn remove(&mut self, id: u16) -> Option<GroundItem> {
+        self.grid.remove(id);
+        self.items.remove(&id)
+    }
+
+    // puts back an item taken off the ground, under a new id if its own went to another one
+
+    pub fn restore(&mut self, mut item: GroundItem) {
+        if self.items.contains_key(&item.id) {
+            match self.next_id() {
+                Some(id) => item.id = id,
+                None => return,
+            }
+        }
+
+        self.grid.insert(item.id, item.position);
+        self.items.insert(item.id, item);
+    }
+
+    pub fn in_view(&self, position: &Position) -> Vec<&GroundItem> {
+        self.grid
+            .query(position, VIEW_RANGE)
+            .iter()
+            .filter_map(|id| self.items.get(id))
+            .collect()
+    }
+
+    pub fn expire(&mut self, now: Instant) -> Vec<GroundItem> {
+        let expired: Vec<u16> = self
+            .items
+            .values()
+            .filter(|item| item.expires_at <= now)
+            .map(|item| item.id)
+            .collect();
+
+        expired
+            .into_iter()
+            .filter_map(|id| self.remove(id))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_reserved_spot_is_kept_for_its_item() {
+        let now = Instant::now();
+        let position = Position::new(2100, 2100);
+        let mut ground = Ground::default();
+
+        let reserved = ground.reserve().unwrap();
+
+        while ground.has_room() {
+            ground.add(Item::new(1), position, None, now, None).unwrap();
+        }
+
+        assert!(ground
+            .add(Item::new(1), position, None, now, None)
+            .is_none());
+
+        let item = ground
+            .add(Item::new(2), position, None, now, Some(reserved))
+            .unwrap();
+        assert_eq!(item.id, reserved);
+
+        assert!(ground
+            .add(Item::new(3), position, None, now, Some(reserved))
+            .is_none());
+    }
+
+    #[test]
+    fn a_released_spot_goes_back_to_the_ground() {
+        let mut ground = Ground::default();
+
+        let reserved = ground.reserve().unwrap();
+        ground.release(reserved);
+
+        assert!(ground
+            .add(
+                Item::new(1),
+                Position::new(2100, 2100),
+                None,
+                Instant::now(),
+                Some(reserved)
+            )
+            .is_none());
+        assert!(ground.reserve().is_some());
+    }
+}
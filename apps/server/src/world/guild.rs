@@ -0,0 +1,587 @@
+use packets::structs::{
+    guild::SGuildMember,
+    packets::{
+        p101::P101,
+        p3c9::{GUILD_MEMBERS_LEN, P3C9},
+        p3ca::P3CA,
+        p3ce::P3CE,
+        p3d0::{GUILD_MARK_LEN, P3D0},
+    },
+};
+
+use crate::{
+    consts::{GUILD_CREATE_FEE, GUILD_NOTICE_MAX_LEN},
+    repository::guilds::{Guild, GuildError, GuildRank, Permission},
+    statics::REPOSITORY,
+    structs::{
+        character::normalize_name,
+        inventory::{ItemError, Slot},
+    },
+    world::{Entities, World},
+};
+
+impl World {
+    pub async fn load_guild_wars(&self) {
+        let wars = REPOSITORY.guild_wars().await;
+
+        self.entities.lock().await.wars = wars
+            .into_iter()
+            .map(|(guild, other)| war_key(guild, other))
+            .collect();
+    }
+
+    pub fn guild_member(entities: &Entities, id: u16) -> Result<(u16, String), GuildError> {
+        let player = entities
+            .players
+            .get(&id)
+            .ok_or(GuildError::PlayerNotFound)?;
+        let guild = player.guild.ok_or(GuildError::NotInGuild)?;
+
+        Ok((guild, player.character.name.clone()))
+    }
+
+    // the guild is written first and removed again when the fee fails to save, the world is
+    // not held while either is written
+
+    pub async fn create_guild(&self, id: u16, name: &str) -> Result<(), GuildError> {
+        let mut entities = self.entities.lock().await;
+
+        let player = entities
+            .players
+            .get(&id)
+            .ok_or(GuildError::PlayerNotFound)?;
+        let leader = player.character.name.clone();
+
+        if player.guild.is_some() {
+            return Err(GuildError::InGuild);
+        }
+
+        let mut transaction = entities.item_transaction(id)?;
+
+        transaction.character.coin = transaction
+            .character
+            .coin
+            .checked_sub(GUILD_CREATE_FEE)
+            .ok_or(GuildError::NotEnoughCoin)?;
+
+        let pending = entities.reserve_items(vec![(id, transaction)])?;
+        drop(entities);
+
+        let guild = match REPOSITORY.create_guild(name, &leader).await {
+            Ok(guild) => guild,
+            Err(error) => {
+                drop(self.release_items(pending, false).await);
+                return Err(error);
+            }
+        };
+
+        if !pending.save().await {
+            if REPOSITORY.delete_guild(guild.id).await.is_none() {
+                println!(
+                    "world.create_guild.error: guild {} created without its fee",
+                    guild.id
+                );
+            }
+
+            drop(self.release_items(pending, false).await);
+            return Err(ItemError::Storage.into());
+        }
+
+        let mut entities = self.release_items(pending, true).await;
+
+        self.collect_taxes(&entities, id, GUILD_CREATE_FEE);
+
+        entities.set_guild(id, Some(guild.id));
+        entities.send_guild(&guild);
+
+        Ok(())
+    }
+
+    pub async fn invite_guild(&self, id: u16, target: u16) -> Result<(), GuildError> {
+        let (guild_id, name) = Self::guild_member(&*self.entities.lock().await, id)?;
+
+        let guild = REPOSITORY
+            .get_guild(guild_id)
+            .await
+            .ok_or(GuildError::NotFound)?;
+
+        guild.check(&name, Permission::Invite)?;
+
+        let mut entities = self.entities.lock().await;
+
+        let invited = entities
+            .players
+            .get(&target)
+            .ok_or(GuildError::PlayerNotFound)?;
+
+        if invited.guild.is_some() {
+            return Err(GuildError::InGuild);
+        }
+
+        entities.guild_invites.insert(target, guild_id);
+        entities.send_to(target, &P3CA::new(target, guild_id, id, &guild.name));
+
+        Ok(())
+    }
+
+    // newcomers start at the bottom and are promoted by the leader
+
+    pub async fn accept_guild(&self, id: u16, guild_id: u16) -> Result<(), GuildError> {
+        let mut entities = self.entities.lock().await;
+
+        if entities.guild_invites.get(&id) != Some(&guild_id) {
+            return Err(GuildError::NotInvited);
+        }
+
+        entities.guild_invites.remove(&id);
+
+        let player = entities
+            .players
+            .get(&id)
+            .ok_or(GuildError::PlayerNotFound)?;
+        let name = player.character.name.clone();
+
+        if player.guild.is_some() {
+            return Err(GuildError::InGuild);
+        }
+
+        drop(entities);
+
+        let guild = REPOSITORY
+            .update_guild(guild_id, |guild| {
+                guild.add(&name)?;
+                Ok(guild.clone())
+            })
+            .await?;
+
+        let mut entities = self.entities.lock().await;
+
+        entities.set_guild(id, Some(guild_id));
+        entities.send_guild(&guild);
+
+        Ok(())
+    }
+
+    // the last one out takes the guild with them, as long as nothing is left in its storage
+
+    pub async fn leave_guild(&self, id: u16) -> Result<(), GuildError> {
+        let (guild_id, name) = Self::guild_member(&*self.entities.lock().await, id)?;
+
+        let guild = REPOSITORY
+            .get_guild(guild_id)
+            .await
+            .ok_or(GuildError::NotFound)?;
+
+        if guild.members.len() > 1 {
+            let guild = REPOSITORY
+                .update_guild(guild_id, |guild| {
+                    guild.leave(&name)?;
+                    Ok(guild.clone())
+                })
+                .await?;
+
+            let mut entities = self.entities.lock().await;
+
+            entities.set_guild(id, None);
+            entities.send_guild(&guild);
+
+            return Ok(());
+        }
+
+        if !guild.is_storage_empty() {
+            return Err(GuildError::StorageNotEmpty);
+        }
+
+        REPOSITORY
+            .delete_guild(guild_id)
+            .await
+            .ok_or(GuildError::Storage)?;
+
+        let mut entities = self.entities.lock().await;
+
+        entities
+            .wars
+            .retain(|(guild, other)| *guild != guild_id && *other != guild_id);
+        entities
+            .guild_invites
+            .retain(|_, invited| *invited != guild_id);
+
+        for event in entities.events.iter_mut() {
+            event.forget(guild_id);
+        }
+
+        entities.set_guild(id, None);
+
+        Ok(())
+    }
+
+    pub async fn kick_from_guild(&self, id: u16, target: &str) -> Result<(), GuildError> {
+        let (guild_id, name) = Self::guild_member(&*self.entities.lock().await, id)?;
+
+        let guild = REPOSITORY
+            .update_guild(guild_id, |guild| {
+                guild.kick(&name, target)?;
+                Ok(guild.clone())
+            })
+            .await?;
+
+        let mut entities = self.entities.lock().await;
+
+        if let Some(kicked) = entities.find_player(target) {
+            entities.set_guild(kicked, None);
+            entities.send_to(kicked, &P101::new("Você foi expulso da guilda."));
+        }
+
+        entities.send_guild(&guild);
+
+        Ok(())
+    }
+
+    pub async fn set_guild_rank(
+        &self,
+        id: u16,
+        target: &str,
+        rank: GuildRank,
+    ) -> Result<(), GuildError> {
+        let (guild_id, name) = Self::guild_member(&*self.entities.lock().await, id)?;
+
+        let guild = REPOSITORY
+            .update_guild(guild_id, |guild| {
+                guild.set_rank(&name, target, rank)?;
+                Ok(guild.clone())
+            })
+            .await?;
+
+        self.entities.lock().await.send_guild(&guild);
+
+        Ok(())
+    }
+
+    pub async fn set_guild_notice(&self, id: u16, notice: &str) -> Result<(), GuildError> {
+        let (guild_id, name) = Self::guild_member(&*self.entities.lock().await, id)?;
+
+        let notice = notice
+            .trim()
+            .chars()
+            .take(GUILD_NOTICE_MAX_LEN)
+            .collect::<String>();
+
+        let guild = REPOSITORY
+            .update_guild(guild_id, |guild| {
+                guild.check(&name, Permission::Notice)?;
+                guild.notice = notice;
+                Ok(guild.clone())
+            })
+            .await?;
+
+        let entities = self.entities.lock().await;
+
+        entities.send_guild(&guild);
+
+        if !guild.notice.is_empty() {
+            entities.send_guild_message(guild_id, &format!("Aviso da guilda: {}", guild.notice));
+        }
+
+        Ok(())
+    }
+
+    // storage
+
+    pub async fn open_guild_storage(&self, id: u16) -> Result<(), GuildError> {
+        let (guild_id, _) = Self::guild_member(&*self.entities.lock().await, id)?;
+
+        let guild = REPOSITORY
+            .get_guild(guild_id)
+            .await
+            .ok_or(GuildError::NotFound)?;
+
+        let mut packet = P3CE::new(id);
+
+        for (slot, item) in packet.items.iter_mut().zip(&guild.storage) {
+            *slot = item.to_struct();
+        }
+
+        self.entities.lock().await.send_to(id, &packet);
+
+        Ok(())
+    }
+
+    // the guild storage is written first and the item goes back out of it when the bags fail
+    // to save
+
+    pub async fn deposit_guild(&self, id: u16, slot: Slot) -> Result<(), GuildError> {
+        let mut entities = self.entities.lock().await;
+
+        let (guild_id, name) = Self::guild_member(&entities, id)?;
+        let mut transaction = entities.item_transaction(id)?;
+
+        let item = transaction.take(slot)?;
+
+        let pending = entities.reserve_items(vec![(id, transaction)])?;
+        drop(entities);
+
+        let stored = REPOSITORY
+            .update_guild(guild_id, |guild| {
+                guild.check(&name, Permission::Deposit)?;
+                guild.deposit(item)
+            })
+            .await;
+
+        let stored = match stored {
+            Ok(stored) => stored,
+            Err(error) => {
+                drop(self.release_items(pending, false).await);
+                return Err(error);
+            }
+        };
+
+        let saved = pending.save().await;
+
+        if !saved
+            && REPOSITORY
+                .update_guild(guild_id, |guild| guild.withdraw(stored))
+                .await
+                .is_err()
+        {
+            println!(
+                "world.deposit_guild.error: guild {} kept an unsaved item",
+                guild_id
+            );
+        }
+
+        drop(self.release_items(pending, saved).await);
+
+        match saved {
+            true => self.open_guild_storage(id).await,
+            false => Err(ItemError::Storage.into()),
+        }
+    }
+
+    pub async fn withdraw_guild(&self, id: u16, stored: usize) -> Result<(), GuildError> {
+        let data = self.data();
+
+        let (guild_id, name) = Self::guild_member(&*self.entities.lock().await, id)?;
+
+        let guild = REPOSITORY
+            .get_guild(guild_id)
+            .await
+            .ok_or(GuildError::NotFound)?;
+
+        guild.check(&name, Permission::Withdraw)?;
+
+        let item = *guild.storage.get(stored).ok_or(ItemError::InvalidSlot)?;
+
+        if item.is_empty() {
+            return Err(ItemError::Empty.into());
+        }
+
+        let mut entities = self.entities.lock().await;
+
+        let mut transaction = entities.item_transaction(id)?;
+        transaction.give(item, &data.items)?;
+
+        let pending = entities.reserve_items(vec![(id, transaction)])?;
+        drop(entities);
+
+        let withdrawn = REPOSITORY
+            .update_guild(guild_id, |guild| {
+                guild.check(&name, Permission::Withdraw)?;
+
+                match guild.withdraw(stored)? == item {
+                    true => Ok(()),
+                    false => Err(ItemError::NotFound.into()),
+                }
+            })
+            .await;
+
+        if let Err(error) = withdrawn {
+            drop(self.release_items(pending, false).await);
+            return Err(error);
+        }
+
+        let saved = pending.save().await;
+
+        if !saved
+            && REPOSITORY
+                .update_guild(guild_id, |guild| guild.restore(stored, item))
+                .await
+                .is_err()
+        {
+            println!(
+                "world.withdraw_guild.error: guild {} lost an item",
+                guild_id
+            );
+        }
+
+        drop(self.release_items(pending, saved).await);
+
+        match saved {
+            true => self.open_guild_storage(id).await,
+            false => Err(ItemError::Storage.into()),
+        }
+    }
+
+    // marks
+
+    pub async fn set_guild_mark(&self, id: u16, mark: &[u8]) -> Result<(), GuildError> {
+        let (guild_id, name) = Self::guild_member(&*self.entities.lock().await, id)?;
+
+        if mark.len() != GUILD_MARK_LEN || mark.iter().all(|byte| *byte == 0) {
+            return Err(GuildError::InvalidMark);
+        }
+
+        REPOSITORY
+            .update_guild(guild_id, |guild| {
+                guild.check(&name, Permission::Mark)?;
+                guild.mark = mark.to_vec();
+                Ok(())
+            })
+            .await?;
+
+        let mut packet = P3D0::new(0, guild_id);
+        packet.mark.copy_from_slice(mark);
+
+        for player in self.entities.lock().await.players.values() {
+            packet.header.client_id = player.id();
+            player.session.send(&packet);
+        }
+
+        Ok(())
+    }
+
+    pub async fn send_guild_mark(&self, id: u16, guild_id: u16) -> Result<(), GuildError> {
+        let guild = REPOSITORY
+            .get_guild(guild_id)
+            .await
+            .ok_or(GuildError::NotFound)?;
+
+        if guild.mark.len() != GUILD_MARK_LEN {
+            return Ok(());
+        }
+
+        let mut packet = P3D0::new(id, guild_id);
+        packet.mark.copy_from_slice(&guild.mark);
+
+        self.entities.lock().await.send_to(id, &packet);
+
+        Ok(())
+    }
+
+    // wars
+
+    pub async fn set_guild_war(
+        &self,
+        id: u16,
+        enemy: u16,
+        declare: bool,
+    ) -> Result<(), GuildError> {
+        let (guild_id, name) = Self::guild_member(&*self.entities.lock().await, id)?;
+
+        let (guild, other) = REPOSITORY
+            .set_guild_war(guild_id, &name, enemy, declare)
+            .await?;
+
+        let mut entities = self.entities.lock().await;
+
+        let message = match declare {
+            true => {
+                entities.wars.insert(war_key(guild.id, other.id));
+                format!(
+                    "A guilda {} declarou guerra à guilda {}.",
+                    guild.name, other.name
+                )
+            }
+            false => {
+                entities.wars.remove(&war_key(guild.id, other.id));
+                format!("A guerra entre {} e {} terminou.", guild.name, other.name)
+            }
+        };
+
+        entities.send_guild_message(guild.id, &message);
+        entities.send_guild_message(other.id, &message);
+
+        Ok(())
+    }
+}
+
+// a war is the same whichever side looks at it
+
+pub fn war_key(guild: u16, other: u16) -> (u16, u16) {
+    (guild.min(other), guild.max(other))
+}
+
+impl Entities {
+    pub fn find_player(&self, name: &str) -> Option<u16> {
+        let name = normalize_name(name);
+
+        self.players
+            .values()
+            .find(|player| normalize_name(&player.character.name) == name)
+            .map(|player| player.id())
+    }
+
+    pub fn guild_members(&self, guild: u16) -> Vec<u16> {
+        self.players
+            .values()
+            .filter(|player| player.guild == Some(guild))
+            .map(|player| player.id())
+            .collect()
+    }
+
+    fn guild_packet(&self, id: u16, guild: &Guild) -> P3C9 {
+        let mut packet = P3C9::new(id, guild.id, &guild.name, &guild.notice);
+        packet.count = guild.members.len().min(u8::MAX as usize) as u8;
+
+        if let Some(member) = self
+            .players
+            .get(&id)
+            .and_then(|player| guild.member(&player.character.name))
+        {
+            packet.rank = member.rank.index();
+        }
+
+        for (position, member) in guild.members.iter().take(GUILD_MEMBERS_LEN).enumerate() {
+            let online = self.find_player(&member.name).is_some();
+            packet.members[position] = SGuildMember::new(&member.name, member.rank.index(), online);
+        }
+
+        packet
+    }
+
+    // whenever the guild changes everyone in it that is online gets the whole list again
+
+    pub fn send_guild(&self, guild: &Guild) {
+        for member in self.guild_members(guild.id) {
+            self.send_to(member, &self.guild_packet(member, guild));
+        }
+    }
+
+    pub fn send_guild_message(&self, guild: u16, message: &str) {
+        for member in self.guild_members(guild) {
+            if let Some(player) = self.players.get(&member) {
+                player.session.send_message(message);
+            }
+        }
+    }
+
+    // the mark over the player comes and goes with the guild, an empty list tells the client
+    // it is out
+
+    fn set_guild(&mut self, id: u16, guild: Option<u16>) {
+        let player = match self.players.get_mut(&id) {
+            Some(player) => player,
+            None => return,
+        };
+
+        player.guild = guild;
+
+        if guild.is_none() {
+            player.session.send(&P3C9::new(id, 0, "", ""));
+        }
+
+        let position = player.character.position;
+        let spawn = player.spawn_packet();
+
+        self.send_in_view(&position, &spawn, None);
+    }
+}
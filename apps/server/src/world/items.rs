@@ -0,0 +1,441 @@
+use packets::structs::packets::{p182::P182, p36b::P36B};
+use tokio::sync::MutexGuard;
+
+use crate::{
+    consts::{DROP_RANGE, MAX_COIN, PICKUP_RANGE},
+    statics::REPOSITORY,
+    structs::{
+        inventory::{ItemError, ItemTransaction, Slot, SlotType},
+        position::Position,
+    },
+    world::{loot::LootRule, trade::TradeError, Entities, World},
+};
+
+impl Entities {
+    pub fn item_transaction(&self, id: u16) -> Result<ItemTransaction, ItemError> {
+        if self.saving.contains(&id) {
+            return Err(ItemError::Busy);
+        }
+
+        let player = self.players.get(&id).ok_or(ItemError::NotFound)?;
+
+        Ok(ItemTransaction::new(
+            player.character.clone(),
+            player.storage.clone(),
+        ))
+    }
+
+    // the players stay out of any other item change until their save is back, so what gets
+    // saved is what ends up kept
+
+    pub fn reserve_items(
+        &mut self,
+        changes: Vec<(u16, ItemTransaction)>,
+    ) -> Result<PendingItems, ItemError> {
+        let mut saves = Vec::new();
+
+        for (id, transaction) in changes {
+            if self.saving.contains(&id) || saves.iter().any(|save: &PendingSave| save.id == id) {
+                return Err(ItemError::Busy);
+            }
+
+            let player = self.players.get(&id).ok_or(ItemError::NotFound)?;
+
+            saves.push(PendingSave {
+                id,
+                username: player.username.clone(),
+                slot: player.slot,
+                original: ItemTransaction::new(player.character.clone(), player.storage.clone()),
+                transaction,
+            });
+        }
+
+        self.saving.extend(saves.iter().map(|save| save.id));
+
+        Ok(PendingItems { saves })
+    }
+
+    pub fn release_items(&mut self, pending: &PendingItems, saved: bool) {
+        for save in &pending.saves {
+            self.saving.remove(&save.id);
+            self.apply_items(save.id, &save.original, &save.transaction, saved);
+        }
+    }
+
+    // only what a transaction can touch is taken from it, the rest of the player went on while
+    // it was being saved and coin that came in meanwhile is kept on top
+
+    fn apply_items(
+        &mut self,
+        id: u16,
+        original: &ItemTransaction,
+        transaction: &ItemTransaction,
+        saved: bool,
+    ) {
+        let player = match self.players.get_mut(&id) {
+            Some(player) => player,
+            None => return,
+        };
+
+        let coin_changed = original.character.coin != transaction.character.coin;
+
+        if saved {
+            let coin = player.character.coin as i64 + transaction.character.coin as i64
+                - original.character.coin as i64;
+
+            player.character.coin = coin.clamp(0, MAX_COIN as i64) as u32;
+            player.character.equip = transaction.character.equip.clone();
+            player.character.inventory = transaction.character.inventory.clone();
+            player.character.quests = transaction.character.quests.clone();
+            player.storage = transaction.storage.clone();
+        }
+
+        let kept = ItemTransaction::new(player.character.clone(), player.storage.clone());
+
+        for slot in transaction.changes() {
+            if let Ok(item) = kept.get(*slot) {
+                player.session.send(&P182::new(
+                    id,
+                    slot.kind.index(),
+                    slot.index as i16,
+                    item.to_struct(),
+                ));
+            }
+        }
+
+        if !saved {
+            return;
+        }
+
+        // whatever the other side was shown may be gone now, so a running trade ends here
+
+        if self.trades.get(id).is_some() {
+            self.cancel_trade(id);
+
+            if let Some(player) = self.players.get(&id) {
+                player
+                    .session
+                    .send_message(&TradeError::Changed.to_string());
+            }
+        }
+
+        let player = match self.players.get_mut(&id) {
+            Some(player) => player,
+            None => return,
+        };
+
+        if coin_changed {
+            player.session.send(&player.etc_packet());
+        }
+
+        if transaction
+            .changes()
+            .iter()
+            .any(|slot| slot.kind == SlotType::Equip)
+        {
+            player.session.send(&player.score_packet());
+
+            let position = player.character.position;
+            let equip = P36B::new(id, player.character.equip_indexes());
+
+            self.send_in_view(&position, &equip, Some(id));
+        }
+    }
+}
+
+impl World {
+    pub async fn commit_items(
+        &self,
+        entities: MutexGuard<'_, Entities>,
+        changes: Vec<(u16, ItemTransaction)>,
+    ) -> Result<(), ItemError> {
+        drop(self.commit_and_relock(entities, changes).await?);
+
+        Ok(())
+    }
+
+    // the save runs with the world unlocked, it comes back locked again with the changes kept
+    // or dropped
+
+    pub async fn commit_and_relock<'a>(
+        &'a self,
+        mut entities: MutexGuard<'a, Entities>,
+        changes: Vec<(u16, ItemTransaction)>,
+    ) -> Result<MutexGuard<'a, Entities>, ItemError> {
+        let pending = entities.reserve_items(changes)?;
+        drop(entities);
+
+        let saved = pending.save().await;
+        let entities = self.release_items(pending, saved).await;
+
+        match saved {
+            true => Ok(entities),
+            false => Err(ItemError::Storage),
+        }
+    }
+
+    pub async fn release_items(
+        &self,
+        pending: PendingItems,
+        saved: bool,
+    ) -> MutexGuard<'_, Entities> {
+        let mut entities = self.entities.lock().await;
+
+        entities.release_items(&pending, saved);
+        self.saved.notify_waiters();
+
+        entities
+    }
+
+    pub async fn move_item(&self, id: u16, from: Slot, to: Slot) -> Result<(), ItemError> {
+        let entities = self.entities.lock().await;
+        let data = self.data();
+
+        let mut transaction = entities.item_transaction(id)?;
+        transaction.move_item(from, to, &data.items)?;
+
+        self.commit_items(entities, vec![(id, transaction)]).await?;
+
+        Ok(())
+    }
+
+    pub async fn split_item(&self, id: u16, slot: Slot, amount: u16) -> Result<(), ItemError> {
+        let entities = self.entities.lock().await;
+        let data = self.data();
+
+        let mut transaction = entities.item_transaction(id)?;
+        transaction.split(slot, amount, &data.items)?;
+
+        self.commit_items(entities, vec![(id, transaction)]).await?;
+
+        Ok(())
+    }
+
+    pub async fn drop_item(
+        &self,
+        id: u16,
+        slot: Slot,
+        position: Position,
+    ) -> Result<(), ItemError> {
+        let mut entities = self.entities.lock().await;
+
+        let mut transaction = entities.item_transaction(id)?;
+
+        if transaction.character.position.distance(&position) > DROP_RANGE
+            || !self.map.contains(&position)
+            || self.map.is_blocked(&position)
+        {
+            return Err(ItemError::TooFar);
+        }
+
+        let item = transaction.take(slot)?;
+
+        // the spot on the ground is held before the save so it is still there once the item
+        // has left the bags, and given back when the save fails
+
+        let reserved = entities.ground.reserve().ok_or(ItemError::NoSpace)?;
+
+        match self
+            .commit_and_relock(entities, vec![(id, transaction)])
+            .await
+        {
+            Ok(mut entities) => {
+                entities.drop_item(item, position, None, Some(reserved), self.clock.now());
+            }
+            Err(error) => {
+                self.entities.lock().await.ground.release(reserved);
+                return Err(error);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn pickup_item(&self, id: u16, item_id: u16) -> Result<(), ItemError> {
+        let mut entities = self.entities.lock().await;
+        let data = self.data();
+
+        let mut transaction = entities.item_transaction(id)?;
+
+        let ground_item = entities.ground.get(item_id).ok_or(ItemError::NotFound)?;
+
+        if transaction
+            .character
+            .position
+            .distance(&ground_item.position)
+            > PICKUP_RANGE
+        {
+            return Err(ItemError::TooFar);
+        }
+
+        let shared = ground_item.owner.is_some_and(|owner| {
+            entities.parties.together(id, owner)
+                && entities
+                    .parties
+                    .get(owner)
+                    .is_some_and(|party| party.loot == LootRule::FreeForAll)
+        });
+
+        if !shared && !ground_item.can_pick(id, self.clock.now()) {
+            return Err(ItemError::NotOwner);
+        }
+
+        transaction.give(ground_item.item, &data.items)?;
+
+        // it leaves the ground before the save so nobody else picks it up meanwhile, and goes
+        // back when the save fails
+
+        let ground_item = entities.ground.remove(item_id).ok_or(ItemError::NotFound)?;
+
+        match self
+            .commit_and_relock(entities, vec![(id, transaction)])
+            .await
+        {
+            Ok(entities) => {
+                entities.send_in_view(&ground_item.position, &ground_item.remove_packet(), None)
+            }
+            Err(error) => {
+                self.entities.lock().await.ground.restore(ground_item);
+                return Err(error);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// item changes on their way to the disk, taken out under the lock and saved without it
+
+pub struct PendingItems {
+    saves: Vec<PendingSave>,
+}
+
+pub struct PendingSave {
+    pub id: u16,
+    pub username: String,
+    pub slot: usize,
+    pub original: ItemTransaction,
+    pub transaction: ItemTransaction,
+}
+
+impl PendingItems {
+    // everyone is saved or no one is, when a later save fails the earlier ones are written
+    // back as they were
+
+    pub async fn save(&self) -> bool {
+        for (position, save) in self.saves.iter().enumerate() {
+            if save.write(&save.transaction).await {
+                continue;
+            }
+
+            for earlier in &self.saves[..position] {
+                if !earlier.write(&earlier.original).await {
+                    println!(
+                        "world.save_items.error: items of {} left half saved",
+                        earlier.username
+                    );
+                }
+            }
+
+            return false;
+        }
+
+        true
+    }
+
+    pub fn first(&self) -> Option<&PendingSave> {
+        self.saves.first()
+    }
+}
+
+impl PendingSave {
+    async fn write(&self, transaction: &ItemTransaction) -> bool {
+        REPOSITORY
+            .save_items(
+                &self.username,
+                self.slot,
+                &transaction.character,
+                &transaction.storage,
+            )
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+    use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+
+    use super::*;
+    use crate::{
+        session::Session,
+        structs::{
+            character::Character, class::Class, item::Item, item_table::ItemTable, storage::Storage,
+        },
+        world::player::Player,
+    };
+
+    fn player(id: u16) -> (Player, UnboundedReceiver<Vec<u8>>) {
+        let (sender, receiver) = unbounded_channel();
+
+        let player = Player::new(
+            Session::new(id, "127.0.0.1".to_string(), sender),
+            format!("account{}", id),
+            0,
+            Character::new(format!("Player{}", id), Class::TransKnight),
+            Storage::default(),
+            Instant::now(),
+        );
+
+        (player, receiver)
+    }
+
+    #[test]
+    fn a_player_being_saved_is_kept_out_of_other_item_changes() {
+        let mut entities = Entities::default();
+        let (player, _receiver) = player(1);
+        entities.players.insert(1, player);
+
+        let mut transaction = entities.item_transaction(1).unwrap();
+        transaction
+            .give(Item::new(800), &ItemTable::default())
+            .unwrap();
+
+        let pending = entities.reserve_items(vec![(1, transaction)]).unwrap();
+
+        let other = ItemTransaction::new(
+            entities.players[&1].character.clone(),
+            entities.players[&1].storage.clone(),
+        );
+
+        assert_eq!(entities.item_transaction(1).err(), Some(ItemError::Busy));
+        assert_eq!(
+            entities.reserve_items(vec![(1, other)]).err(),
+            Some(ItemError::Busy)
+        );
+        assert!(entities.players[&1].character.inventory[0].is_empty());
+
+        entities.release_items(&pending, true);
+
+        assert_eq!(entities.players[&1].character.inventory[0], Item::new(800));
+        assert!(entities.item_transaction(1).is_ok());
+    }
+
+    #[test]
+    fn a_failed_save_keeps_the_items_as_they_were() {
+        let mut entities = Entities::default();
+        let (player, _receiver) = player(1);
+        entities.players.insert(1, player);
+
+        let mut transaction = entities.item_transaction(1).unwrap();
+        transaction
+            .give(Item::new(800), &ItemTable::default())
+            .unwrap();
+
+        let pending = entities.reserve_items(vec![(1, transaction)]).unwrap();
+        entities.release_items(&pending, false);
+
+        assert!(entities.players[&1].character.inventory[0].is_empty());
+        assert!(entities.item_transaction(1).is_ok());
+    }
+}
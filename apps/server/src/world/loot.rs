@@ -0,0 +1,179 @@
+use packets::structs::mob::SMob;
+use rand::{seq::SliceRandom, Rng};
+use std::{
+    fmt::Display,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use crate::{
+    combat::experience::Contribution,
+    consts::{DROP_CHANCE_SCALE, DROP_TIER_CHANCES, DROP_TIER_SIZE},
+    structs::item::Item,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LootRule {
+    FreeForAll,
+    #[default]
+    TopDamage,
+    RoundRobin,
+}
+
+impl LootRule {
+    pub fn from_index(index: u16) -> Option<Self> {
+        match index {
+            0 => Some(LootRule::FreeForAll),
+            1 => Some(LootRule::TopDamage),
+            2 => Some(LootRule::RoundRobin),
+            _ => None,
+        }
+    }
+}
+
+impl Display for LootRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LootRule::FreeForAll => write!(f, "livre para o grupo"),
+            LootRule::TopDamage => write!(f, "maior dano"),
+            LootRule::RoundRobin => write!(f, "revezamento"),
+        }
+    }
+}
+
+// percent multipliers changed while the server runs, events just turn them up
+
+pub struct Rates {
+    drop: AtomicU32,
+    coin: AtomicU32,
+}
+
+impl Rates {
+    pub fn new(drop: u32, coin: u32) -> Self {
+        Self {
+            drop: AtomicU32::new(drop),
+            coin: AtomicU32::new(coin),
+        }
+    }
+
+    pub fn drop(&self) -> u32 {
+        self.drop.load(Ordering::Relaxed)
+    }
+
+    pub fn coin(&self) -> u32 {
+        self.coin.load(Ordering::Relaxed)
+    }
+
+    pub fn set_drop(&self, percent: u32) {
+        self.drop.store(percent, Ordering::Relaxed);
+    }
+
+    pub fn set_coin(&self, percent: u32) {
+        self.coin.store(percent, Ordering::Relaxed);
+    }
+}
+
+impl Default for Rates {
+    fn default() -> Self {
+        Self::new(100, 100)
+    }
+}
+
+// what a mob carries is what it can drop, the position in the carry sets how rare it is
+
+#[derive(Debug, Clone, Default)]
+pub struct LootTable {
+    tiers: Vec<Vec<Item>>,
+}
+
+impl LootTable {
+    pub fn from_template(template: &SMob) -> Self {
+        let tiers = template
+            .inventory
+            .chunks(DROP_TIER_SIZE)
+            .take(DROP_TIER_CHANCES.len())
+            .map(|tier| {
+                tier.iter()
+                    .map(Item::from_struct)
+                    .filter(|item| !item.is_empty())
+                    .collect()
+            })
+            .collect();
+
+        Self { tiers }
+    }
+
+    // every tier rolls once and gives at most one of its items
+
+    pub fn roll<R: Rng>(&self, rate: u32, rng: &mut R) -> Vec<Item> {
+        self.tiers
+            .iter()
+            .zip(DROP_TIER_CHANCES)
+            .filter(|(tier, _)| !tier.is_empty())
+            .filter_map(|(tier, chance)| {
+                let chance = (chance as u64 * rate as u64 / 100).min(DROP_CHANCE_SCALE as u64);
+
+                match rng.gen_range(0..DROP_CHANCE_SCALE as u64) < chance {
+                    true => tier.choose(rng).copied(),
+                    false => None,
+                }
+            })
+            .collect()
+    }
+}
+
+// who gets the first pick of the drops, turn is only used to rotate round robin
+
+pub fn owner(rule: LootRule, contributions: &[Contribution], turn: usize) -> Option<u16> {
+    match rule {
+        LootRule::FreeForAll => None,
+        LootRule::TopDamage => contributions
+            .iter()
+            .max_by_key(|contribution| contribution.damage)
+            .map(|contribution| contribution.id),
+        LootRule::RoundRobin => {
+            let mut ids: Vec<u16> = contributions.iter().map(|c| c.id).collect();
+            ids.sort_unstable();
+
+            match ids.is_empty() {
+                true => None,
+                false => Some(ids[turn % ids.len()]),
+            }
+        }
+    }
+}
+
+pub fn scale(amount: i64, rate: u32) -> u32 {
+    (amount.max(0) as u64 * rate as u64 / 100).min(u32::MAX as u64) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(id: u16, damage: i32) -> Contribution {
+        Contribution {
+            id,
+            level: 1,
+            damage,
+        }
+    }
+
+    #[test]
+    fn each_rule_picks_its_owner() {
+        let contributions = [hit(3, 50), hit(1, 200), hit(2, 100)];
+
+        assert_eq!(owner(LootRule::FreeForAll, &contributions, 0), None);
+        assert_eq!(owner(LootRule::TopDamage, &contributions, 0), Some(1));
+        assert_eq!(owner(LootRule::RoundRobin, &contributions, 0), Some(1));
+        assert_eq!(owner(LootRule::RoundRobin, &contributions, 1), Some(2));
+        assert_eq!(owner(LootRule::RoundRobin, &contributions, 5), Some(3));
+        assert_eq!(owner(LootRule::RoundRobin, &[], 0), None);
+    }
+
+    #[test]
+    fn rules_come_from_the_client_index() {
+        assert_eq!(LootRule::from_index(0), Some(LootRule::FreeForAll));
+        assert_eq!(LootRule::from_index(2), Some(LootRule::RoundRobin));
+        assert_eq!(LootRule::from_index(3), None);
+    }
+}
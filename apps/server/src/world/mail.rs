@@ -0,0 +1,110 @@
+use packets::structs::packets::p3c5::{MAILBOX_LEN, P3C5};
+
+use crate::{
+    consts::MAX_COIN,
+    statics::REPOSITORY,
+    structs::{
+        inventory::ItemError,
+        mail::{Mail, MailError},
+    },
+    world::{Entities, World},
+};
+
+impl World {
+    pub async fn send_mail(&self, username: &str, mail: Mail) -> bool {
+        if !REPOSITORY.send_mail(username, mail).await {
+            return false;
+        }
+
+        self.send_mailbox(username).await;
+
+        true
+    }
+
+    async fn send_mailbox(&self, username: &str) {
+        let mailbox = match REPOSITORY.get_account(username).await {
+            Some(account) => account.mail,
+            None => return,
+        };
+
+        self.entities.lock().await.send_mailbox(username, &mailbox);
+    }
+
+    // the mail leaves the mailbox in the same write that saves the bags
+
+    pub async fn claim_mail(&self, id: u16, mail_id: u32) -> Result<(), MailError> {
+        let data = self.data();
+
+        let username = match self.entities.lock().await.players.get(&id) {
+            Some(player) => player.username.clone(),
+            None => return Err(ItemError::NotFound.into()),
+        };
+
+        let mail = REPOSITORY
+            .get_account(&username)
+            .await
+            .and_then(|account| account.mail.into_iter().find(|mail| mail.id == mail_id))
+            .ok_or(MailError::NotFound)?;
+
+        let mut entities = self.entities.lock().await;
+        let mut transaction = entities.item_transaction(id)?;
+
+        if !mail.item.is_empty() {
+            transaction.give(mail.item, &data.items)?;
+        }
+
+        transaction.character.coin = transaction
+            .character
+            .coin
+            .checked_add(mail.coin)
+            .filter(|coin| *coin <= MAX_COIN)
+            .ok_or(MailError::CoinLimit)?;
+
+        let pending = entities.reserve_items(vec![(id, transaction)])?;
+        drop(entities);
+
+        let saved = match pending.first() {
+            Some(save) => {
+                REPOSITORY
+                    .claim_mail(
+                        &save.username,
+                        save.slot,
+                        &save.transaction.character,
+                        &save.transaction.storage,
+                        mail_id,
+                    )
+                    .await
+            }
+            None => false,
+        };
+
+        drop(self.release_items(pending, saved).await);
+
+        if !saved {
+            return Err(ItemError::Storage.into());
+        }
+
+        self.send_mailbox(&username).await;
+
+        Ok(())
+    }
+}
+
+impl Entities {
+    pub fn send_mailbox(&self, username: &str, mailbox: &[Mail]) {
+        for player in self
+            .players
+            .values()
+            .filter(|player| player.username == username)
+        {
+            let count = mailbox.len().min(u16::MAX as usize) as u16;
+            let mut packet = P3C5::new(player.id(), count);
+
+            for (position, mail) in mailbox.iter().take(MAILBOX_LEN).enumerate() {
+                packet.mails[position] = mail.to_struct();
+            }
+
+            player.session.send(&packet);
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use std::{fs, path::PathBuf};
+
+use crate::{
+    consts::{
+        ATTRIBUTE_MAP_FILE, ATTRIBUTE_MAP_SIZE, HEIGHT_MAP_FILE, MAP_ATTRIBUTE_BLOCKED,
+        MAP_ATTRIBUTE_PVP, MAP_ATTRIBUTE_SAFE, MAP_ATTRIBUTE_VILLAGE, MAP_FOLDER, MAP_SIZE,
+    },
+    structs::position::Position,
+};
+
+pub struct Map {
+    heights: Vec<u8>,
+    attributes: Vec<u8>,
+}
+
+impl Default for Map {
+    fn default() -> Self {
+        Self {
+            heights: vec![0; MAP_SIZE * MAP_SIZE],
+            attributes: vec![0; ATTRIBUTE_MAP_SIZE * ATTRIBUTE_MAP_SIZE],
+        }
+    }
+}
+
+impl Map {
+    pub fn load(folder: PathBuf) -> Self {
+        let folder = folder.join(MAP_FOLDER);
+        let mut map = Self::default();
+
+        match Self::load_file(folder.join(HEIGHT_MAP_FILE), MAP_SIZE * MAP_SIZE) {
+            Some(heights) => map.heights = heights,
+            None => println!("map.load: {} not found, using a flat map", HEIGHT_MAP_FILE),
+        };
+
+        match Self::load_file(
+            folder.join(ATTRIBUTE_MAP_FILE),
+            ATTRIBUTE_MAP_SIZE * ATTRIBUTE_MAP_SIZE,
+        ) {
+            Some(attributes) => map.attributes = attributes,
+            None => println!(
+                "map.load: {} not found, using an open map",
+                ATTRIBUTE_MAP_FILE
+            ),
+        };
+
+        map
+    }
+
+    fn load_file(file: PathBuf, size: usize) -> Option<Vec<u8>> {
+        match fs::read(&file) {
+            Ok(buf) if buf.len() == size => Some(buf),
+            Ok(buf) => {
+                println!(
+                    "map.load_file.error: {:?} has {} bytes, expected {}",
+                    file,
+                    buf.len(),
+                    size
+                );
+                None
+            }
+            Err(_error) => None,
+        }
+    }
+
+    // queries
+
+    pub fn contains(&self, position: &Position) -> bool {
+        (position.x as usize) < MAP_SIZE && (position.y as usize) < MAP_SIZE
+    }
+
+    pub fn height(&self, position: &Position) -> u8 {
+        if !self.contains(position) {
+            return 0;
+        }
+
+        self.heights[position.y as usize * MAP_SIZE + position.x as usize]
+    }
+
+    // every attribute cell covers a square of tiles
+
+    pub fn attribute(&self, position: &Position) -> u8 {
+        if !self.contains(position) {
+            return MAP_ATTRIBUTE_BLOCKED;
+        }
+
+        let scale = MAP_SIZE / ATTRIBUTE_MAP_SIZE;
+        let x = position.x as usize / scale;
+        let y = position.y as usize / scale;
+
+        self.attributes[y * ATTRIBUTE_MAP_SIZE + x]
+    }
+
+    pub fn is_blocked(&self, position: &Position) -> bool {
+        self.attribute(position) & MAP_ATTRIBUTE_BLOCKED != 0
+    }
+
+    pub fn is_village(&self, position: &Position) -> bool {
+        self.attribute(position) & MAP_ATTRIBUTE_VILLAGE != 0
+    }
+
+    pub fn is_safe(&self, position: &Position) -> bool {
+        self.attribute(position) & (MAP_ATTRIBUTE_SAFE | MAP_ATTRIBUTE_VILLAGE) != 0
+    }
+
+    pub fn is_pvp(&self, position: &Position) -> bool {
+        self.attribute(position) & MAP_ATTRIBUTE_PVP != 0
+    }
+}
+
+#[cfg(test)]
+impl Map {
+    pub fn set_height(&mut self, position: &Position, height: u8) {
+        self.heights[position.y as usize * MAP_SIZE + position.x as usize] = height;
+    }
+
+    pub fn set_attribute(&mut self, position: &Position, attribute: u8) {
+        let scale = MAP_SIZE / ATTRIBUTE_MAP_SIZE;
+        let x = position.x as usize / scale;
+        let y = position.y as usize / scale;
+
+        self.attributes[y * ATTRIBUTE_MAP_SIZE + x] = attribute;
+    }
+}
@@ -0,0 +1,100 @@
+use packets::structs::{mob::SMob, packets::p364::P364, score::SScore};
+use std::{collections::HashMap, time::Instant};
+
+use crate::{
+    combat::Combatant,
+    structs::position::Position,
+    world::{affects::Affects, ai::MobAi, shop::Shop},
+};
+
+#[derive(Clone)]
+pub struct Mob {
+    pub id: u16,
+    pub generator: usize,
+    pub group: usize,
+    pub template: SMob,
+    pub position: Position,
+    pub home: Position,
+    pub score: SScore,
+    pub ai: MobAi,
+    pub next_regen: Instant,
+    pub attackers: HashMap<u16, i32>,
+    pub affects: Affects,
+    pub spawned_at: Instant,
+    pub shop: Option<Shop>,
+}
+
+impl Mob {
+    pub fn new(
+        id: u16,
+        generator: usize,
+        group: usize,
+        template: SMob,
+        position: Position,
+        now: Instant,
+    ) -> Self {
+        let mut score = template.current_score;
+        score.hp = score.max_hp;
+        score.mp = score.max_mp;
+
+        let shop = (template.merchant != 0).then(|| Shop::from_template(&template));
+
+        Self {
+            id,
+            generator,
+            group,
+            template,
+            position,
+            home: position,
+            score,
+            ai: MobAi::new(now),
+            next_regen: now,
+            attackers: HashMap::new(),
+            affects: Affects::default(),
+            spawned_at: now,
+            shop,
+        }
+    }
+
+    pub fn name(&self) -> String {
+        self.template.get_name()
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.score.hp > 0
+    }
+
+    pub fn is_merchant(&self) -> bool {
+        self.template.merchant != 0
+    }
+
+    pub fn combatant(&self) -> Combatant {
+        Combatant {
+            level: self.score.level,
+            damage: self.score.damage,
+            magic: self.template.magic as i32 + self.score.int as i32,
+            defense: self.score.defense,
+            dex: self.score.dex as i32,
+            critical: self.template.critical as i32,
+            resist: self.template.resist.map(|resist| resist as i32),
+        }
+    }
+
+    pub fn speed(&self) -> u8 {
+        self.affects.speed(self.score.speed)
+    }
+
+    pub fn spawn_packet(&self) -> P364 {
+        let mut score = self.score;
+        score.speed = self.speed();
+
+        let mut p = P364::new(self.id, &self.name(), self.position.to_struct(), score);
+        p.affects = self.affects.kinds();
+
+        for (slot, item) in self.template.equip.iter().enumerate() {
+            p.equip[slot] = item.index.max(0) as u16;
+        }
+
+        p
+    }
+}
@@ -0,0 +1,1657 @@
+use packets::structs::packets::{
+    p101::P101,
+    p165::P165,
+    p181::P181,
+    p364::P364,
+    p36c::P36C,
+    p39d::{ATTACK_FLAG_CRITICAL, ATTACK_FLAG_MISS, P39D},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, RwLock},
+    time::Instant,
+};
+use tokio::sync::{Mutex, Notify};
+
+use rand::{thread_rng, Rng};
+
+use crate::{
+    clock::Clock,
+    combat::{
+        damage::resolve,
+        experience::{distribute, split_by_level, Contribution},
+        rules::{can_attack, in_range, pvp_damage, CombatError, Target},
+        Attack, Combatant, Outcome, Rolls,
+    },
+    consts::{
+        DROP_SPREAD, FIRST_MOB_ID, GROUND_ITEM_DURATION, GROUND_OWNER_DURATION, LAST_MOB_ID,
+        MAX_COIN, MOB_ATTACK_INTERVAL, MOB_FLEE_DISTANCE, MOB_MOVE_INTERVAL, MOB_THINK_INTERVAL,
+        MOVE_TILES_PER_SPEED, PARTY_EXP_RANGE, PLAYER_ATTACK_INTERVAL, PLAYER_ATTACK_RANGE,
+        REGEN_INTERVAL, REGEN_PERCENT, SKILL_AREA_RANGE, SPAWN_POSITION, SPAWN_RETRY_DELAY,
+        VIEW_RANGE,
+    },
+    crafting::compose::Recipe,
+    scripting::Scripts,
+    statics::REPOSITORY,
+    structs::{item::Item, item_table::ItemTable, position::Position},
+};
+
+use self::{
+    affects::Affect,
+    ai::{flee_target, Decision, MobState, Perception},
+    chat::ChatFilter,
+    data::GameData,
+    events::{Event, Schedule},
+    generators::{Generator, Waypoint},
+    grid::Grid,
+    ground::Ground,
+    guild::war_key,
+    loot::{owner, scale, LootRule, LootTable, Rates},
+    map::Map,
+    mob::Mob,
+    movement::{check_start, path_towards, route_of, validate_route, MoveError, Movement},
+    party::Parties,
+    player::Player,
+    shop::Taxes,
+    skills::{Instance, Skill, SkillError},
+    spawner::{random_position, Spawner},
+    trade::Trades,
+};
+
+pub mod affects;
+pub mod ai;
+pub mod auction;
+pub mod chat;
+pub mod crafting;
+pub mod data;
+pub mod events;
+pub mod generators;
+pub mod gm;
+pub mod grid;
+pub mod ground;
+pub mod guild;
+pub mod items;
+pub mod loot;
+pub mod mail;
+pub mod map;
+pub mod mob;
+pub mod movement;
+pub mod party;
+pub mod player;
+pub mod progression;
+pub mod scripts;
+pub mod shop;
+pub mod skills;
+pub mod spawner;
+pub mod trade;
+pub mod vendor;
+
+// everything that can be seen by a player shares one lock, so the index never drifts from
+// the entities it points to
+
+#[derive(Default)]
+pub struct Entities {
+    pub players: HashMap<u16, Player>,
+    pub mobs: HashMap<u16, Mob>,
+    pub spawners: Vec<Spawner>,
+    pub grid: Grid,
+    pub ground: Ground,
+    pub trades: Trades,
+    pub parties: Parties,
+    pub wars: HashSet<(u16, u16)>,
+    pub guild_invites: HashMap<u16, u16>,
+    pub events: Vec<Event>,
+    saving: HashSet<u16>,
+    next_mob_id: u16,
+}
+
+impl Entities {
+    pub fn players_in_view(&self, position: &Position) -> Vec<&Player> {
+        self.grid
+            .query(position, VIEW_RANGE)
+            .iter()
+            .filter_map(|id| self.players.get(id))
+            .collect()
+    }
+
+    pub fn send_in_view<T: Sized>(&self, position: &Position, packet: &T, except: Option<u16>) {
+        for player in self.players_in_view(position) {
+            if Some(player.id()) != except {
+                player.session.send(packet);
+            }
+        }
+    }
+
+    pub fn send_to<T: Sized>(&self, id: u16, packet: &T) {
+        if let Some(player) = self.players.get(&id) {
+            player.session.send(packet);
+        }
+    }
+
+    pub fn notice(&self, message: &str) {
+        for player in self.players.values() {
+            player.session.send_message(message);
+        }
+    }
+
+    pub fn spawn_packet(&self, id: u16) -> Option<P364> {
+        match self.players.get(&id) {
+            Some(player) => Some(player.spawn_packet()),
+            None => self.mobs.get(&id).map(|mob| mob.spawn_packet()),
+        }
+    }
+
+    // mobs
+
+    fn next_mob_id(&mut self) -> Option<u16> {
+        let count = LAST_MOB_ID - FIRST_MOB_ID + 1;
+
+        for offset in 0..count {
+            let id = FIRST_MOB_ID + (self.next_mob_id + offset) % count;
+
+            if !self.mobs.contains_key(&id) {
+                self.next_mob_id = (id - FIRST_MOB_ID + 1) % count;
+                return Some(id);
+            }
+        }
+
+        None
+    }
+
+    pub fn spawn_mob(&mut self, mob: Mob) {
+        let spawn = mob.spawn_packet();
+
+        self.grid.insert(mob.id, mob.position);
+        self.send_in_view(&mob.position, &spawn, None);
+        self.mobs.insert(mob.id, mob);
+    }
+
+    fn spawn_group(
+        &mut self,
+        map: &Map,
+        index: usize,
+        generator: &Generator,
+        now: Instant,
+    ) -> bool {
+        let leader_position = match random_position(map, &generator.start()) {
+            Some(position) => position,
+            None => return false,
+        };
+
+        let group = self.spawners[index].open_group();
+
+        // a group nobody could be placed in would never die out and come back
+
+        if self.spawn_members(map, index, group, generator, leader_position, now) == 0 {
+            self.spawners[index].groups.remove(&group);
+            return false;
+        }
+
+        true
+    }
+
+    // members of a group the spawner never opened are not waited for once they die
+
+    fn spawn_members(
+        &mut self,
+        map: &Map,
+        index: usize,
+        group: usize,
+        generator: &Generator,
+        leader_position: Position,
+        now: Instant,
+    ) -> usize {
+        let followers = match generator.follower {
+            Some(_) if generator.max_group >= generator.min_group => {
+                thread_rng().gen_range(generator.min_group..=generator.max_group)
+            }
+            _ => 0,
+        };
+
+        let mut members = vec![(generator.leader, leader_position)];
+
+        if let Some(follower) = generator.follower {
+            let around = Waypoint {
+                position: leader_position,
+                range: 2,
+                wait: 0,
+            };
+
+            for _ in 0..followers {
+                if let Some(position) = random_position(map, &around) {
+                    members.push((follower, position));
+                }
+            }
+        }
+
+        let mut spawned = 0;
+
+        for (template, position) in members {
+            let id = match self.next_mob_id() {
+                Some(id) => id,
+                None => break,
+            };
+
+            self.spawners[index].add_member(group, id);
+            self.spawn_mob(Mob::new(id, index, group, template, position, now));
+
+            spawned += 1;
+        }
+
+        spawned
+    }
+
+    // every mob is taken off the map and each spawner starts over, the old groups would not
+    // line up with the new definitions
+
+    pub fn reset_spawners(&mut self, generators: &[Generator], now: Instant) {
+        for (id, mob) in std::mem::take(&mut self.mobs) {
+            self.grid.remove(id);
+            self.send_in_view(&mob.position, &P165::new(id, 1), None);
+        }
+
+        self.spawners = generators
+            .iter()
+            .map(|generator| Spawner::new(generator.max_groups, now))
+            .collect();
+    }
+
+    pub fn remove_mob(&mut self, id: u16, generators: &[Generator], now: Instant) -> Option<Mob> {
+        let mob = self.mobs.remove(&id)?;
+
+        self.grid.remove(id);
+        self.send_in_view(&mob.position, &P165::new(id, 1), None);
+
+        let group_gone = match self.spawners.get_mut(mob.generator) {
+            Some(spawner) => spawner.remove_member(mob.group, id),
+            None => false,
+        };
+
+        if group_gone {
+            if let Some(respawn) = generators.get(mob.generator).and_then(|g| g.respawn) {
+                self.spawners[mob.generator].pending.push(now + respawn);
+            }
+        }
+
+        Some(mob)
+    }
+
+    // one thinking step for a mob, the decision is taken on what the mob sees and then
+    // applied here where the other entities can be reached
+
+    fn process_mob(&mut self, map: &Map, generators: &[Generator], id: u16, now: Instant) {
+        let mob = match self.mobs.get(&id) {
+            Some(mob) if mob.is_alive() && now >= mob.ai.next_think => mob,
+            _ => return,
+        };
+
+        let generator = match generators.get(mob.generator) {
+            Some(generator) => generator,
+            None => return,
+        };
+
+        let targets: Vec<(u16, Position)> = self
+            .players_in_view(&mob.position)
+            .iter()
+            .filter(|player| player.character.hp > 0)
+            .filter(|player| !map.is_safe(&player.character.position))
+            .map(|player| (player.id(), player.character.position))
+            .collect();
+
+        // nobody around to see it, no reason to walk around
+
+        let idle = matches!(mob.ai.state, MobState::Idle { .. } | MobState::Wandering);
+
+        if targets.is_empty() && idle {
+            return;
+        }
+
+        let perception = Perception {
+            position: mob.position,
+            home: mob.home,
+            hp: mob.score.hp,
+            max_hp: mob.score.max_hp,
+            aggressive: mob.template.merchant == 0,
+            route: &generator.route,
+            route_type: generator.route_type,
+            targets,
+        };
+
+        let mut ai = mob.ai.clone();
+        let decision = ai.think(&perception, now);
+        ai.next_think = now + MOB_THINK_INTERVAL;
+
+        let chasing = matches!(ai.state, MobState::Chasing { .. });
+
+        match decision {
+            Decision::Wait => {}
+            Decision::Recover => {
+                if let Some(mob) = self.mobs.get_mut(&id) {
+                    mob.score.hp = mob.score.max_hp;
+                    mob.score.mp = mob.score.max_mp;
+                }
+            }
+            Decision::MoveTo(destination) => {
+                if now >= ai.next_move && self.move_mob(map, id, destination, chasing) {
+                    ai.next_move = now + MOB_MOVE_INTERVAL;
+                }
+            }
+            Decision::FleeFrom(threat) => {
+                let destination = flee_target(perception.position, threat, MOB_FLEE_DISTANCE);
+
+                if now >= ai.next_move && self.move_mob(map, id, destination, false) {
+                    ai.next_move = now + MOB_MOVE_INTERVAL;
+                }
+            }
+            Decision::Attack(target) => {
+                if now >= ai.next_attack {
+                    self.mob_attack(id, target);
+                    ai.next_attack = now + MOB_ATTACK_INTERVAL;
+                }
+            }
+        }
+
+        if let Some(mob) = self.mobs.get_mut(&id) {
+            mob.ai = ai;
+        }
+    }
+
+    fn move_mob(&mut self, map: &Map, id: u16, destination: Position, stop_short: bool) -> bool {
+        let (from, speed) = match self.mobs.get(&id) {
+            Some(mob) => (mob.position, mob.speed()),
+            None => return false,
+        };
+
+        let tiles = (speed as f32 * MOVE_TILES_PER_SPEED).ceil() as usize;
+        let mut path = path_towards(map, from, destination, tiles);
+
+        // chasing stops next to the target instead of on top of it
+
+        if stop_short && path.last() == Some(&destination) {
+            path.pop();
+        }
+
+        let to = match path.last() {
+            Some(to) => *to,
+            None => return false,
+        };
+
+        let mut packet = P36C::new(id, from.to_struct(), to.to_struct(), speed as i32);
+        packet.set_route(&route_of(from, &path));
+
+        if let Some(mob) = self.mobs.get_mut(&id) {
+            mob.position = to;
+        }
+
+        self.move_entity(id, to, &packet);
+
+        true
+    }
+
+    fn mob_attack(&mut self, id: u16, target: u16) {
+        let attacker = match self.mobs.get(&id) {
+            Some(mob) => mob.combatant(),
+            None => return,
+        };
+
+        let defender = match self.players.get(&target) {
+            Some(player) => player.character.combatant(),
+            None => return,
+        };
+
+        let rolls = Rolls::roll(&mut thread_rng());
+        let outcome = resolve(&attacker, &defender, &Attack::basic(), &rolls);
+
+        self.apply_hit(id, target, &outcome, -1);
+    }
+
+    // combat
+
+    // players of guilds at war are always fair game for each other
+
+    fn at_war(&self, id: u16, other: u16) -> bool {
+        let guild = |id| self.players.get(&id).and_then(|player| player.guild);
+
+        match (guild(id), guild(other)) {
+            (Some(guild), Some(other)) => self.wars.contains(&war_key(guild, other)),
+            _ => false,
+        }
+    }
+
+    // a running event opens its ground to everyone but their own guild
+
+    fn in_event(&self, id: u16, other: u16) -> bool {
+        let (player, other) = match (self.players.get(&id), self.players.get(&other)) {
+            (Some(player), Some(other)) => (player, other),
+            _ => return false,
+        };
+
+        if player.guild.is_some() && player.guild == other.guild {
+            return false;
+        }
+
+        self.events.iter().any(|event| {
+            event.in_battle(&player.character.position)
+                && event.in_battle(&other.character.position)
+        })
+    }
+
+    // the castle whose taxes cover the trade done where the player stands
+
+    fn castle_of(&self, id: u16) -> Option<String> {
+        let position = self.players.get(&id)?.character.position;
+
+        self.events
+            .iter()
+            .find(|event| {
+                event
+                    .schedule
+                    .taxes
+                    .is_some_and(|area| area.contains(&position))
+            })
+            .map(|event| event.schedule.name.clone())
+    }
+
+    fn combat_target(
+        &self,
+        attacker: u16,
+        id: u16,
+    ) -> Result<(Position, Combatant, Target), CombatError> {
+        if let Some(player) = self.players.get(&id) {
+            if player.character.hp <= 0 {
+                return Err(CombatError::Dead);
+            }
+
+            return Ok((
+                player.character.position,
+                player.character.combatant(),
+                Target::Player {
+                    at_war: self.at_war(attacker, id),
+                    event: self.in_event(attacker, id),
+                },
+            ));
+        }
+
+        let mob = self.mobs.get(&id).ok_or(CombatError::NotFound)?;
+
+        if !mob.is_alive() {
+            return Err(CombatError::Dead);
+        }
+
+        Ok((
+            mob.position,
+            mob.combatant(),
+            Target::Mob {
+                merchant: mob.is_merchant(),
+            },
+        ))
+    }
+
+    // applies an already resolved hit to whoever was the target and shows it around, the
+    // remaining hp is returned so the caller can deal with deaths
+
+    fn apply_hit(&mut self, attacker: u16, target: u16, outcome: &Outcome, skill: i16) -> i32 {
+        let attacker_position = match self.grid.position(attacker) {
+            Some(position) => position,
+            None => return 0,
+        };
+
+        let mut damage = outcome.damage;
+
+        let (target_position, hp) = if let Some(player) = self.players.get_mut(&target) {
+            damage = player.affects.absorb(damage);
+            player.character.hp = (player.character.hp - damage).max(0);
+            (player.character.position, player.character.hp)
+        } else if let Some(mob) = self.mobs.get_mut(&target) {
+            damage = mob.affects.absorb(damage);
+            let taken = damage.min(mob.score.hp);
+
+            mob.score.hp -= taken;
+
+            if self.players.contains_key(&attacker) {
+                *mob.attackers.entry(attacker).or_insert(0) += taken;
+                mob.ai.provoke(attacker);
+            }
+
+            (mob.position, mob.score.hp)
+        } else {
+            return 0;
+        };
+
+        let mut packet = P39D::new(
+            attacker,
+            attacker_position.to_struct(),
+            target,
+            target_position.to_struct(),
+        );
+        packet.skill_index = skill;
+        packet.damage = damage;
+        packet.target_hp = hp;
+
+        if outcome.critical {
+            packet.flags |= ATTACK_FLAG_CRITICAL;
+        }
+
+        if outcome.miss {
+            packet.flags |= ATTACK_FLAG_MISS;
+        }
+
+        self.send_in_view(&target_position, &packet, None);
+
+        hp
+    }
+
+    // resolves and applies one hit from a player, a mob left without hp is reaped by the world
+
+    fn strike(
+        &mut self,
+        attacker: u16,
+        target: u16,
+        attack: &Attack,
+        skill: i16,
+    ) -> Option<Outcome> {
+        let combatant = self.players.get(&attacker)?.character.combatant();
+        let (_, defender, kind) = self.combat_target(attacker, target).ok()?;
+
+        let rolls = Rolls::roll(&mut thread_rng());
+        let mut outcome = resolve(&combatant, &defender, attack, &rolls);
+
+        if matches!(kind, Target::Player { .. }) && !outcome.miss {
+            outcome.damage = pvp_damage(outcome.damage);
+        }
+
+        self.apply_hit(attacker, target, &outcome, skill);
+
+        Some(outcome)
+    }
+
+    // the main target always comes first, the rest are the closest ones that could also be
+    // attacked around it
+
+    fn area_targets(&self, map: &Map, attacker: u16, target: u16, max: usize) -> Vec<u16> {
+        let (origin, center) = match (self.grid.position(attacker), self.grid.position(target)) {
+            (Some(origin), Some(center)) => (origin, center),
+            _ => return vec![target],
+        };
+
+        let mut around: Vec<(u16, Position)> = self
+            .grid
+            .query(&center, SKILL_AREA_RANGE)
+            .into_iter()
+            .filter(|id| *id != attacker && *id != target)
+            .filter_map(|id| {
+                let (position, _, kind) = self.combat_target(attacker, id).ok()?;
+                can_attack(map, &origin, &position, kind).ok()?;
+                Some((id, position))
+            })
+            .collect();
+
+        around.sort_by_key(|(id, position)| (center.distance(position), *id));
+
+        let mut targets = vec![target];
+        targets.extend(
+            around
+                .into_iter()
+                .take(max.saturating_sub(1))
+                .map(|(id, _)| id),
+        );
+
+        targets
+    }
+
+    fn heal(&mut self, healer: u16, target: u16, amount: i32, skill: i16) {
+        let magic = match self.players.get(&healer) {
+            Some(player) => player.character.combatant().magic,
+            None => return,
+        };
+
+        let player = match self.players.get_mut(&target) {
+            Some(player) if player.character.hp > 0 => player,
+            _ => return,
+        };
+
+        let score = player.character.score();
+        let healed = (amount + magic).min(score.max_hp - score.hp).max(0);
+
+        player.character.hp = score.hp + healed;
+
+        let position = player.character.position;
+        let hp = player.character.hp;
+
+        let mut packet = P39D::new(healer, position.to_struct(), target, position.to_struct());
+        packet.skill_index = skill;
+        packet.damage = -healed;
+        packet.target_hp = hp;
+
+        if let Some(from) = self.grid.position(healer) {
+            packet.attacker_position = from.to_struct();
+        }
+
+        self.send_in_view(&position, &packet, None);
+        self.send_points(target);
+    }
+
+    pub fn apply_affect(&mut self, target: u16, affect: Affect) {
+        if let Some(player) = self.players.get_mut(&target) {
+            if player.character.hp > 0 {
+                player.affects.apply(affect);
+            }
+        } else if let Some(mob) = self.mobs.get_mut(&target) {
+            mob.affects.apply(affect);
+        }
+    }
+
+    pub fn send_points(&self, id: u16) {
+        if let Some(player) = self.players.get(&id) {
+            let score = player.character.score();
+
+            player.session.send(&P181::new(
+                id,
+                score.hp,
+                score.mp,
+                score.max_hp,
+                score.max_mp,
+            ));
+        }
+    }
+
+    // the exp goes to the players who hurt the mob and are still around to collect it
+
+    fn mob_died(
+        &mut self,
+        map: &Map,
+        generators: &[Generator],
+        rates: &Rates,
+        id: u16,
+        now: Instant,
+    ) -> Option<Mob> {
+        let mob = self.remove_mob(id, generators, now)?;
+
+        let contributions: Vec<Contribution> = mob
+            .attackers
+            .iter()
+            .filter_map(|(id, damage)| {
+                self.players.get(id).map(|player| Contribution {
+                    id: *id,
+                    level: player.character.level as i32,
+                    damage: *damage,
+                })
+            })
+            .collect();
+
+        // party members pool what they earned and split it with everyone in the party close
+        // enough to the kill
+
+        let mut pools: Vec<(Vec<u16>, u64)> = Vec::new();
+
+        for (id, exp) in distribute(mob.template.exp, mob.score.level, &contributions) {
+            let members = match self.parties.get(id) {
+                Some(party) => &party.members,
+                None => {
+                    self.give_exp(id, exp);
+                    continue;
+                }
+            };
+
+            match pools.iter_mut().find(|(pool, _)| pool == members) {
+                Some((_, pool)) => *pool += exp,
+                None => pools.push((members.clone(), exp)),
+            }
+        }
+
+        for (members, pool) in pools {
+            let nearby: Vec<(u16, i32)> = members
+                .iter()
+                .filter_map(|member| self.players.get(member))
+                .filter(|player| {
+                    contributions.iter().any(|c| c.id == player.id())
+                        || (player.character.hp > 0
+                            && player.character.position.distance(&mob.position) <= PARTY_EXP_RANGE)
+                })
+                .map(|player| (player.id(), player.character.level as i32))
+                .collect();
+
+            for (id, exp) in split_by_level(pool, &nearby) {
+                self.give_exp(id, exp);
+            }
+        }
+
+        let owner = self.loot_owner(&contributions);
+        self.drop_loot(map, &mob, owner, rates, now);
+
+        Some(mob)
+    }
+
+    // the side that hit the hardest wins the loot, a party then hands it out by the rule its
+    // leader picked, left free it stays with the top hitter for the whole party to pick up
+
+    fn loot_owner(&mut self, contributions: &[Contribution]) -> Option<u16> {
+        let winner = owner(
+            LootRule::TopDamage,
+            &self.party_contributions(contributions),
+            0,
+        )?;
+
+        let party = match self.parties.get_mut(winner) {
+            Some(party) => party,
+            None => return Some(winner),
+        };
+
+        let members: Vec<Contribution> = contributions
+            .iter()
+            .filter(|contribution| party.members.contains(&contribution.id))
+            .copied()
+            .collect();
+
+        let owner = owner(party.loot, &members, party.turn).unwrap_or(winner);
+
+        if party.loot == LootRule::RoundRobin {
+            party.turn = party.turn.wrapping_add(1);
+        }
+
+        Some(owner)
+    }
+
+    // a party competes for the loot as one, standing in for it is whoever of them hit the
+    // hardest
+
+    fn party_contributions(&self, contributions: &[Contribution]) -> Vec<Contribution> {
+        let mut merged: Vec<Contribution> = Vec::new();
+
+        for contribution in contributions {
+            let ally = merged
+                .iter_mut()
+                .find(|other| self.parties.together(other.id, contribution.id));
+
+            match ally {
+                Some(ally) => {
+                    if contribution.damage > ally.damage {
+                        ally.id = contribution.id;
+                        ally.level = contribution.level;
+                    }
+
+                    ally.damage = ally.damage.saturating_add(contribution.damage);
+                }
+                None => merged.push(*contribution),
+            }
+        }
+
+        merged
+    }
+
+    // coins go straight to whoever owns the loot, items are spread on the ground around the
+    // body and kept for the owner for a while
+
+    fn drop_loot(&mut self, map: &Map, mob: &Mob, owner: Option<u16>, rates: &Rates, now: Instant) {
+        if let Some(player) = owner.and_then(|owner| self.players.get_mut(&owner)) {
+            let coin = scale(mob.template.coin as i64, rates.coin());
+
+            if coin > 0 {
+                if let Some(total) = player
+                    .character
+                    .coin
+                    .checked_add(coin)
+                    .filter(|coin| *coin <= MAX_COIN)
+                {
+                    player.character.coin = total;
+                    player.session.send(&player.etc_packet());
+                }
+            }
+        }
+
+        let around = Waypoint {
+            position: mob.position,
+            range: DROP_SPREAD,
+            wait: 0,
+        };
+
+        for item in LootTable::from_template(&mob.template).roll(rates.drop(), &mut thread_rng()) {
+            if !self.ground.has_room() {
+                break;
+            }
+
+            let position = random_position(map, &around).unwrap_or(mob.position);
+            self.drop_item(item, position, owner, None, now);
+        }
+    }
+
+    pub fn give_exp(&mut self, id: u16, exp: u64) {
+        let player = match self.players.get_mut(&id) {
+            Some(player) => player,
+            None => return,
+        };
+
+        let gained = player.character.gain_exp(exp);
+
+        player.session.send(&player.etc_packet());
+
+        if gained > 0 {
+            player.session.send(&player.score_packet());
+            player.session.send_message(&format!(
+                "Parabéns! Você alcançou o nível {}.",
+                player.character.level
+            ));
+
+            self.send_points(id);
+        }
+    }
+
+    // affects run out and hurt over time here, poison can bring anyone down to 1 hp but
+    // never kills
+
+    fn process_affects(&mut self, now: Instant) {
+        for player in self.players.values_mut() {
+            player.affects.expire(now);
+
+            let damage = player.affects.tick(now);
+
+            if damage > 0 && player.character.hp > 1 {
+                player.character.hp = (player.character.hp - damage).max(1);
+
+                let score = player.score();
+
+                player.session.send(&P181::new(
+                    player.id(),
+                    score.hp,
+                    score.mp,
+                    score.max_hp,
+                    score.max_mp,
+                ));
+            }
+
+            if player.affects.take_changed() {
+                player.session.send(&player.affects_packet(now));
+            }
+        }
+
+        for mob in self.mobs.values_mut() {
+            mob.affects.expire(now);
+
+            let damage = mob.affects.tick(now);
+
+            if damage > 0 && mob.score.hp > 1 {
+                mob.score.hp = (mob.score.hp - damage).max(1);
+            }
+
+            mob.affects.take_changed();
+        }
+    }
+
+    // hp and mp come back slowly for the living, mobs only recover once they stop fighting
+
+    fn regenerate(&mut self, now: Instant) {
+        for player in self.players.values_mut() {
+            if now < player.next_regen {
+                continue;
+            }
+
+            player.next_regen = now + REGEN_INTERVAL;
+
+            let score = player.character.score();
+
+            if score.hp <= 0 || (score.hp >= score.max_hp && score.mp >= score.max_mp) {
+                continue;
+            }
+
+            player.character.hp = regenerate(score.hp, score.max_hp);
+            player.character.mp = regenerate(score.mp, score.max_mp);
+
+            player.session.send(&P181::new(
+                player.id(),
+                player.character.hp,
+                player.character.mp,
+                score.max_hp,
+                score.max_mp,
+            ));
+        }
+
+        for mob in self.mobs.values_mut() {
+            if now < mob.next_regen {
+                continue;
+            }
+
+            mob.next_regen = now + REGEN_INTERVAL;
+
+            if !mob.is_alive()
+                || !matches!(mob.ai.state, MobState::Idle { .. } | MobState::Wandering)
+            {
+                continue;
+            }
+
+            mob.score.hp = regenerate(mob.score.hp, mob.score.max_hp);
+            mob.score.mp = regenerate(mob.score.mp, mob.score.max_mp);
+        }
+    }
+
+    // moves the entity in the index and tells everyone around what changed, entities that
+    // come into view are spawned and the ones left behind are removed
+
+    pub fn move_entity<T: Sized>(&mut self, id: u16, to: Position, packet: &T) {
+        let from = match self.grid.position(id) {
+            Some(from) => from,
+            None => return,
+        };
+
+        let before = self.grid.query(&from, VIEW_RANGE);
+        self.grid.update(id, to);
+        let after = self.grid.query(&to, VIEW_RANGE);
+
+        let spawn = self.spawn_packet(id);
+        let remove = P165::new(id, 0);
+
+        for other in before.iter().filter(|other| **other != id) {
+            if !after.contains(other) {
+                self.send_to(*other, &remove);
+                self.send_to(id, &P165::new(*other, 0));
+            }
+        }
+
+        for other in after.iter().filter(|other| **other != id) {
+            if !before.contains(other) {
+                if let Some(spawn) = &spawn {
+                    self.send_to(*other, spawn);
+                }
+
+                if let Some(other_spawn) = self.spawn_packet(*other) {
+                    self.send_to(id, &other_spawn);
+                }
+            }
+
+            self.send_to(*other, packet);
+        }
+
+        if self.players.contains_key(&id) {
+            self.update_ground_view(id, &from, &to);
+        }
+    }
+
+    // the client is told to jump rather than walk, everyone around sees the player leave and
+    // show up
+
+    fn teleport(&mut self, id: u16, to: Position, now: Instant) {
+        self.close_vendor(id);
+
+        let player = match self.players.get_mut(&id) {
+            Some(player) => player,
+            None => return,
+        };
+
+        let from = player.character.position;
+
+        player.character.position = to;
+        player.movement = Movement::new(now);
+
+        let mut teleport = P36C::new(id, from.to_struct(), to.to_struct(), 0);
+        teleport.action_type = 1;
+
+        player.session.send(&teleport);
+
+        self.move_entity(id, to, &teleport);
+    }
+
+    // ground items
+
+    fn update_ground_view(&self, id: u16, from: &Position, to: &Position) {
+        let before: Vec<u16> = self
+            .ground
+            .in_view(from)
+            .iter()
+            .map(|item| item.id)
+            .collect();
+        let after = self.ground.in_view(to);
+
+        for item in after.iter().filter(|item| !before.contains(&item.id)) {
+            self.send_to(id, &item.spawn_packet());
+        }
+
+        for item in self.ground.in_view(from) {
+            if !after.iter().any(|other| other.id == item.id) {
+                self.send_to(id, &item.remove_packet());
+            }
+        }
+    }
+
+    // the owner, when there is one, gets a head start before anyone else can pick it up
+
+    pub fn drop_item(
+        &mut self,
+        item: Item,
+        position: Position,
+        owner: Option<u16>,
+        reserved: Option<u16>,
+        now: Instant,
+    ) -> bool {
+        let owner = owner.map(|owner| (owner, now + GROUND_OWNER_DURATION));
+
+        let packet =
+            match self
+                .ground
+                .add(item, position, owner, now + GROUND_ITEM_DURATION, reserved)
+            {
+                Some(ground_item) => ground_item.spawn_packet(),
+                None => return false,
+            };
+
+        self.send_in_view(&position, &packet, None);
+
+        true
+    }
+
+    fn process_ground(&mut self, now: Instant) {
+        for item in self.ground.expire(now) {
+            self.send_in_view(&item.position, &item.remove_packet(), None);
+        }
+    }
+}
+
+fn regenerate(current: i32, max: i32) -> i32 {
+    (current + (max * REGEN_PERCENT / 100).max(1)).min(max)
+}
+
+pub struct World {
+    pub map: Arc<Map>,
+    pub rates: Rates,
+    pub taxes: Taxes,
+    pub filter: ChatFilter,
+    pub scripts: Scripts,
+    pub clock: Arc<dyn Clock>,
+    data: RwLock<Arc<GameData>>,
+    entities: Arc<Mutex<Entities>>,
+    saved: Notify,
+}
+
+impl World {
+    pub fn new(
+        map: Map,
+        generators: Vec<Generator>,
+        skills: Vec<Skill>,
+        items: ItemTable,
+        recipes: Vec<Recipe>,
+        events: Vec<Schedule>,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
+        let now = clock.now();
+
+        let entities = Entities {
+            spawners: generators
+                .iter()
+                .map(|generator| Spawner::new(generator.max_groups, now))
+                .collect(),
+            events: events.into_iter().map(Event::new).collect(),
+            ..Entities::default()
+        };
+
+        Self {
+            map: Arc::new(map),
+            rates: Rates::default(),
+            taxes: Taxes::default(),
+            filter: ChatFilter::default(),
+            scripts: Scripts::default(),
+            clock,
+            data: RwLock::new(Arc::new(GameData::new(generators, skills, items, recipes))),
+            entities: Arc::new(Mutex::new(entities)),
+            saved: Notify::new(),
+        }
+    }
+
+    pub fn now(&self) -> Instant {
+        self.clock.now()
+    }
+
+    // one fixed step of the simulation, everything that happens on its own goes through here
+
+    pub async fn tick(&self, now: Instant) {
+        self.process_spawns(now).await;
+        self.process_mobs(now).await;
+        self.process_regeneration(now).await;
+        self.process_affects(now).await;
+        self.process_ground(now).await;
+        self.process_trades().await;
+        self.process_parties().await;
+        self.process_auctions().await;
+        self.process_events().await;
+    }
+
+    // players
+
+    pub async fn enter(&self, mut player: Player) {
+        let guild = REPOSITORY.guild_of(&player.character.name).await;
+        player.guild = guild.as_ref().map(|guild| guild.id);
+
+        let mailbox = REPOSITORY
+            .get_account(&player.username)
+            .await
+            .map(|account| account.mail)
+            .unwrap_or_default();
+
+        let mut entities = self.entities.lock().await;
+
+        if !self.map.contains(&player.character.position)
+            || self.map.is_blocked(&player.character.position)
+        {
+            player.character.position = Position::new(SPAWN_POSITION.0, SPAWN_POSITION.1);
+        }
+
+        let id = player.id();
+        let position = player.character.position;
+        let spawn = player.spawn_packet();
+
+        player.session.send(&player.login_packet());
+        player.session.send(&spawn);
+
+        for other in entities.grid.query(&position, VIEW_RANGE) {
+            entities.send_to(other, &spawn);
+
+            if let Some(other_spawn) = entities.spawn_packet(other) {
+                player.session.send(&other_spawn);
+            }
+        }
+
+        for item in entities.ground.in_view(&position) {
+            player.session.send(&item.spawn_packet());
+        }
+
+        let username = player.username.clone();
+
+        entities.grid.insert(id, position);
+        entities.players.insert(id, player);
+
+        entities.send_mailbox(&username, &mailbox);
+
+        // the rest of the guild sees them come online and they get the notice
+
+        if let Some(guild) = guild {
+            entities.send_guild(&guild);
+
+            if !guild.notice.is_empty() {
+                entities.send_to(
+                    id,
+                    &P101::new(&format!("Aviso da guilda: {}", guild.notice)),
+                );
+            }
+        }
+    }
+
+    // a save still on its way is let through first, so the character written on the way out
+    // carries what it saved
+
+    pub async fn leave(&self, id: u16) -> Option<Player> {
+        let mut entities = loop {
+            let saved = self.saved.notified();
+            let entities = self.entities.lock().await;
+
+            if !entities.saving.contains(&id) {
+                break entities;
+            }
+
+            drop(entities);
+            saved.await;
+        };
+
+        entities.cancel_trade(id);
+        entities.leave_party(id);
+        entities.guild_invites.remove(&id);
+
+        let mut player = entities.players.remove(&id)?;
+        player.character.affects = player.affects.save(self.clock.now());
+        entities.grid.remove(id);
+
+        entities.send_in_view(&player.character.position, &P165::new(id, 0), None);
+
+        Some(player)
+    }
+
+    pub async fn move_player(&self, id: u16, packet: P36C) -> Result<(), MoveError> {
+        let mut entities = self.entities.lock().await;
+
+        let player = match entities.players.get_mut(&id) {
+            Some(player) => player,
+            None => return Ok(()),
+        };
+
+        let from = player.character.position;
+        let start = Position::new(packet.position.x, packet.position.y);
+        let target = Position::new(packet.target.x, packet.target.y);
+
+        // the dead stay with their body until they restart
+
+        let result = if player.character.hp <= 0 {
+            Err(MoveError::Dead)
+        } else {
+            check_start(&from, &start)
+                .and_then(|_| validate_route(&self.map, start, &packet.get_route(), target))
+        };
+
+        let result = result.and_then(|path| {
+            let speed = player.score().speed;
+            let tiles = path.len() + from.distance(&start) as usize;
+
+            player.movement.consume(speed, tiles, self.clock.now())
+        });
+
+        if let Err(error) = result {
+            // snap the client back to where the server believes it is
+
+            let mut correction = P36C::new(id, from.to_struct(), from.to_struct(), 0);
+            correction.action_type = 1;
+            player.session.send(&correction);
+
+            return Err(error);
+        }
+
+        player.character.position = target;
+
+        let mut broadcast = P36C::new(id, start.to_struct(), target.to_struct(), packet.speed);
+        broadcast.set_route(&packet.get_route());
+
+        entities.move_entity(id, target, &broadcast);
+
+        // walking away from the shop closes it
+
+        entities.close_vendor(id);
+
+        Ok(())
+    }
+
+    // mobs
+
+    pub async fn process_spawns(&self, now: Instant) {
+        let mut entities = self.entities.lock().await;
+        let data = self.data();
+
+        for (index, generator) in data.generators.iter().enumerate() {
+            let due = entities.spawners[index].take_due(now);
+
+            for spawned in 0..due {
+                if !entities.spawn_group(&self.map, index, generator, now) {
+                    // nowhere to stand or no free ids right now, every group still owed is
+                    // tried again a bit later
+
+                    for _ in spawned..due {
+                        entities.spawners[index]
+                            .pending
+                            .push(now + SPAWN_RETRY_DELAY);
+                    }
+
+                    break;
+                }
+            }
+        }
+    }
+
+    pub async fn process_mobs(&self, now: Instant) {
+        let mut entities = self.entities.lock().await;
+        let data = self.data();
+
+        let ids: Vec<u16> = entities.mobs.keys().copied().collect();
+
+        for id in ids {
+            entities.process_mob(&self.map, &data.generators, id, now);
+        }
+    }
+
+    // combat
+
+    pub async fn attack(
+        &self,
+        attacker: u16,
+        target: u16,
+        attack: Attack,
+        range: u16,
+        skill: i16,
+    ) -> Result<Outcome, CombatError> {
+        let now = self.clock.now();
+        let mut entities = self.entities.lock().await;
+
+        let player = entities
+            .players
+            .get(&attacker)
+            .ok_or(CombatError::NotFound)?;
+
+        if player.character.hp <= 0 {
+            return Err(CombatError::Dead);
+        }
+
+        if attacker == target {
+            return Err(CombatError::Myself);
+        }
+
+        if now < player.next_attack {
+            return Err(CombatError::TooFast);
+        }
+
+        let position = player.character.position;
+
+        let (target_position, _, kind) = entities.combat_target(attacker, target)?;
+
+        can_attack(&self.map, &position, &target_position, kind)?;
+
+        if !in_range(&position, &target_position, range) {
+            return Err(CombatError::OutOfRange);
+        }
+
+        if let Some(player) = entities.players.get_mut(&attacker) {
+            player.next_attack = now + PLAYER_ATTACK_INTERVAL;
+        }
+
+        let outcome = entities
+            .strike(attacker, target, &attack, skill)
+            .ok_or(CombatError::NotFound)?;
+
+        self.reap(&mut entities, target, now);
+
+        Ok(outcome)
+    }
+
+    fn reap(&self, entities: &mut Entities, id: u16, now: Instant) {
+        if entities.mobs.get(&id).is_some_and(|mob| !mob.is_alive()) {
+            entities.mob_died(&self.map, &self.data().generators, &self.rates, id, now);
+        }
+    }
+
+    // skills are checked and paid for once, then every target they reach is handled on its own
+
+    pub async fn cast(&self, caster: u16, target: u16, index: u16) -> Result<(), SkillError> {
+        let now = self.clock.now();
+        let data = self.data();
+        let skill = data.skills.get(index as usize).ok_or(SkillError::Unknown)?;
+
+        let mut entities = self.entities.lock().await;
+
+        let player = entities.players.get(&caster).ok_or(CombatError::NotFound)?;
+
+        if player.character.hp <= 0 {
+            return Err(CombatError::Dead.into());
+        }
+
+        if skill.passive {
+            return Err(SkillError::Passive);
+        }
+
+        if skill.class() != Some(player.character.class)
+            || player.character.learned_skills & skill.bit() == 0
+        {
+            return Err(SkillError::NotLearned);
+        }
+
+        if player
+            .cooldowns
+            .get(&index)
+            .is_some_and(|until| now < *until)
+        {
+            return Err(SkillError::Cooldown);
+        }
+
+        if player.character.mp < skill.mana {
+            return Err(SkillError::NoMana);
+        }
+
+        let position = player.character.position;
+        let range = skill.range.max(PLAYER_ATTACK_RANGE);
+
+        let targets = if skill.aggressive {
+            if target == caster {
+                return Err(CombatError::Myself.into());
+            }
+
+            let (target_position, _, kind) = entities.combat_target(caster, target)?;
+
+            can_attack(&self.map, &position, &target_position, kind)?;
+
+            if !in_range(&position, &target_position, range) {
+                return Err(CombatError::OutOfRange.into());
+            }
+
+            entities.area_targets(&self.map, caster, target, skill.max_targets)
+        } else if skill.party {
+            // party skills reach the caster and every living member around them
+
+            let mut members = vec![caster];
+
+            if let Some(party) = entities.parties.get(caster) {
+                members.extend(party.members.iter().copied().filter(|id| *id != caster));
+            }
+
+            members
+                .into_iter()
+                .filter(|id| {
+                    entities.players.get(id).is_some_and(|member| {
+                        member.character.hp > 0
+                            && in_range(&position, &member.character.position, range)
+                    })
+                })
+                .collect()
+        } else {
+            // anything helpful lands on the caster unless another player was picked
+
+            let target = match entities.players.contains_key(&target) {
+                true => target,
+                false => caster,
+            };
+
+            let other = &entities.players[&target];
+
+            if other.character.hp <= 0 {
+                return Err(CombatError::Dead.into());
+            }
+
+            if !in_range(&position, &other.character.position, range) {
+                return Err(CombatError::OutOfRange.into());
+            }
+
+            vec![target]
+        };
+
+        if let Some(player) = entities.players.get_mut(&caster) {
+            player.character.mp -= skill.mana;
+            player.cooldowns.insert(index, now + skill.cooldown);
+            player.next_attack = now + PLAYER_ATTACK_INTERVAL;
+        }
+
+        entities.send_points(caster);
+
+        for target in targets {
+            match skill.instance {
+                Instance::Damage(attack) => {
+                    entities.strike(caster, target, &attack, index as i16);
+                    self.reap(&mut entities, target, now);
+                }
+                Instance::Heal(amount) => entities.heal(caster, target, amount, index as i16),
+                Instance::None => {}
+            }
+
+            if let Some(affect) = skill.affect {
+                entities.apply_affect(
+                    target,
+                    Affect::new(affect.kind, affect.value, affect.duration, now),
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn process_affects(&self, now: Instant) {
+        self.entities.lock().await.process_affects(now);
+    }
+
+    pub async fn process_ground(&self, now: Instant) {
+        self.entities.lock().await.process_ground(now);
+    }
+
+    pub async fn process_regeneration(&self, now: Instant) {
+        self.entities.lock().await.regenerate(now);
+    }
+
+    pub async fn get_player(&self, id: u16) -> Option<Player> {
+        self.entities.lock().await.players.get(&id).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use packets::{serializer::deserialize, structs::mob::SMob};
+    use std::{mem::size_of, time::Duration};
+    use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+
+    use super::*;
+    use crate::{
+        clock::ManualClock,
+        consts::{AFFECT_HASTE, AFFECT_POISON, REGEN_PERCENT},
+        session::Session,
+        structs::{character::Character, class::Class, storage::Storage},
+        world::affects::Affect,
+    };
+
+    const RESPAWN: Duration = Duration::from_secs(10);
+
+    fn generator() -> Generator {
+        let mut leader = deserialize::<SMob>(&vec![0; size_of::<SMob>()]).unwrap();
+        leader.current_score.max_hp = 100;
+
+        Generator {
+            index: 0,
+            leader,
+            follower: None,
+            min_group: 0,
+            max_group: 0,
+            max_groups: 1,
+            respawn: Some(RESPAWN),
+            route_type: 0,
+            route: vec![Waypoint {
+                position: Position::new(100, 100),
+                range: 0,
+                wait: 0,
+            }],
+        }
+    }
+
+    fn world(clock: &ManualClock, generators: Vec<Generator>) -> World {
+        World::new(
+            Map::default(),
+            generators,
+            Vec::new(),
+            ItemTable::default(),
+            Vec::new(),
+            Vec::new(),
+            Arc::new(clock.clone()),
+        )
+    }
+
+    async fn enter(world: &World, id: u16) -> UnboundedReceiver<Vec<u8>> {
+        let (sender, receiver) = unbounded_channel();
+
+        let mut player = Player::new(
+            Session::new(id, "127.0.0.1".to_string(), sender),
+            format!("account{}", id),
+            0,
+            Character::new(format!("Player{}", id), Class::TransKnight),
+            Storage::default(),
+            world.now(),
+        );
+        player.character.position = Position::new(200, 200);
+
+        let mut entities = world.entities.lock().await;
+        entities.grid.insert(id, player.character.position);
+        entities.players.insert(id, player);
+
+        receiver
+    }
+
+    async fn mobs(world: &World) -> Vec<u16> {
+        world.entities.lock().await.mobs.keys().copied().collect()
+    }
+
+    #[tokio::test]
+    async fn a_dead_group_comes_back_after_its_respawn() {
+        let clock = ManualClock::default();
+        let world = world(&clock, vec![generator()]);
+
+        world.tick(clock.now()).await;
+
+        let spawned = mobs(&world).await;
+        assert_eq!(spawned.len(), 1);
+
+        world
+            .entities
+            .lock()
+            .await
+            .remove_mob(spawned[0], &world.data().generators, clock.now());
+
+        world
+            .tick(clock.advance(RESPAWN - Duration::from_secs(1)))
+            .await;
+        assert!(mobs(&world).await.is_empty());
+
+        world.tick(clock.advance(Duration::from_secs(1))).await;
+        assert_eq!(mobs(&world).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn players_regenerate_once_per_interval() {
+        let clock = ManualClock::default();
+        let world = world(&clock, Vec::new());
+        let _receiver = enter(&world, 1).await;
+
+        world
+            .entities
+            .lock()
+            .await
+            .players
+            .get_mut(&1)
+            .unwrap()
+            .character
+            .hp = 1;
+        let max_hp = world.get_player(1).await.unwrap().character.score().max_hp;
+        let gain = (max_hp * REGEN_PERCENT / 100).max(1);
+
+        world.tick(clock.advance(REGEN_INTERVAL / 2)).await;
+        assert_eq!(world.get_player(1).await.unwrap().character.hp, 1);
+
+        world.tick(clock.advance(REGEN_INTERVAL / 2)).await;
+        assert_eq!(world.get_player(1).await.unwrap().character.hp, 1 + gain);
+
+        world.tick(clock.now()).await;
+        assert_eq!(world.get_player(1).await.unwrap().character.hp, 1 + gain);
+    }
+
+    #[tokio::test]
+    async fn affects_run_out_and_poison_hurts_on_the_way() {
+        let clock = ManualClock::default();
+        let world = world(&clock, Vec::new());
+        let _receiver = enter(&world, 1).await;
+
+        {
+            let mut entities = world.entities.lock().await;
+            let player = entities.players.get_mut(&1).unwrap();
+            let now = clock.now();
+
+            player.character.hp = 100;
+            player
+                .affects
+                .apply(Affect::new(AFFECT_HASTE, 10, Duration::from_secs(3), now));
+            player
+                .affects
+                .apply(Affect::new(AFFECT_POISON, 7, Duration::from_secs(2), now));
+        }
+
+        // the regen interval is longer than the test, so only the poison moves the hp
+
+        world.tick(clock.advance(Duration::from_secs(1))).await;
+
+        let player = world.get_player(1).await.unwrap();
+        assert_eq!(player.character.hp, 93);
+        assert!(player.affects.get(AFFECT_HASTE).is_some());
+
+        world.tick(clock.advance(Duration::from_secs(1))).await;
+
+        let player = world.get_player(1).await.unwrap();
+        assert!(player.affects.get(AFFECT_POISON).is_none());
+        assert!(player.affects.get(AFFECT_HASTE).is_some());
+
+        world.tick(clock.advance(Duration::from_secs(1))).await;
+
+        let player = world.get_player(1).await.unwrap();
+        assert!(player.affects.get(AFFECT_HASTE).is_none());
+    }
+}
@@ -0,0 +1,336 @@
+use std::{fmt::Display, time::Instant};
+
+use crate::{
+    consts::{
+        MAX_POSITION_DRIFT, MAX_STEP_HEIGHT, MOVE_BURST_TILES, MOVE_SPEED_TOLERANCE,
+        MOVE_TILES_PER_SPEED,
+    },
+    structs::position::Position,
+    world::map::Map,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveError {
+    InvalidStart,
+    InvalidStep,
+    Blocked,
+    TooSteep,
+    InvalidTarget,
+    TooFast,
+    Dead,
+}
+
+impl Display for MoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveError::InvalidStart => write!(f, "invalid start"),
+            MoveError::InvalidStep => write!(f, "invalid step"),
+            MoveError::Blocked => write!(f, "blocked"),
+            MoveError::TooSteep => write!(f, "too steep"),
+            MoveError::InvalidTarget => write!(f, "invalid target"),
+            MoveError::TooFast => write!(f, "too fast"),
+            MoveError::Dead => write!(f, "dead"),
+        }
+    }
+}
+
+// routes are sent as numpad digits, 5 would be standing still so it is never used
+
+pub fn step(position: &Position, direction: u8) -> Option<Position> {
+    let (dx, dy): (i32, i32) = match direction {
+        b'1' => (-1, 1),
+        b'2' => (0, 1),
+        b'3' => (1, 1),
+        b'4' => (-1, 0),
+        b'6' => (1, 0),
+        b'7' => (-1, -1),
+        b'8' => (0, -1),
+        b'9' => (1, -1),
+        _ => return None,
+    };
+
+    let x = u16::try_from(position.x as i32 + dx).ok()?;
+    let y = u16::try_from(position.y as i32 + dy).ok()?;
+
+    Some(Position::new(x, y))
+}
+
+pub fn direction(from: &Position, to: &Position) -> Option<u8> {
+    let dx = (to.x as i32 - from.x as i32).signum();
+    let dy = (to.y as i32 - from.y as i32).signum();
+
+    match (dx, dy) {
+        (-1, 1) => Some(b'1'),
+        (0, 1) => Some(b'2'),
+        (1, 1) => Some(b'3'),
+        (-1, 0) => Some(b'4'),
+        (1, 0) => Some(b'6'),
+        (-1, -1) => Some(b'7'),
+        (0, -1) => Some(b'8'),
+        (1, -1) => Some(b'9'),
+        _ => None,
+    }
+}
+
+// greedy walk used by the server side entities, it slides along walls instead of stopping
+// when the straight step is blocked
+
+pub fn path_towards(map: &Map, from: Position, to: Position, max_tiles: usize) -> Vec<Position> {
+    let mut path = Vec::new();
+    let mut current = from;
+
+    while path.len() < max_tiles && current != to {
+        let dx = (to.x as i32 - current.x as i32).signum();
+        let dy = (to.y as i32 - current.y as i32).signum();
+
+        let candidates = [(dx, dy), (dx, 0), (0, dy)];
+
+        let next = candidates.iter().find_map(|(dx, dy)| {
+            if *dx == 0 && *dy == 0 {
+                return None;
+            }
+
+            let x = u16::try_from(current.x as i32 + dx).ok()?;
+            let y = u16::try_from(current.y as i32 + dy).ok()?;
+            let next = Position::new(x, y);
+
+            can_step(map, &current, &next).ok().map(|_| next)
+        });
+
+        match next {
+            Some(next) => {
+                path.push(next);
+                current = next;
+            }
+            None => break,
+        }
+    }
+
+    path
+}
+
+pub fn route_of(from: Position, path: &[Position]) -> Vec<u8> {
+    let mut current = from;
+    let mut route = Vec::with_capacity(path.len());
+
+    for next in path {
+        if let Some(direction) = direction(&current, next) {
+            route.push(direction);
+        }
+
+        current = *next;
+    }
+
+    route
+}
+
+pub fn can_step(map: &Map, from: &Position, to: &Position) -> Result<(), MoveError> {
+    if !map.contains(to) || map.is_blocked(to) {
+        return Err(MoveError::Blocked);
+    }
+
+    if map.height(from).abs_diff(map.height(to)) > MAX_STEP_HEIGHT {
+        return Err(MoveError::TooSteep);
+    }
+
+    Ok(())
+}
+
+// the client may be a tile off from where the server has it, anything further is a jump
+
+pub fn check_start(from: &Position, start: &Position) -> Result<(), MoveError> {
+    match from.distance(start) <= MAX_POSITION_DRIFT {
+        true => Ok(()),
+        false => Err(MoveError::InvalidStart),
+    }
+}
+
+pub fn validate_route(
+    map: &Map,
+    start: Position,
+    route: &[u8],
+    target: Position,
+) -> Result<Vec<Position>, MoveError> {
+    if !map.contains(&start) || map.is_blocked(&start) {
+        return Err(MoveError::InvalidStart);
+    }
+
+    // without a route the client can only shuffle into a neighbour tile
+
+    if route.is_empty() {
+        if start.distance(&target) > 1 {
+            return Err(MoveError::InvalidTarget);
+        }
+
+        can_step(map, &start, &target)?;
+
+        return Ok(vec![target]);
+    }
+
+    let mut current = start;
+    let mut path = Vec::with_capacity(route.len());
+
+    for direction in route {
+        let next = step(&current, *direction).ok_or(MoveError::InvalidStep)?;
+
+        can_step(map, &current, &next)?;
+
+        path.push(next);
+        current = next;
+    }
+
+    if current != target {
+        return Err(MoveError::InvalidTarget);
+    }
+
+    Ok(path)
+}
+
+// token bucket refilled by the movement speed, a full route can be walked at once but keeping
+// it up takes the right amount of time
+
+#[derive(Clone)]
+pub struct Movement {
+    tokens: f32,
+    last_update: Instant,
+}
+
+impl Movement {
+    pub fn new(now: Instant) -> Self {
+        Self {
+            tokens: MOVE_BURST_TILES,
+            last_update: now,
+        }
+    }
+
+    pub fn consume(&mut self, speed: u8, tiles: usize, now: Instant) -> Result<(), MoveError> {
+        let elapsed = now
+            .saturating_duration_since(self.last_update)
+            .as_secs_f32();
+        let rate = speed.max(1) as f32 * MOVE_TILES_PER_SPEED * MOVE_SPEED_TOLERANCE;
+
+        self.tokens = (self.tokens + elapsed * rate).min(MOVE_BURST_TILES);
+        self.last_update = now;
+
+        if (tiles as f32) > self.tokens {
+            return Err(MoveError::TooFast);
+        }
+
+        self.tokens -= tiles as f32;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::consts::{BASE_SPEED, MAP_ATTRIBUTE_BLOCKED};
+
+    // an open flat map with a wall cell right of the start and a cliff below it
+
+    fn map() -> Map {
+        let mut map = Map::default();
+        map.set_attribute(&Position::new(104, 100), MAP_ATTRIBUTE_BLOCKED);
+        map.set_height(&Position::new(100, 101), MAX_STEP_HEIGHT + 1);
+        map
+    }
+
+    #[test]
+    fn a_start_past_the_drift_is_a_teleport() {
+        let from = Position::new(100, 100);
+
+        assert_eq!(check_start(&from, &Position::new(101, 101)), Ok(()));
+        assert_eq!(
+            check_start(&from, &Position::new(102, 100)),
+            Err(MoveError::InvalidStart)
+        );
+        assert_eq!(
+            check_start(&from, &Position::new(2000, 2000)),
+            Err(MoveError::InvalidStart)
+        );
+    }
+
+    #[test]
+    fn a_route_follows_its_steps_to_the_target() {
+        let start = Position::new(100, 100);
+
+        assert_eq!(
+            validate_route(&map(), start, b"88", Position::new(100, 98)),
+            Ok(vec![Position::new(100, 99), Position::new(100, 98)])
+        );
+        assert_eq!(
+            validate_route(&map(), start, b"88", Position::new(100, 97)),
+            Err(MoveError::InvalidTarget)
+        );
+        assert_eq!(
+            validate_route(&map(), start, b"85", Position::new(100, 98)),
+            Err(MoveError::InvalidStep)
+        );
+    }
+
+    #[test]
+    fn walls_and_cliffs_stop_the_route() {
+        let start = Position::new(100, 100);
+
+        assert_eq!(
+            validate_route(&map(), start, b"6666", Position::new(104, 100)),
+            Err(MoveError::Blocked)
+        );
+        assert_eq!(
+            validate_route(&map(), start, b"2", Position::new(100, 101)),
+            Err(MoveError::TooSteep)
+        );
+        assert_eq!(
+            validate_route(
+                &map(),
+                Position::new(104, 100),
+                b"4",
+                Position::new(103, 100)
+            ),
+            Err(MoveError::InvalidStart)
+        );
+    }
+
+    #[test]
+    fn standing_still_only_reaches_a_neighbour() {
+        let start = Position::new(100, 100);
+
+        assert_eq!(
+            validate_route(&map(), start, b"", Position::new(99, 99)),
+            Ok(vec![Position::new(99, 99)])
+        );
+        assert_eq!(
+            validate_route(&map(), start, b"", Position::new(98, 100)),
+            Err(MoveError::InvalidTarget)
+        );
+    }
+
+    #[test]
+    fn walking_faster_than_the_speed_runs_out_of_tiles() {
+        let start = Instant::now();
+        let mut movement = Movement::new(start);
+
+        assert_eq!(
+            movement.consume(BASE_SPEED, MOVE_BURST_TILES as usize, start),
+            Ok(())
+        );
+        assert_eq!(
+            movement.consume(BASE_SPEED, 1, start),
+            Err(MoveError::TooFast)
+        );
+
+        // a second later only what the speed refilled can be walked
+
+        let later = start + Duration::from_secs(1);
+        let refilled = (BASE_SPEED as f32 * MOVE_TILES_PER_SPEED * MOVE_SPEED_TOLERANCE) as usize;
+
+        assert_eq!(movement.consume(BASE_SPEED, refilled, later), Ok(()));
+        assert_eq!(
+            movement.consume(BASE_SPEED, 1, later),
+            Err(MoveError::TooFast)
+        );
+    }
+}
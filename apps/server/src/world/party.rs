@@ -0,0 +1,405 @@
+use packets::structs::packets::{p181::P181, p37d::P37D, p37e::P37E, p37f::P37F};
+use std::{collections::HashMap, fmt::Display};
+
+use crate::{
+    consts::PARTY_MAX_MEMBERS,
+    world::{loot::LootRule, Entities, World},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PartyError {
+    NotFound,
+    Myself,
+    InParty,
+    NotLeader,
+    NoParty,
+    NotMember,
+    NotInvited,
+    Full,
+    InvalidLoot,
+}
+
+impl Display for PartyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PartyError::NotFound => write!(f, "Jogador não encontrado."),
+            PartyError::Myself => write!(f, "Você não pode convidar você mesmo."),
+            PartyError::InParty => write!(f, "O jogador já está em um grupo."),
+            PartyError::NotLeader => write!(f, "Apenas o líder pode fazer isso."),
+            PartyError::NoParty => write!(f, "Você não está em um grupo."),
+            PartyError::NotMember => write!(f, "O jogador não está no seu grupo."),
+            PartyError::NotInvited => write!(f, "Convite não encontrado."),
+            PartyError::Full => write!(f, "O grupo está cheio."),
+            PartyError::InvalidLoot => write!(f, "Regra de saque inválida."),
+        }
+    }
+}
+
+// the leader is always the first member, and picks how the loot is shared
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Party {
+    pub members: Vec<u16>,
+    pub loot: LootRule,
+    pub turn: usize,
+}
+
+impl Party {
+    pub fn leader(&self) -> Option<u16> {
+        self.members.first().copied()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Parties {
+    parties: HashMap<u32, Party>,
+    members: HashMap<u16, u32>,
+    invites: HashMap<u16, u16>,
+    points: HashMap<u16, [i32; 4]>,
+    next_id: u32,
+}
+
+impl Parties {
+    pub fn get(&self, id: u16) -> Option<&Party> {
+        self.parties.get(self.members.get(&id)?)
+    }
+
+    pub fn get_mut(&mut self, id: u16) -> Option<&mut Party> {
+        self.parties.get_mut(self.members.get(&id)?)
+    }
+
+    pub fn all(&self) -> Vec<Party> {
+        self.parties.values().cloned().collect()
+    }
+
+    pub fn together(&self, id: u16, other: u16) -> bool {
+        match (self.members.get(&id), self.members.get(&other)) {
+            (Some(party), Some(other)) => party == other,
+            _ => false,
+        }
+    }
+
+    // only a leader, or someone still on their own, can bring people in
+
+    pub fn invite(&mut self, leader: u16, target: u16) -> Result<(), PartyError> {
+        if leader == target {
+            return Err(PartyError::Myself);
+        }
+
+        if self.members.contains_key(&target) {
+            return Err(PartyError::InParty);
+        }
+
+        if let Some(party) = self.get(leader) {
+            if party.leader() != Some(leader) {
+                return Err(PartyError::NotLeader);
+            }
+
+            if party.members.len() >= PARTY_MAX_MEMBERS {
+                return Err(PartyError::Full);
+            }
+        }
+
+        self.invites.insert(target, leader);
+
+        Ok(())
+    }
+
+    // the party only comes to exist once somebody accepts, returns who is in it afterwards
+
+    pub fn accept(&mut self, id: u16, leader: u16) -> Result<Vec<u16>, PartyError> {
+        if self.invites.get(&id) != Some(&leader) {
+            return Err(PartyError::NotInvited);
+        }
+
+        self.invites.remove(&id);
+
+        if self.members.contains_key(&id) {
+            return Err(PartyError::InParty);
+        }
+
+        let key = match self.members.get(&leader) {
+            Some(key) => *key,
+            None => {
+                self.next_id = self.next_id.wrapping_add(1);
+                self.parties.insert(
+                    self.next_id,
+                    Party {
+                        members: vec![leader],
+                        ..Default::default()
+                    },
+                );
+                self.members.insert(leader, self.next_id);
+                self.next_id
+            }
+        };
+
+        let party = self.parties.get_mut(&key).ok_or(PartyError::NotFound)?;
+
+        if party.leader() != Some(leader) {
+            return Err(PartyError::NotLeader);
+        }
+
+        if party.members.len() >= PARTY_MAX_MEMBERS {
+            return Err(PartyError::Full);
+        }
+
+        party.members.push(id);
+        self.members.insert(id, key);
+
+        Ok(party.members.clone())
+    }
+
+    // returns who was left behind, a party of one is no party so it goes away with them still
+    // listed to be told
+
+    pub fn leave(&mut self, id: u16) -> Option<Vec<u16>> {
+        let key = self.members.remove(&id)?;
+        let party = self.parties.get_mut(&key)?;
+
+        party.members.retain(|member| *member != id);
+        self.points.remove(&id);
+
+        let remaining = party.members.clone();
+
+        if remaining.len() < 2 {
+            self.parties.remove(&key);
+
+            for member in &remaining {
+                self.members.remove(member);
+                self.points.remove(member);
+            }
+        }
+
+        Some(remaining)
+    }
+
+    pub fn kick(&mut self, leader: u16, target: u16) -> Result<Vec<u16>, PartyError> {
+        self.check_leader(leader, target)?;
+        self.leave(target).ok_or(PartyError::NotMember)
+    }
+
+    pub fn transfer(&mut self, leader: u16, target: u16) -> Result<Vec<u16>, PartyError> {
+        self.check_leader(leader, target)?;
+
+        let key = self.members.get(&leader).ok_or(PartyError::NoParty)?;
+        let party = self.parties.get_mut(key).ok_or(PartyError::NoParty)?;
+
+        party.members.retain(|member| *member != target);
+        party.members.insert(0, target);
+
+        Ok(party.members.clone())
+    }
+
+    pub fn set_loot(&mut self, leader: u16, rule: LootRule) -> Result<Vec<u16>, PartyError> {
+        let party = self.get_mut(leader).ok_or(PartyError::NoParty)?;
+
+        if party.leader() != Some(leader) {
+            return Err(PartyError::NotLeader);
+        }
+
+        party.loot = rule;
+        party.turn = 0;
+
+        Ok(party.members.clone())
+    }
+
+    pub fn cancel_invites(&mut self, id: u16) {
+        self.invites.remove(&id);
+        self.invites.retain(|_, leader| *leader != id);
+    }
+
+    // remembers what was last shown of a member, so only changes are sent around
+
+    pub fn points_changed(&mut self, id: u16, points: [i32; 4]) -> bool {
+        self.points.insert(id, points) != Some(points)
+    }
+
+    fn check_leader(&self, leader: u16, target: u16) -> Result<(), PartyError> {
+        if leader == target {
+            return Err(PartyError::Myself);
+        }
+
+        let party = self.get(leader).ok_or(PartyError::NoParty)?;
+
+        if party.leader() != Some(leader) {
+            return Err(PartyError::NotLeader);
+        }
+
+        if !self.together(leader, target) {
+            return Err(PartyError::NotMember);
+        }
+
+        Ok(())
+    }
+}
+
+impl World {
+    pub async fn invite_party(&self, id: u16, target: u16) -> Result<(), PartyError> {
+        let mut entities = self.entities.lock().await;
+
+        if !entities.players.contains_key(&target) {
+            return Err(PartyError::NotFound);
+        }
+
+        entities.parties.invite(id, target)?;
+
+        let leader = entities.players.get(&id).ok_or(PartyError::NotFound)?;
+        let score = leader.score();
+
+        let mut packet = P37F::new(target, id, target);
+        packet.level = leader.character.level.min(i16::MAX as u16) as i16;
+        packet.max_hp = score.max_hp.clamp(0, i16::MAX as i32) as i16;
+        packet.hp = score.hp.clamp(0, i16::MAX as i32) as i16;
+        packet.set_name(&leader.character.name);
+
+        entities.send_to(target, &packet);
+
+        Ok(())
+    }
+
+    pub async fn accept_party(&self, id: u16, leader: u16) -> Result<(), PartyError> {
+        let mut entities = self.entities.lock().await;
+
+        if !entities.players.contains_key(&leader) {
+            entities.parties.cancel_invites(leader);
+            return Err(PartyError::NotFound);
+        }
+
+        let members = entities.parties.accept(id, leader)?;
+        entities.send_party(&members);
+
+        Ok(())
+    }
+
+    // leaving is removing yourself, anyone else can only be removed by the leader
+
+    pub async fn remove_from_party(&self, id: u16, target: u16) -> Result<(), PartyError> {
+        let mut entities = self.entities.lock().await;
+
+        if id == target {
+            entities.leave_party(id);
+            return Ok(());
+        }
+
+        let remaining = entities.parties.kick(id, target)?;
+        entities.notify_left(target, &remaining);
+
+        Ok(())
+    }
+
+    pub async fn transfer_party(&self, id: u16, target: u16) -> Result<(), PartyError> {
+        let mut entities = self.entities.lock().await;
+
+        let members = entities.parties.transfer(id, target)?;
+        entities.send_party(&members);
+
+        Ok(())
+    }
+
+    pub async fn set_party_loot(&self, id: u16, rule: u16) -> Result<(), PartyError> {
+        let rule = LootRule::from_index(rule).ok_or(PartyError::InvalidLoot)?;
+
+        let mut entities = self.entities.lock().await;
+        let members = entities.parties.set_loot(id, rule)?;
+
+        let message = format!("Regra de saque: {}.", rule);
+
+        for player in members
+            .iter()
+            .filter_map(|member| entities.players.get(member))
+        {
+            player.session.send_message(&message);
+        }
+
+        Ok(())
+    }
+
+    pub async fn process_parties(&self) {
+        self.entities.lock().await.process_parties();
+    }
+}
+
+impl Entities {
+    fn member_packet(&self, leader: u16, member: u16) -> Option<P37D> {
+        let player = self.players.get(&member)?;
+        let score = player.score();
+
+        let mut packet = P37D::new(member, leader, member);
+        packet.level = player.character.level.min(i16::MAX as u16) as i16;
+        packet.max_hp = score.max_hp.clamp(0, i16::MAX as i32) as i16;
+        packet.hp = score.hp.clamp(0, i16::MAX as i32) as i16;
+        packet.set_name(&player.character.name);
+
+        Some(packet)
+    }
+
+    // whenever the party changes everyone in it gets the whole list again
+
+    fn send_party(&self, members: &[u16]) {
+        let leader = match members.first() {
+            Some(leader) => *leader,
+            None => return,
+        };
+
+        for member in members {
+            for other in members {
+                if let Some(packet) = self.member_packet(leader, *other) {
+                    self.send_to(*member, &packet);
+                }
+            }
+        }
+    }
+
+    // removing yourself from the list is how the client learns it is out of the party
+
+    fn notify_left(&self, id: u16, remaining: &[u16]) {
+        self.send_to(id, &P37E::new(id, id));
+
+        for member in remaining {
+            self.send_to(*member, &P37E::new(*member, id));
+        }
+
+        match remaining.len() {
+            0 | 1 => {
+                for member in remaining {
+                    self.send_to(*member, &P37E::new(*member, *member));
+                }
+            }
+            _ => self.send_party(remaining),
+        }
+    }
+
+    pub fn leave_party(&mut self, id: u16) {
+        self.parties.cancel_invites(id);
+
+        if let Some(remaining) = self.parties.leave(id) {
+            self.notify_left(id, &remaining);
+        }
+    }
+
+    // members see each other's life and mana, only what changed since the last look is sent
+
+    fn process_parties(&mut self) {
+        for party in self.parties.all() {
+            for member in &party.members {
+                let score = match self.players.get(member) {
+                    Some(player) => player.score(),
+                    None => continue,
+                };
+
+                let points = [score.hp, score.mp, score.max_hp, score.max_mp];
+
+                if !self.parties.points_changed(*member, points) {
+                    continue;
+                }
+
+                let packet = P181::new(*member, score.hp, score.mp, score.max_hp, score.max_mp);
+
+                for other in party.members.iter().filter(|other| *other != member) {
+                    self.send_to(*other, &packet);
+                }
+            }
+        }
+    }
+}
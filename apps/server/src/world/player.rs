@@ -0,0 +1,129 @@
+use packets::structs::{
+    packets::{p114::P114, p336::P336, p337::P337, p364::P364, p3b9::P3B9},
+    score::SScore,
+};
+use std::{collections::HashMap, time::Instant};
+
+use crate::{
+    consts::REGEN_INTERVAL,
+    repository::accounts::GmLevel,
+    session::Session,
+    structs::{character::Character, storage::Storage},
+    world::{affects::Affects, chat::ChatLimit, movement::Movement, vendor::Vendor},
+};
+
+#[derive(Clone)]
+pub struct Player {
+    pub session: Session,
+    pub username: String,
+    pub slot: usize,
+    pub character: Character,
+    pub storage: Storage,
+    pub movement: Movement,
+    pub next_regen: Instant,
+    pub next_attack: Instant,
+    pub cooldowns: HashMap<u16, Instant>,
+    pub affects: Affects,
+    pub vendor: Option<Vendor>,
+    pub guild: Option<u16>,
+    pub chat: ChatLimit,
+    pub muted_until: u64,
+    pub gm_level: GmLevel,
+}
+
+impl Player {
+    pub fn new(
+        session: Session,
+        username: String,
+        slot: usize,
+        character: Character,
+        storage: Storage,
+        now: Instant,
+    ) -> Self {
+        let affects = Affects::restore(&character.affects, now);
+
+        Self {
+            session,
+            username,
+            slot,
+            character,
+            storage,
+            movement: Movement::new(now),
+            next_regen: now + REGEN_INTERVAL,
+            next_attack: now,
+            cooldowns: HashMap::new(),
+            affects,
+            vendor: None,
+            guild: None,
+            chat: ChatLimit::default(),
+            muted_until: 0,
+            gm_level: GmLevel::Player,
+        }
+    }
+
+    pub fn id(&self) -> u16 {
+        *self.session.id
+    }
+
+    // the character score with whatever is currently affecting it
+
+    pub fn score(&self) -> SScore {
+        let mut score = self.character.score();
+        score.speed = self.affects.speed(score.speed);
+        score
+    }
+
+    // packets
+
+    pub fn login_packet(&self) -> P114 {
+        P114::new(
+            self.id(),
+            self.slot as u16,
+            self.character.position.to_struct(),
+            self.character.to_mob(),
+        )
+    }
+
+    pub fn spawn_packet(&self) -> P364 {
+        let mut p = P364::new(
+            self.id(),
+            &self.character.name,
+            self.character.position.to_struct(),
+            self.score(),
+        );
+
+        p.equip = self.character.equip_indexes();
+        p.affects = self.affects.kinds();
+        p.guild = self.guild.unwrap_or(0);
+
+        // an open shop shows its title over the seller
+
+        if let Some(vendor) = &self.vendor {
+            p.set_tab(&vendor.title);
+        }
+
+        p
+    }
+
+    pub fn score_packet(&self) -> P336 {
+        let mut p = P336::new(self.id(), self.score());
+        p.affects = self.affects.kinds();
+        p
+    }
+
+    pub fn etc_packet(&self) -> P337 {
+        let mut p = P337::new(self.id());
+
+        p.exp = self.character.exp as i64;
+        p.learned_skill = self.character.learned_skills;
+        p.score_bonus = self.character.stat_points;
+        p.skill_bonus = self.character.skill_points;
+        p.coin = self.character.coin as i32;
+
+        p
+    }
+
+    pub fn affects_packet(&self, now: Instant) -> P3B9 {
+        P3B9::new(self.id(), self.affects.to_struct(now))
+    }
+}
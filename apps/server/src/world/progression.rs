@@ -0,0 +1,96 @@
+use packets::structs::packets::{
+    p181::P181,
+    p277::{BONUS_SCORE, BONUS_SKILL},
+};
+
+use crate::{
+    consts::SPAWN_POSITION,
+    structs::{character::ProgressError, evolution::Evolution, position::Position},
+    world::World,
+};
+
+impl World {
+    pub async fn apply_bonus(
+        &self,
+        id: u16,
+        bonus_type: i16,
+        detail: i16,
+    ) -> Result<(), ProgressError> {
+        let mut entities = self.entities.lock().await;
+        let data = self.data();
+
+        let player = match entities.players.get_mut(&id) {
+            Some(player) => player,
+            None => return Ok(()),
+        };
+
+        match bonus_type {
+            BONUS_SCORE => player.character.spend_stat_point(detail)?,
+            BONUS_SKILL => {
+                let skill = usize::try_from(detail)
+                    .ok()
+                    .and_then(|index| data.skills.get(index))
+                    .ok_or(ProgressError::UnknownSkill)?;
+
+                if skill.class() != Some(player.character.class) {
+                    return Err(ProgressError::WrongClass);
+                }
+
+                let cost = skill.points.clamp(0, u16::MAX as i32) as u16;
+                player.character.learn_skill(skill.bit(), cost)?;
+            }
+            _ => return Err(ProgressError::InvalidStat),
+        }
+
+        player.session.send(&player.score_packet());
+        player.session.send(&player.etc_packet());
+
+        entities.send_points(id);
+
+        Ok(())
+    }
+
+    pub async fn evolve(&self, id: u16) -> Result<Evolution, ProgressError> {
+        let mut entities = self.entities.lock().await;
+
+        let player = match entities.players.get_mut(&id) {
+            Some(player) => player,
+            None => return Err(ProgressError::NotMaxLevel),
+        };
+
+        let evolution = player.character.evolve()?;
+
+        player.session.send(&player.score_packet());
+        player.session.send(&player.etc_packet());
+
+        entities.send_points(id);
+
+        Ok(evolution)
+    }
+
+    // a dead player comes back in town with everything restored
+
+    pub async fn restart(&self, id: u16) {
+        let mut entities = self.entities.lock().await;
+
+        let player = match entities.players.get_mut(&id) {
+            Some(player) if player.character.hp <= 0 => player,
+            _ => return,
+        };
+
+        let score = player.character.score();
+        player.character.hp = score.max_hp;
+        player.character.mp = score.max_mp;
+
+        player.session.send(&P181::new(
+            id,
+            score.max_hp,
+            score.max_mp,
+            score.max_hp,
+            score.max_mp,
+        ));
+
+        let to = Position::new(SPAWN_POSITION.0, SPAWN_POSITION.1);
+        entities.teleport(id, to, self.clock.now());
+    }
+}
@@ -0,0 +1,168 @@
+use packets::structs::packets::{p101::P101, p333::P333};
+use tokio::sync::MutexGuard;
+
+use crate::{
+    combat::rules::in_range,
+    consts::{MAX_COIN, NPC_TALK_RANGE},
+    scripting::{
+        api::{Action, Context},
+        Hook, ScriptError,
+    },
+    structs::{
+        inventory::{ItemError, ItemTransaction, Slot},
+        item::Item,
+    },
+    world::{Entities, World},
+};
+
+impl World {
+    pub async fn talk(&self, id: u16, npc: u16) -> Result<(), ScriptError> {
+        let entities = self.entities.lock().await;
+
+        let position = entities
+            .players
+            .get(&id)
+            .ok_or(ScriptError::NotFound)?
+            .character
+            .position;
+        let mob = entities
+            .mobs
+            .get(&npc)
+            .filter(|mob| mob.is_alive())
+            .ok_or(ScriptError::NotFound)?;
+
+        if !in_range(&position, &mob.position, NPC_TALK_RANGE) {
+            return Err(ScriptError::TooFar);
+        }
+
+        let hook = Hook::Talk(mob.name());
+
+        if !self.scripts.has(&hook) {
+            return Ok(());
+        }
+
+        self.run_script(entities, id, &hook, None, Some(npc)).await
+    }
+
+    // an item with a script of its own is used through it, false leaves the item to whatever
+    // else it can be used for
+
+    pub async fn use_item(&self, id: u16, slot: Slot) -> Result<bool, ScriptError> {
+        let entities = self.entities.lock().await;
+
+        let item = entities.item_transaction(id)?.get(slot)?;
+        let hook = Hook::Use(item.index);
+
+        if item.is_empty() || !self.scripts.has(&hook) {
+            return Ok(false);
+        }
+
+        self.run_script(entities, id, &hook, Some(slot), None)
+            .await?;
+
+        Ok(true)
+    }
+
+    // the script works on a copy of the player, what it asked for is applied in one go once
+    // it is done and none of it is when any part fails
+
+    pub async fn run_script<'a>(
+        &'a self,
+        mut entities: MutexGuard<'a, Entities>,
+        id: u16,
+        hook: &Hook,
+        used: Option<Slot>,
+        speaker: Option<u16>,
+    ) -> Result<(), ScriptError> {
+        let player = entities.players.get(&id).ok_or(ScriptError::NotFound)?;
+        let context = Context::new(&player.character, player.guild);
+
+        let (context, consumed) = self.scripts.run(hook, context)?;
+
+        let mut transaction = entities.item_transaction(id)?;
+
+        if let Some(slot) = used.filter(|_| consumed) {
+            transaction.consume(slot, 1)?;
+        }
+
+        for action in context.actions.iter() {
+            match action {
+                Action::GiveItem(index, amount) => {
+                    self.give_items(&mut transaction, *index, *amount)?
+                }
+                Action::TakeItem(index, amount) => transaction.remove(*index, *amount)?,
+                Action::GiveCoin(coin) => {
+                    transaction.character.coin = transaction
+                        .character
+                        .coin
+                        .checked_add(*coin)
+                        .filter(|coin| *coin <= MAX_COIN)
+                        .ok_or(ScriptError::CoinLimit)?;
+                }
+                Action::Teleport(to) if !self.map.contains(to) || self.map.is_blocked(to) => {
+                    println!("world.run_script.error: {} teleports into {:?}", hook, to);
+                    return Err(ScriptError::Failed);
+                }
+                Action::Teleport(_) | Action::Say(_) => {}
+            }
+        }
+
+        let player = entities.players.get(&id).ok_or(ScriptError::NotFound)?;
+
+        if !transaction.changes().is_empty()
+            || transaction.character.coin != player.character.coin
+            || context.flags != player.character.quests
+        {
+            transaction.character.quests = context.flags.clone();
+            entities = self
+                .commit_and_relock(entities, vec![(id, transaction)])
+                .await?;
+        }
+
+        for action in context.actions {
+            match action {
+                Action::Say(text) => match speaker {
+                    Some(npc) => entities.send_to(id, &P333::new(npc, &text)),
+                    None => entities.send_to(id, &P101::new(&text)),
+                },
+                Action::Teleport(to) => entities.teleport(id, to, self.clock.now()),
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    // stackable items are handed out in full stacks, anything else one by one
+
+    fn give_items(
+        &self,
+        transaction: &mut ItemTransaction,
+        index: i16,
+        amount: u16,
+    ) -> Result<(), ItemError> {
+        let data = self.data();
+        let info = data.items.get(index).ok_or(ItemError::NotFound)?;
+
+        let stack = match info.is_stackable() {
+            true => info.max_stack.max(1),
+            false => 1,
+        };
+
+        let mut left = amount;
+
+        while left > 0 {
+            let given = left.min(stack);
+            let mut item = Item::new(index);
+
+            if given > 1 {
+                item.set_amount(given);
+            }
+
+            transaction.give(item, &data.items)?;
+            left -= given;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,287 @@
+use packets::structs::{
+    item::SItem,
+    mob::SMob,
+    packets::p17c::{P17C, SHOP_LEN},
+};
+use std::{
+    fmt::Display,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use crate::{
+    consts::{ITEM_EFFECT_AMOUNT, MAX_COIN, SELL_PRICE_PERCENT, SHOP_RANGE},
+    structs::{
+        inventory::{ItemError, Slot, SlotType},
+        item::Item,
+    },
+    world::{Entities, World},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShopError {
+    Item(ItemError),
+    NotMerchant,
+    TooFar,
+    SoldOut,
+    NotEnoughCoin,
+    CoinLimit,
+    CannotSell,
+}
+
+impl Display for ShopError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShopError::Item(error) => write!(f, "{}", error),
+            ShopError::NotMerchant => write!(f, "Este personagem não vende nada."),
+            ShopError::TooFar => write!(f, "Muito longe do vendedor."),
+            ShopError::SoldOut => write!(f, "Este item está esgotado."),
+            ShopError::NotEnoughCoin => write!(f, "Gold insuficiente."),
+            ShopError::CoinLimit => write!(f, "Você não pode carregar mais gold."),
+            ShopError::CannotSell => write!(f, "Este item não pode ser vendido."),
+        }
+    }
+}
+
+impl From<ItemError> for ShopError {
+    fn from(error: ItemError) -> Self {
+        ShopError::Item(error)
+    }
+}
+
+// what a merchant sells is what its template carries, an amount on a carried item limits how
+// many can be bought until the merchant comes back
+
+#[derive(Debug, Clone)]
+pub struct Shop {
+    items: [Item; SHOP_LEN],
+    stock: [Option<u16>; SHOP_LEN],
+}
+
+impl Shop {
+    pub fn from_template(template: &SMob) -> Self {
+        let mut items = [Item::default(); SHOP_LEN];
+        let mut stock = [None; SHOP_LEN];
+
+        for (slot, carried) in template.inventory.iter().take(SHOP_LEN).enumerate() {
+            let mut item = Item::from_struct(carried);
+
+            if item.effect(ITEM_EFFECT_AMOUNT).is_some() {
+                stock[slot] = Some(item.amount());
+                item.remove_effect(ITEM_EFFECT_AMOUNT);
+            }
+
+            items[slot] = item;
+        }
+
+        Self { items, stock }
+    }
+
+    pub fn get(&self, slot: usize) -> Result<Item, ShopError> {
+        let item = self
+            .items
+            .get(slot)
+            .copied()
+            .ok_or(ItemError::InvalidSlot)?;
+
+        if item.is_empty() {
+            return Err(ItemError::Empty.into());
+        }
+
+        if self.stock[slot] == Some(0) {
+            return Err(ShopError::SoldOut);
+        }
+
+        Ok(item)
+    }
+
+    pub fn take(&mut self, slot: usize) {
+        if let Some(Some(stock)) = self.stock.get_mut(slot) {
+            *stock = stock.saturating_sub(1);
+        }
+    }
+
+    pub fn restore(&mut self, slot: usize) {
+        if let Some(Some(stock)) = self.stock.get_mut(slot) {
+            *stock = stock.saturating_add(1);
+        }
+    }
+
+    // sold out items are shown with nothing left so the client greys them out
+
+    pub fn to_struct(&self) -> [SItem; SHOP_LEN] {
+        let mut items = self.items.map(|item| item.to_struct());
+
+        for (slot, stock) in self.stock.iter().enumerate() {
+            if let Some(stock) = stock {
+                let mut item = self.items[slot];
+                item.set_amount((*stock).min(u8::MAX as u16));
+                items[slot] = item.to_struct();
+            }
+        }
+
+        items
+    }
+}
+
+// the tax is added on top of every purchase, what it brings in goes to the castle the trade
+// happened under
+
+pub struct Taxes {
+    rate: AtomicU32,
+}
+
+impl Taxes {
+    pub fn new(rate: u32) -> Self {
+        Self {
+            rate: AtomicU32::new(rate),
+        }
+    }
+
+    pub fn rate(&self) -> u32 {
+        self.rate.load(Ordering::Relaxed)
+    }
+
+    pub fn set_rate(&self, percent: u32) {
+        self.rate.store(percent, Ordering::Relaxed);
+    }
+}
+
+impl Default for Taxes {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+pub fn tax(price: u32, rate: u32) -> u32 {
+    (price as u64 * rate as u64 / 100).min(u32::MAX as u64) as u32
+}
+
+pub fn sell_price(price: i32, amount: u16) -> u32 {
+    let total = price.max(0) as u64 * amount as u64 * SELL_PRICE_PERCENT as u64 / 100;
+    total.min(u32::MAX as u64) as u32
+}
+
+impl World {
+    pub async fn open_shop(&self, id: u16, merchant: u16) -> Result<(), ShopError> {
+        let entities = self.entities.lock().await;
+
+        let items = entities.shop_in_reach(id, merchant)?.to_struct();
+
+        entities.send_to(id, &P17C::new(merchant, items, self.taxes.rate() as i32));
+
+        Ok(())
+    }
+
+    pub async fn buy(&self, id: u16, merchant: u16, slot: usize) -> Result<(), ShopError> {
+        let mut entities = self.entities.lock().await;
+        let data = self.data();
+
+        let item = entities.shop_in_reach(id, merchant)?.get(slot)?;
+        let info = data.items.get(item.index).ok_or(ItemError::Unknown)?;
+
+        let price = info.price.max(0) as u32;
+        let tax = tax(price, self.taxes.rate());
+        let total = price.saturating_add(tax);
+
+        let mut transaction = entities.item_transaction(id)?;
+
+        if transaction.character.coin < total {
+            return Err(ShopError::NotEnoughCoin);
+        }
+
+        transaction.character.coin -= total;
+        transaction.give(item, &data.items)?;
+
+        // the stock goes down before the save so nobody buys the last one meanwhile, and comes
+        // back when the save fails
+
+        if let Some(shop) = entities
+            .mobs
+            .get_mut(&merchant)
+            .and_then(|mob| mob.shop.as_mut())
+        {
+            shop.take(slot);
+        }
+
+        let entities = match self
+            .commit_and_relock(entities, vec![(id, transaction)])
+            .await
+        {
+            Ok(entities) => entities,
+            Err(error) => {
+                if let Some(shop) = self
+                    .entities
+                    .lock()
+                    .await
+                    .mobs
+                    .get_mut(&merchant)
+                    .and_then(|mob| mob.shop.as_mut())
+                {
+                    shop.restore(slot);
+                }
+
+                return Err(error.into());
+            }
+        };
+
+        self.collect_taxes(&entities, id, tax);
+
+        if let Some(shop) = entities
+            .mobs
+            .get(&merchant)
+            .and_then(|mob| mob.shop.as_ref())
+        {
+            let items = shop.to_struct();
+            entities.send_to(id, &P17C::new(merchant, items, self.taxes.rate() as i32));
+        }
+
+        Ok(())
+    }
+
+    pub async fn sell(&self, id: u16, merchant: u16, slot: Slot) -> Result<(), ShopError> {
+        let entities = self.entities.lock().await;
+        let data = self.data();
+
+        entities.shop_in_reach(id, merchant)?;
+
+        if slot.kind != SlotType::Inventory {
+            return Err(ShopError::CannotSell);
+        }
+
+        let mut transaction = entities.item_transaction(id)?;
+
+        let item = transaction.take(slot)?;
+        let info = data.items.get(item.index).ok_or(ShopError::CannotSell)?;
+
+        if info.price <= 0 {
+            return Err(ShopError::CannotSell);
+        }
+
+        let coin = transaction
+            .character
+            .coin
+            .checked_add(sell_price(info.price, item.amount()))
+            .filter(|coin| *coin <= MAX_COIN)
+            .ok_or(ShopError::CoinLimit)?;
+
+        transaction.character.coin = coin;
+
+        self.commit_items(entities, vec![(id, transaction)]).await?;
+
+        Ok(())
+    }
+}
+
+impl Entities {
+    fn shop_in_reach(&self, id: u16, merchant: u16) -> Result<&Shop, ShopError> {
+        let player = self.players.get(&id).ok_or(ItemError::NotFound)?;
+        let mob = self.mobs.get(&merchant).ok_or(ShopError::NotMerchant)?;
+        let shop = mob.shop.as_ref().ok_or(ShopError::NotMerchant)?;
+
+        if player.character.position.distance(&mob.position) > SHOP_RANGE {
+            return Err(ShopError::TooFar);
+        }
+
+        Ok(shop)
+    }
+}
@@ -0,0 +1,169 @@
+use packets::{serializer::deserialize, structs::spell::SSpell};
+use std::{
+    fmt::Display,
+    fs,
+    mem::size_of,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{
+    combat::{rules::CombatError, Attack, AttackKind},
+    consts::{SKILLS_PER_CLASS, SKILL_DATA_FILE},
+    structs::class::Class,
+    world::data::DataError,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkillError {
+    Unknown,
+    Passive,
+    NotLearned,
+    Cooldown,
+    NoMana,
+    Combat(CombatError),
+}
+
+impl Display for SkillError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SkillError::Unknown => write!(f, "unknown skill"),
+            SkillError::Passive => write!(f, "passive skill"),
+            SkillError::NotLearned => write!(f, "skill not learned"),
+            SkillError::Cooldown => write!(f, "skill in cooldown"),
+            SkillError::NoMana => write!(f, "not enough mana"),
+            SkillError::Combat(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<CombatError> for SkillError {
+    fn from(error: CombatError) -> Self {
+        SkillError::Combat(error)
+    }
+}
+
+// instance types: 1 hits physically, 2 to 5 hit with magic of one of the four elements and
+// 6 heals, anything else has no instant effect
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instance {
+    None,
+    Damage(Attack),
+    Heal(i32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SkillAffect {
+    pub kind: u8,
+    pub value: i32,
+    pub duration: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct Skill {
+    pub index: u16,
+    pub points: i32,
+    pub mana: i32,
+    pub cooldown: Duration,
+    pub range: u16,
+    pub instance: Instance,
+    pub affect: Option<SkillAffect>,
+    pub aggressive: bool,
+    pub max_targets: usize,
+    pub party: bool,
+    pub passive: bool,
+}
+
+impl Skill {
+    pub fn from_struct(index: u16, spell: &SSpell) -> Self {
+        let instance = match spell.instance_type {
+            1 => Instance::Damage(Attack {
+                kind: AttackKind::Physical,
+                bonus: spell.instance_value.max(0),
+            }),
+            2..=5 => Instance::Damage(Attack {
+                kind: AttackKind::Magic {
+                    element: (spell.instance_type - 2) as usize,
+                },
+                bonus: spell.instance_value.max(0),
+            }),
+            6 => Instance::Heal(spell.instance_value.max(0)),
+            _ => Instance::None,
+        };
+
+        let affect = (spell.affect_type > 0 && spell.affect_time > 0).then(|| SkillAffect {
+            kind: spell.affect_type.clamp(0, u8::MAX as i32) as u8,
+            value: spell.affect_value,
+            duration: Duration::from_secs(spell.affect_time as u64),
+        });
+
+        Self {
+            index,
+            points: spell.skill_point,
+            mana: spell.mana_spent.max(0),
+            cooldown: Duration::from_secs(spell.delay.max(0) as u64),
+            range: spell.range.clamp(0, u16::MAX as i32) as u16,
+            instance,
+            affect,
+            aggressive: spell.aggressive != 0,
+            max_targets: spell.max_target.max(1) as usize,
+            party: spell.party != 0,
+            passive: spell.passive != 0,
+        }
+    }
+
+    // every class owns a block of skills, the learned mask only covers that block
+
+    pub fn class(&self) -> Option<Class> {
+        Class::from_index(self.index as i32 / SKILLS_PER_CLASS as i32)
+    }
+
+    pub fn bit(&self) -> u32 {
+        1 << (self.index as u32 % SKILLS_PER_CLASS as u32)
+    }
+}
+
+pub fn load(folder: PathBuf) -> Vec<Skill> {
+    match read(&folder, false) {
+        Ok(skills) => skills,
+        Err(_error) => {
+            println!("skills.load: {} not found, no skills", SKILL_DATA_FILE);
+            Vec::new()
+        }
+    }
+}
+
+pub fn read(folder: &Path, strict: bool) -> Result<Vec<Skill>, DataError> {
+    let buf = match fs::read(folder.join(SKILL_DATA_FILE)) {
+        Ok(buf) => buf,
+        Err(_error) => return Err(DataError::Missing(SKILL_DATA_FILE.to_string())),
+    };
+
+    let trailing = buf.len() % size_of::<SSpell>();
+
+    if trailing != 0 && strict {
+        return Err(DataError::Invalid(format!(
+            "{} tem {} bytes sobrando",
+            SKILL_DATA_FILE, trailing
+        )));
+    }
+
+    if trailing != 0 {
+        println!(
+            "skills.load.error: {} has {} trailing bytes",
+            SKILL_DATA_FILE, trailing
+        );
+    }
+
+    let skills: Vec<Skill> = buf
+        .chunks_exact(size_of::<SSpell>())
+        .filter_map(deserialize::<SSpell>)
+        .enumerate()
+        .map(|(index, spell)| Skill::from_struct(index as u16, &spell))
+        .collect();
+
+    println!("Loaded {} skills", skills.len());
+
+    Ok(skills)
+}
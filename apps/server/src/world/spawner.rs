@@ -0,0 +1,84 @@
+use rand::{thread_rng, Rng};
+use std::{collections::HashMap, time::Instant};
+
+use crate::{
+    consts::SPAWN_ATTEMPTS,
+    structs::position::Position,
+    world::{generators::Waypoint, map::Map},
+};
+
+// keeps track of the groups a generator has alive and when the missing ones come back
+
+pub struct Spawner {
+    pub groups: HashMap<usize, Vec<u16>>,
+    pub pending: Vec<Instant>,
+    next_group: usize,
+}
+
+impl Spawner {
+    pub fn new(max_groups: u16, now: Instant) -> Self {
+        Self {
+            groups: HashMap::new(),
+            pending: vec![now; max_groups as usize],
+            next_group: 0,
+        }
+    }
+
+    pub fn take_due(&mut self, now: Instant) -> usize {
+        let before = self.pending.len();
+        self.pending.retain(|at| *at > now);
+        before - self.pending.len()
+    }
+
+    pub fn open_group(&mut self) -> usize {
+        let group = self.next_group;
+        self.next_group += 1;
+        self.groups.insert(group, Vec::new());
+        group
+    }
+
+    pub fn add_member(&mut self, group: usize, id: u16) {
+        if let Some(members) = self.groups.get_mut(&group) {
+            members.push(id);
+        }
+    }
+
+    // returns true once the whole group is gone
+
+    pub fn remove_member(&mut self, group: usize, id: u16) -> bool {
+        match self.groups.get_mut(&group) {
+            Some(members) => {
+                members.retain(|member| *member != id);
+
+                if members.is_empty() {
+                    self.groups.remove(&group);
+                    return true;
+                }
+
+                false
+            }
+            None => false,
+        }
+    }
+}
+
+pub fn random_position(map: &Map, waypoint: &Waypoint) -> Option<Position> {
+    let mut rng = thread_rng();
+    let range = waypoint.range as i32;
+
+    for _ in 0..SPAWN_ATTEMPTS {
+        let x = waypoint.position.x as i32 + rng.gen_range(-range..=range);
+        let y = waypoint.position.y as i32 + rng.gen_range(-range..=range);
+
+        let position = match (u16::try_from(x), u16::try_from(y)) {
+            (Ok(x), Ok(y)) => Position::new(x, y),
+            _ => continue,
+        };
+
+        if map.contains(&position) && !map.is_blocked(&position) {
+            return Some(position);
+        }
+    }
+
+    None
+}
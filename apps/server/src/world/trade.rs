@@ -0,0 +1,513 @@
+use packets::structs::packets::{
+    p383::{P383, TRADE_CONFIRM, TRADE_LEN, TRADE_LOCK, TRADE_OFFER},
+    p384::P384,
+};
+use std::{collections::HashMap, fmt::Display};
+
+use crate::{
+    consts::{MAX_COIN, TRADE_RANGE},
+    structs::{
+        inventory::{ItemError, ItemTransaction, Slot, SlotType},
+        item::Item,
+        item_table::ItemTable,
+    },
+    world::{Entities, World},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradeError {
+    Item(ItemError),
+    NotFound,
+    Myself,
+    Busy,
+    TooFar,
+    Locked,
+    NotLocked,
+    InvalidOffer,
+    Changed,
+    NotEnoughCoin,
+    CoinLimit,
+}
+
+impl Display for TradeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TradeError::Item(error) => write!(f, "{}", error),
+            TradeError::NotFound => write!(f, "Jogador não encontrado."),
+            TradeError::Myself => write!(f, "Você não pode negociar com você mesmo."),
+            TradeError::Busy => write!(f, "O jogador já está negociando."),
+            TradeError::TooFar => write!(f, "Muito longe para negociar."),
+            TradeError::Locked => write!(f, "A oferta já foi travada."),
+            TradeError::NotLocked => write!(f, "As duas ofertas precisam estar travadas."),
+            TradeError::InvalidOffer => write!(f, "Oferta inválida."),
+            TradeError::Changed => write!(f, "Os itens da oferta mudaram."),
+            TradeError::NotEnoughCoin => write!(f, "Gold insuficiente."),
+            TradeError::CoinLimit => write!(f, "O jogador não pode carregar tanto gold."),
+        }
+    }
+}
+
+impl From<ItemError> for TradeError {
+    fn from(error: ItemError) -> Self {
+        TradeError::Item(error)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradeState {
+    Open,
+    Locked,
+    Confirmed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradeAction {
+    Offer,
+    Lock,
+    Confirm,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Offer {
+    pub slots: Vec<Slot>,
+    pub items: Vec<Item>,
+    pub coin: u32,
+}
+
+impl Offer {
+    // what sits in the offered slots is kept with them, so the other side gets exactly what
+    // they were shown
+
+    pub fn seal(mut self, transaction: &ItemTransaction) -> Result<Self, TradeError> {
+        self.items = self
+            .slots
+            .iter()
+            .map(|slot| transaction.get(*slot))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.validate(transaction)?;
+
+        Ok(self)
+    }
+
+    // only whole inventory slots can be offered, each of them once
+
+    pub fn validate(&self, transaction: &ItemTransaction) -> Result<(), TradeError> {
+        if self.items.len() != self.slots.len() {
+            return Err(TradeError::InvalidOffer);
+        }
+
+        for (position, (slot, item)) in self.slots.iter().zip(&self.items).enumerate() {
+            if slot.kind != SlotType::Inventory || self.slots[..position].contains(slot) {
+                return Err(TradeError::InvalidOffer);
+            }
+
+            let current = transaction.get(*slot)?;
+
+            if current.is_empty() {
+                return Err(ItemError::Empty.into());
+            }
+
+            if current != *item {
+                return Err(TradeError::Changed);
+            }
+        }
+
+        if transaction.character.coin < self.coin {
+            return Err(TradeError::NotEnoughCoin);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Trade {
+    pub partner: u16,
+    pub offer: Offer,
+    pub state: TradeState,
+}
+
+// both sides of a trade are kept under their own id, a side is never there without the other
+
+#[derive(Debug, Default)]
+pub struct Trades {
+    trades: HashMap<u16, Trade>,
+    requests: HashMap<u16, u16>,
+}
+
+impl Trades {
+    pub fn get(&self, id: u16) -> Option<&Trade> {
+        self.trades.get(&id)
+    }
+
+    pub fn ids(&self) -> Vec<u16> {
+        self.trades.keys().copied().collect()
+    }
+
+    // a request answered by the other side opens the trade for both
+
+    pub fn request(&mut self, from: u16, to: u16) -> Result<bool, TradeError> {
+        if from == to {
+            return Err(TradeError::Myself);
+        }
+
+        if self.trades.contains_key(&from) || self.trades.contains_key(&to) {
+            return Err(TradeError::Busy);
+        }
+
+        if self.requests.get(&to) != Some(&from) {
+            self.requests.insert(from, to);
+            return Ok(false);
+        }
+
+        self.requests.remove(&to);
+        self.requests.remove(&from);
+
+        for (id, partner) in [(from, to), (to, from)] {
+            self.trades.insert(
+                id,
+                Trade {
+                    partner,
+                    offer: Offer::default(),
+                    state: TradeState::Open,
+                },
+            );
+        }
+
+        Ok(true)
+    }
+
+    // changing an offer takes back whatever the other side had agreed to
+
+    pub fn set_offer(&mut self, id: u16, offer: Offer) -> Result<(), TradeError> {
+        let trade = self.trades.get_mut(&id).ok_or(TradeError::NotFound)?;
+
+        if trade.state != TradeState::Open {
+            return Err(TradeError::Locked);
+        }
+
+        trade.offer = offer;
+        let partner = trade.partner;
+
+        if let Some(other) = self.trades.get_mut(&partner) {
+            other.state = TradeState::Open;
+        }
+
+        Ok(())
+    }
+
+    pub fn lock(&mut self, id: u16) -> Result<(), TradeError> {
+        let trade = self.trades.get_mut(&id).ok_or(TradeError::NotFound)?;
+
+        if trade.state == TradeState::Open {
+            trade.state = TradeState::Locked;
+        }
+
+        Ok(())
+    }
+
+    // returns true once both sides confirmed, that is when the swap has to happen
+
+    pub fn confirm(&mut self, id: u16) -> Result<bool, TradeError> {
+        let partner = self.trades.get(&id).ok_or(TradeError::NotFound)?.partner;
+        let other = self.trades.get(&partner).ok_or(TradeError::NotFound)?.state;
+
+        let trade = self.trades.get_mut(&id).ok_or(TradeError::NotFound)?;
+
+        if trade.state == TradeState::Open || other == TradeState::Open {
+            return Err(TradeError::NotLocked);
+        }
+
+        trade.state = TradeState::Confirmed;
+
+        Ok(other == TradeState::Confirmed)
+    }
+
+    // ends whatever the player had going on and tells who was on the other side
+
+    pub fn cancel(&mut self, id: u16) -> Option<u16> {
+        self.requests.remove(&id);
+        self.requests.retain(|_, to| *to != id);
+
+        let trade = self.trades.remove(&id)?;
+        self.trades.remove(&trade.partner);
+
+        Some(trade.partner)
+    }
+}
+
+// every offered item leaves both sides before anything is handed over, so the free slots of
+// what was given away can already take what comes back
+
+pub fn exchange(
+    first: (&mut ItemTransaction, &Offer),
+    second: (&mut ItemTransaction, &Offer),
+    items: &ItemTable,
+) -> Result<(), TradeError> {
+    let (first, first_offer) = first;
+    let (second, second_offer) = second;
+
+    first_offer.validate(first)?;
+    second_offer.validate(second)?;
+
+    let first_items = first_offer
+        .slots
+        .iter()
+        .map(|slot| first.take(*slot))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let second_items = second_offer
+        .slots
+        .iter()
+        .map(|slot| second.take(*slot))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    first.character.coin = receive_coin(first.character.coin, first_offer, second_offer)?;
+    second.character.coin = receive_coin(second.character.coin, second_offer, first_offer)?;
+
+    for item in second_items {
+        first.give(item, items)?;
+    }
+
+    for item in first_items {
+        second.give(item, items)?;
+    }
+
+    Ok(())
+}
+
+fn receive_coin(coin: u32, given: &Offer, received: &Offer) -> Result<u32, TradeError> {
+    coin.checked_sub(given.coin)
+        .ok_or(TradeError::NotEnoughCoin)?
+        .checked_add(received.coin)
+        .filter(|coin| *coin <= MAX_COIN)
+        .ok_or(TradeError::CoinLimit)
+}
+
+impl World {
+    pub async fn trade(
+        &self,
+        id: u16,
+        target: u16,
+        offer: Offer,
+        action: TradeAction,
+    ) -> Result<(), TradeError> {
+        let mut entities = self.entities.lock().await;
+        let data = self.data();
+
+        let partner = match entities.trades.get(id) {
+            Some(trade) if trade.partner != target => return Err(TradeError::Busy),
+            Some(trade) => trade.partner,
+            None => {
+                entities.trade_range(id, target)?;
+
+                if [id, target].iter().any(|id| {
+                    entities
+                        .players
+                        .get(id)
+                        .is_some_and(|player| player.vendor.is_some())
+                }) {
+                    return Err(TradeError::Busy);
+                }
+
+                match entities.trades.request(id, target)? {
+                    true => {
+                        entities.send_to(id, &P383::new(target, target));
+                        entities.send_to(target, &P383::new(id, id));
+                    }
+                    false => entities.send_to(target, &P383::new(id, id)),
+                }
+
+                return Ok(());
+            }
+        };
+
+        if let Err(error) = entities.trade_range(id, partner) {
+            entities.cancel_trade(id);
+            return Err(error);
+        }
+
+        match action {
+            TradeAction::Offer => {
+                let offer = offer.seal(&entities.item_transaction(id)?)?;
+                entities.trades.set_offer(id, offer)?;
+            }
+            TradeAction::Lock => entities.trades.lock(id)?,
+            TradeAction::Confirm => {
+                if entities.trades.confirm(id)? {
+                    let changes = entities.finish_trade(id, &data.items)?;
+                    self.commit_items(entities, changes).await?;
+
+                    return Ok(());
+                }
+            }
+        }
+
+        entities.send_trade(id);
+        entities.send_trade(partner);
+
+        Ok(())
+    }
+
+    pub async fn cancel_trade(&self, id: u16) {
+        self.entities.lock().await.cancel_trade(id);
+    }
+
+    pub async fn process_trades(&self) {
+        self.entities.lock().await.process_trades();
+    }
+}
+
+impl Entities {
+    fn trade_range(&self, id: u16, other: u16) -> Result<(), TradeError> {
+        let player = self.players.get(&id).ok_or(TradeError::NotFound)?;
+        let other = self.players.get(&other).ok_or(TradeError::NotFound)?;
+
+        if player
+            .character
+            .position
+            .distance(&other.character.position)
+            > TRADE_RANGE
+        {
+            return Err(TradeError::TooFar);
+        }
+
+        Ok(())
+    }
+
+    // shows the side of the trade that belongs to id to the one on the other side
+
+    fn send_trade(&self, id: u16) {
+        let trade = match self.trades.get(id) {
+            Some(trade) => trade,
+            None => return,
+        };
+
+        let mut packet = P383::new(id, id);
+        packet.coin = trade.offer.coin as i32;
+        packet.state = match trade.state {
+            TradeState::Open => TRADE_OFFER,
+            TradeState::Locked => TRADE_LOCK,
+            TradeState::Confirmed => TRADE_CONFIRM,
+        };
+
+        for (position, (slot, item)) in trade
+            .offer
+            .slots
+            .iter()
+            .zip(&trade.offer.items)
+            .enumerate()
+            .take(TRADE_LEN)
+        {
+            packet.items[position] = item.to_struct();
+            packet.slots[position] = slot.index as i8;
+        }
+
+        self.send_to(trade.partner, &packet);
+    }
+
+    pub fn cancel_trade(&mut self, id: u16) {
+        if let Some(partner) = self.trades.cancel(id) {
+            self.send_to(id, &P384::new(id));
+            self.send_to(partner, &P384::new(partner));
+        }
+    }
+
+    // the trade is over either way, what comes back still has to be saved for both
+
+    fn finish_trade(
+        &mut self,
+        id: u16,
+        items: &ItemTable,
+    ) -> Result<Vec<(u16, ItemTransaction)>, TradeError> {
+        let trade = self.trades.get(id).cloned().ok_or(TradeError::NotFound)?;
+        let partner = trade.partner;
+        let partner_offer = self
+            .trades
+            .get(partner)
+            .map(|trade| trade.offer.clone())
+            .ok_or(TradeError::NotFound)?;
+
+        self.cancel_trade(id);
+
+        let mut first = self.item_transaction(id)?;
+        let mut second = self.item_transaction(partner)?;
+
+        exchange(
+            (&mut first, &trade.offer),
+            (&mut second, &partner_offer),
+            items,
+        )?;
+
+        Ok(vec![(id, first), (partner, second)])
+    }
+
+    fn process_trades(&mut self) {
+        for id in self.trades.ids() {
+            let partner = match self.trades.get(id) {
+                Some(trade) => trade.partner,
+                None => continue,
+            };
+
+            if let Err(error) = self.trade_range(id, partner) {
+                self.cancel_trade(id);
+
+                for player in [id, partner] {
+                    if let Some(player) = self.players.get(&player) {
+                        player.session.send_message(&error.to_string());
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::{character::Character, class::Class, storage::Storage};
+
+    fn carrying(index: i16) -> ItemTransaction {
+        let mut character = Character::new("trader".to_string(), Class::TransKnight);
+        character.inventory[0] = Item::new(index);
+
+        ItemTransaction::new(character, Storage::default())
+    }
+
+    fn offer(slots: Vec<Slot>) -> Offer {
+        Offer {
+            slots,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn a_sealed_offer_only_holds_the_same_items() {
+        let slot = Slot::new(SlotType::Inventory, 0);
+        let sealed = offer(vec![slot]).seal(&carrying(1000)).unwrap();
+
+        assert_eq!(sealed.items, vec![Item::new(1000)]);
+        assert_eq!(sealed.validate(&carrying(1000)), Ok(()));
+        assert_eq!(sealed.validate(&carrying(1001)), Err(TradeError::Changed));
+    }
+
+    #[test]
+    fn offers_are_whole_distinct_inventory_slots() {
+        let slot = Slot::new(SlotType::Inventory, 0);
+        let equip = Slot::new(SlotType::Equip, 0);
+
+        assert_eq!(
+            offer(vec![slot, slot]).seal(&carrying(1000)).err(),
+            Some(TradeError::InvalidOffer)
+        );
+        assert_eq!(
+            offer(vec![equip]).seal(&carrying(1000)).err(),
+            Some(TradeError::InvalidOffer)
+        );
+        assert_eq!(
+            offer(vec![slot]).validate(&carrying(1000)),
+            Err(TradeError::InvalidOffer)
+        );
+    }
+}
@@ -0,0 +1,325 @@
+use packets::structs::packets::{
+    p384::P384,
+    p397::{P397, VENDOR_LEN},
+};
+use std::fmt::Display;
+
+use crate::{
+    consts::{MAX_COIN, SHOP_RANGE, VENDOR_TITLE_MAX_LEN},
+    structs::{
+        inventory::{ItemError, ItemTransaction, Slot, SlotType},
+        item::Item,
+    },
+    world::{shop::tax, Entities, World},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VendorError {
+    Item(ItemError),
+    NotFound,
+    Myself,
+    Busy,
+    TooFar,
+    Closed,
+    InvalidListing,
+    PriceChanged,
+    NotEnoughCoin,
+    CoinLimit,
+}
+
+impl Display for VendorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VendorError::Item(error) => write!(f, "{}", error),
+            VendorError::NotFound => write!(f, "Loja não encontrada."),
+            VendorError::Myself => write!(f, "Você não pode comprar da sua própria loja."),
+            VendorError::Busy => write!(f, "Termine a negociação antes de abrir a loja."),
+            VendorError::TooFar => write!(f, "Muito longe da loja."),
+            VendorError::Closed => write!(f, "Este item não está mais à venda."),
+            VendorError::InvalidListing => write!(f, "Loja inválida."),
+            VendorError::PriceChanged => write!(f, "O preço deste item mudou."),
+            VendorError::NotEnoughCoin => write!(f, "Gold insuficiente."),
+            VendorError::CoinLimit => write!(f, "O vendedor não pode carregar tanto gold."),
+        }
+    }
+}
+
+impl From<ItemError> for VendorError {
+    fn from(error: ItemError) -> Self {
+        VendorError::Item(error)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Listing {
+    pub slot: Slot,
+    pub item: Item,
+    pub price: u32,
+}
+
+// listed items stay in the bags, the shop only remembers what was there when it opened so
+// anything moved since then is no longer sold
+
+#[derive(Debug, Clone)]
+pub struct Vendor {
+    pub title: String,
+    listings: [Option<Listing>; VENDOR_LEN],
+}
+
+impl Vendor {
+    pub fn open(
+        title: &str,
+        entries: &[(usize, Slot, u32)],
+        transaction: &ItemTransaction,
+    ) -> Result<Self, VendorError> {
+        let title = title
+            .trim()
+            .chars()
+            .take(VENDOR_TITLE_MAX_LEN)
+            .collect::<String>();
+
+        if title.is_empty() || entries.is_empty() {
+            return Err(VendorError::InvalidListing);
+        }
+
+        let mut listings = [None; VENDOR_LEN];
+
+        for (index, (position, slot, price)) in entries.iter().enumerate() {
+            let listed = entries[..index]
+                .iter()
+                .any(|(other, other_slot, _)| other == position || other_slot == slot);
+
+            if *position >= VENDOR_LEN
+                || slot.kind != SlotType::Inventory
+                || listed
+                || *price == 0
+                || *price > MAX_COIN
+            {
+                return Err(VendorError::InvalidListing);
+            }
+
+            let item = transaction.get(*slot)?;
+
+            if item.is_empty() {
+                return Err(ItemError::Empty.into());
+            }
+
+            listings[*position] = Some(Listing {
+                slot: *slot,
+                item,
+                price: *price,
+            });
+        }
+
+        Ok(Self { title, listings })
+    }
+
+    pub fn get(&self, position: usize) -> Result<Listing, VendorError> {
+        self.listings
+            .get(position)
+            .copied()
+            .flatten()
+            .ok_or(VendorError::Closed)
+    }
+
+    pub fn remove(&mut self, position: usize) {
+        if let Some(listing) = self.listings.get_mut(position) {
+            *listing = None;
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.listings.iter().all(|listing| listing.is_none())
+    }
+
+    pub fn to_packet(&self, owner: u16, tax: u32) -> P397 {
+        let mut packet = P397::new(owner, owner, &self.title);
+        packet.tax = tax.min(i16::MAX as u32) as i16;
+
+        for (position, listing) in self.listings.iter().enumerate() {
+            if let Some(listing) = listing {
+                packet.items[position] = listing.item.to_struct();
+                packet.slots[position] = listing.slot.index as i8;
+                packet.prices[position] = listing.price as i32;
+            }
+        }
+
+        packet
+    }
+}
+
+impl World {
+    pub async fn open_vendor(
+        &self,
+        id: u16,
+        title: &str,
+        entries: &[(usize, Slot, u32)],
+    ) -> Result<(), VendorError> {
+        let mut entities = self.entities.lock().await;
+
+        if entities.trades.get(id).is_some() {
+            return Err(VendorError::Busy);
+        }
+
+        let vendor = Vendor::open(title, entries, &entities.item_transaction(id)?)?;
+        let packet = vendor.to_packet(id, self.taxes.rate());
+
+        let player = match entities.players.get_mut(&id) {
+            Some(player) => player,
+            None => return Ok(()),
+        };
+
+        player.vendor = Some(vendor);
+        player.session.send(&packet);
+
+        let position = player.character.position;
+        let spawn = player.spawn_packet();
+
+        entities.send_in_view(&position, &spawn, Some(id));
+
+        Ok(())
+    }
+
+    pub async fn close_vendor(&self, id: u16) {
+        self.entities.lock().await.close_vendor(id);
+    }
+
+    pub async fn view_vendor(&self, id: u16, owner: u16) -> Result<(), VendorError> {
+        let entities = self.entities.lock().await;
+
+        let packet = entities
+            .vendor_in_reach(id, owner)?
+            .to_packet(owner, self.taxes.rate());
+
+        entities.send_to(id, &packet);
+
+        Ok(())
+    }
+
+    // the buyer pays the listed price and the tax comes out of what the seller gets, the
+    // price the buyer saw has to match so a change in between never charges more
+
+    pub async fn buy_from_vendor(
+        &self,
+        id: u16,
+        owner: u16,
+        position: usize,
+        price: u32,
+    ) -> Result<(), VendorError> {
+        let mut entities = self.entities.lock().await;
+        let data = self.data();
+
+        if id == owner {
+            return Err(VendorError::Myself);
+        }
+
+        let listing = entities.vendor_in_reach(id, owner)?.get(position)?;
+
+        if listing.price != price {
+            return Err(VendorError::PriceChanged);
+        }
+
+        let mut seller = entities.item_transaction(owner)?;
+        let mut buyer = entities.item_transaction(id)?;
+
+        if seller.get(listing.slot)? != listing.item {
+            entities.remove_listing(owner, position, id, self.taxes.rate());
+            return Err(VendorError::Closed);
+        }
+
+        let item = seller.take(listing.slot)?;
+        let tax = tax(price, self.taxes.rate()).min(price);
+
+        buyer.character.coin = buyer
+            .character
+            .coin
+            .checked_sub(price)
+            .ok_or(VendorError::NotEnoughCoin)?;
+
+        seller.character.coin = seller
+            .character
+            .coin
+            .checked_add(price - tax)
+            .filter(|coin| *coin <= MAX_COIN)
+            .ok_or(VendorError::CoinLimit)?;
+
+        buyer.give(item, &data.items)?;
+
+        let mut entities = self
+            .commit_and_relock(entities, vec![(id, buyer), (owner, seller)])
+            .await?;
+
+        self.collect_taxes(&entities, owner, tax);
+        entities.remove_listing(owner, position, id, self.taxes.rate());
+
+        if let Some(player) = entities.players.get(&owner) {
+            player
+                .session
+                .send_message(&format!("Item vendido por {} gold.", price - tax));
+        }
+
+        Ok(())
+    }
+}
+
+impl Entities {
+    fn vendor_in_reach(&self, id: u16, owner: u16) -> Result<&Vendor, VendorError> {
+        let player = self.players.get(&id).ok_or(ItemError::NotFound)?;
+        let owner = self.players.get(&owner).ok_or(VendorError::NotFound)?;
+        let vendor = owner.vendor.as_ref().ok_or(VendorError::NotFound)?;
+
+        if player
+            .character
+            .position
+            .distance(&owner.character.position)
+            > SHOP_RANGE
+        {
+            return Err(VendorError::TooFar);
+        }
+
+        Ok(vendor)
+    }
+
+    // a shop left with nothing to sell closes by itself, otherwise whoever was looking at it
+    // gets what is left
+
+    fn remove_listing(&mut self, owner: u16, position: usize, viewer: u16, tax: u32) {
+        let vendor = match self
+            .players
+            .get_mut(&owner)
+            .and_then(|player| player.vendor.as_mut())
+        {
+            Some(vendor) => vendor,
+            None => return,
+        };
+
+        vendor.remove(position);
+
+        if vendor.is_empty() {
+            self.close_vendor(owner);
+            return;
+        }
+
+        let packet = vendor.to_packet(owner, tax);
+
+        self.send_to(viewer, &packet);
+        self.send_to(owner, &packet);
+    }
+
+    // the title over the seller comes and goes with the shop
+
+    pub fn close_vendor(&mut self, id: u16) {
+        let player = match self.players.get_mut(&id) {
+            Some(player) if player.vendor.is_some() => player,
+            _ => return,
+        };
+
+        player.vendor = None;
+        player.session.send(&P384::new(id));
+
+        let position = player.character.position;
+        let spawn = player.spawn_packet();
+
+        self.send_in_view(&position, &spawn, Some(id));
+    }
+}
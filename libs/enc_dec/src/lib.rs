@@ -42,39 +42,35 @@ pub fn encode(buf: &mut [u8]) {
     let mut checksum_dec = 0;
     let mut key_result;
     let hash_key: u8 = buf[2];
-    let mut key_increment = (KEYS.index(((hash_key as u32) * 2) as usize) & 0xFF) as u32;
+    let mut key_increment = *KEYS.index(((hash_key as u32) * 2) as usize) as u32;
 
     let mut i = 4;
     let mut loop_iterator;
 
     while i < size {
-        match buf.get_mut(i as usize) {
-            Some(curr_buf) => {
-                checksum_dec += *curr_buf as u32;
+        if let Some(curr_buf) = buf.get_mut(i as usize) {
+            checksum_dec += *curr_buf as u32;
 
-                key_result =
-                    (*KEYS.index((((key_increment & 0x800000FF) * 2) + 1) as usize)) as u32;
+            key_result = (*KEYS.index((((key_increment & 0x800000FF) * 2) + 1) as usize)) as u32;
 
-                loop_iterator = i & 3;
+            loop_iterator = i & 3;
 
-                match loop_iterator {
-                    0 => *curr_buf = ((*curr_buf as i32) + ((key_result * 2) as i32)) as u8,
-                    1 => *curr_buf = ((*curr_buf as i32) - ((key_result >> 3) as i32)) as u8,
-                    2 => *curr_buf = ((*curr_buf as i32) + ((key_result * 4) as i32)) as u8,
-                    3 => *curr_buf = ((*curr_buf as i32) - ((key_result >> 5) as i32)) as u8,
-                    _ => (),
-                };
+            match loop_iterator {
+                0 => *curr_buf = ((*curr_buf as i32) + ((key_result * 2) as i32)) as u8,
+                1 => *curr_buf = ((*curr_buf as i32) - ((key_result >> 3) as i32)) as u8,
+                2 => *curr_buf = ((*curr_buf as i32) + ((key_result * 4) as i32)) as u8,
+                3 => *curr_buf = ((*curr_buf as i32) - ((key_result >> 5) as i32)) as u8,
+                _ => (),
+            };
 
-                checksum_enc += *curr_buf as u32;
-            }
-            _ => (),
+            checksum_enc += *curr_buf as u32;
         }
 
         i += 1;
         key_increment += 1;
     }
 
-    buf[3] = (checksum_enc - checksum_dec) as u8;
+    buf[3] = checksum_enc.wrapping_sub(checksum_dec) as u8;
 }
 
 pub fn decode(buf: &mut [u8]) {
@@ -87,22 +83,19 @@ pub fn decode(buf: &mut [u8]) {
     let mut this_iterator;
 
     while i < size {
-        match buf.get_mut(i as usize) {
-            Some(curr_buf) => {
-                key_result = KEYS[(((key_increment & 0x800000FF) * 2) + 1) as usize];
-
-                this_iterator = i & 3;
-
-                match this_iterator {
-                    0 => *curr_buf = ((*curr_buf as i32) - ((key_result << 1) as i32)) as u8,
-                    1 => *curr_buf = ((*curr_buf as i32) + ((key_result >> 3) as i32)) as u8,
-                    2 => *curr_buf = ((*curr_buf as i32) - ((key_result << 2) as i32)) as u8,
-                    3 => *curr_buf = ((*curr_buf as i32) + ((key_result >> 5) as i32)) as u8,
-                    _ => (),
-                }
+        if let Some(curr_buf) = buf.get_mut(i as usize) {
+            key_result = KEYS[(((key_increment & 0x800000FF) * 2) + 1) as usize];
+
+            this_iterator = i & 3;
+
+            match this_iterator {
+                0 => *curr_buf = ((*curr_buf as i32) - ((key_result << 1) as i32)) as u8,
+                1 => *curr_buf = ((*curr_buf as i32) + ((key_result >> 3) as i32)) as u8,
+                2 => *curr_buf = ((*curr_buf as i32) - ((key_result << 2) as i32)) as u8,
+                3 => *curr_buf = ((*curr_buf as i32) + ((key_result >> 5) as i32)) as u8,
+                _ => (),
             }
-            None => (),
-        };
+        }
 
         i += 1;
         key_increment += 1;
@@ -0,0 +1,16 @@
+// effect ids used by the item templates and by the effects carried on each item
+
+pub const EF_NONE: i16 = 0;
+pub const EF_LEVEL: i16 = 1;
+pub const EF_DAMAGE: i16 = 2;
+pub const EF_AC: i16 = 3;
+pub const EF_HP: i16 = 4;
+pub const EF_MP: i16 = 5;
+pub const EF_EXP: i16 = 6;
+pub const EF_STR: i16 = 7;
+pub const EF_INT: i16 = 8;
+pub const EF_DEX: i16 = 9;
+pub const EF_CON: i16 = 10;
+pub const EF_CLASS: i16 = 18;
+pub const EF_SANC: i16 = 43;
+pub const EF_AMOUNT: i16 = 61;
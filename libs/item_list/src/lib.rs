@@ -0,0 +1,179 @@
+use packets::{
+    serializer::{deserialize, serialize},
+    strings::{bytes_to_str, str_to_bytes},
+};
+use std::{fs, mem::size_of, path::Path};
+
+pub mod effects;
+
+pub const ITEM_LIST_FILE: &str = "ItemList.bin";
+pub const ITEM_LIST_KEY: u8 = 0x5A;
+pub const ITEM_EFFECTS: usize = 12;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[repr(C)]
+pub struct SItemEffect {
+    pub index: i16,
+    pub value: i16,
+}
+
+// one item template of the client ItemList.bin, the index of the item is its position in the file
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub struct SItemList {
+    pub name: [u8; 64],
+    pub mesh: i16,
+    pub texture: i16,
+    pub visual_effect: i16,
+    pub req_level: i16,
+    pub req_str: i16,
+    pub req_int: i16,
+    pub req_dex: i16,
+    pub req_con: i16,
+    pub effects: [SItemEffect; ITEM_EFFECTS],
+    pub price: i32,
+    pub unique: i16,
+    pub pos: i16,
+    pub extreme: i16,
+    pub grade: i16,
+}
+
+impl Default for SItemList {
+    fn default() -> Self {
+        Self {
+            name: [0; 64],
+            mesh: 0,
+            texture: 0,
+            visual_effect: 0,
+            req_level: 0,
+            req_str: 0,
+            req_int: 0,
+            req_dex: 0,
+            req_con: 0,
+            effects: [SItemEffect::default(); ITEM_EFFECTS],
+            price: 0,
+            unique: 0,
+            pos: 0,
+            extreme: 0,
+            grade: 0,
+        }
+    }
+}
+
+impl SItemList {
+    pub fn get_name(&self) -> String {
+        bytes_to_str(&self.name)
+    }
+
+    pub fn set_name(&mut self, name: &str) {
+        str_to_bytes(&mut self.name, name);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.name[0] == 0
+    }
+
+    pub fn effect(&self, index: i16) -> Option<i16> {
+        self.effects
+            .iter()
+            .find(|effect| effect.index == index)
+            .map(|effect| effect.value)
+    }
+}
+
+// the file is only xored, the same call encodes and decodes it
+
+pub fn xor(buf: &mut [u8]) {
+    for byte in buf.iter_mut() {
+        *byte ^= ITEM_LIST_KEY;
+    }
+}
+
+pub fn read(buf: &[u8]) -> Option<Vec<SItemList>> {
+    if !buf.len().is_multiple_of(size_of::<SItemList>()) {
+        return None;
+    }
+
+    let mut buf = buf.to_vec();
+    xor(&mut buf);
+
+    buf.chunks_exact(size_of::<SItemList>())
+        .map(deserialize::<SItemList>)
+        .collect()
+}
+
+pub fn write(items: &[SItemList]) -> Vec<u8> {
+    let mut buf: Vec<u8> = items.iter().flat_map(serialize).collect();
+    xor(&mut buf);
+    buf
+}
+
+pub fn load(folder: &Path) -> Option<Vec<SItemList>> {
+    read(&fs::read(folder.join(ITEM_LIST_FILE)).ok()?)
+}
+
+pub fn save(folder: &Path, items: &[SItemList]) -> bool {
+    fs::write(folder.join(ITEM_LIST_FILE), write(items)).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items() -> Vec<SItemList> {
+        let mut sword = SItemList::default();
+        sword.set_name("Espada Longa");
+        sword.mesh = 12;
+        sword.req_level = 40;
+        sword.req_str = -1;
+        sword.price = 150_000;
+        sword.effects[0] = SItemEffect {
+            index: 2,
+            value: 35,
+        };
+
+        let mut potion = SItemList::default();
+        potion.set_name("Poção");
+        potion.effects[ITEM_EFFECTS - 1] = SItemEffect {
+            index: 61,
+            value: 120,
+        };
+
+        vec![SItemList::default(), sword, potion]
+    }
+
+    #[test]
+    fn written_items_read_back_the_same() {
+        let items = items();
+
+        assert_eq!(read(&write(&items)), Some(items));
+    }
+
+    #[test]
+    fn the_file_is_xored_with_the_key() {
+        let items = items();
+        let buf = write(&items);
+
+        let plain: Vec<u8> = items.iter().flat_map(serialize).collect();
+
+        assert_eq!(buf.len(), plain.len());
+        assert!(buf
+            .iter()
+            .zip(&plain)
+            .all(|(encoded, plain)| *encoded == plain ^ ITEM_LIST_KEY));
+
+        // an empty record is all key bytes on disk
+
+        assert!(buf[..size_of::<SItemList>()]
+            .iter()
+            .all(|byte| *byte == ITEM_LIST_KEY));
+    }
+
+    #[test]
+    fn a_cut_file_is_refused() {
+        let buf = write(&items());
+
+        assert_eq!(read(&buf[..buf.len() - 1]), None);
+    }
+}
@@ -1,7 +1,8 @@
 use encoding_rs::WINDOWS_1252;
 
 pub fn bytes_to_str(bytes: &[u8]) -> String {
-    WINDOWS_1252.decode(bytes).0.to_string()
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    WINDOWS_1252.decode(&bytes[..end]).0.to_string()
 }
 
 pub fn str_to_bytes(bytes: &mut [u8], value: &str) {
@@ -11,7 +12,7 @@ pub fn str_to_bytes(bytes: &mut [u8], value: &str) {
         .to_vec()
         .iter()
         .filter(|v| **v != 0)
-        .map(|v| *v)
+        .copied()
         .collect::<Vec<_>>();
 
     encoded.resize(bytes.len(), 0);
@@ -0,0 +1,8 @@
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[repr(C)]
+pub struct SAffect {
+    pub kind: u8,
+    pub value: u8,
+    pub level: u16,
+    pub time: u32,
+}
@@ -0,0 +1,84 @@
+use crate::{
+    strings::{bytes_to_str, str_to_bytes},
+    structs::item::SItem,
+};
+
+#[derive(Debug, Clone, Copy, Default)]
+#[repr(C)]
+pub struct SAuction {
+    pub id: u32,
+    pub item: SItem,
+    pub price: i32,
+    pub remaining: u32,
+    seller: [u8; 16],
+}
+
+impl SAuction {
+    pub fn new(id: u32, item: SItem, price: i32, remaining: u32, seller: &str) -> SAuction {
+        let mut auction = SAuction {
+            id,
+            item,
+            price,
+            remaining,
+            seller: [0; 16],
+        };
+
+        auction.set_seller(seller);
+
+        auction
+    }
+
+    pub fn get_seller(&self) -> String {
+        bytes_to_str(&self.seller)
+    }
+    pub fn set_seller(&mut self, seller: &str) {
+        str_to_bytes(&mut self.seller, seller)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct SMail {
+    pub id: u32,
+    pub item: SItem,
+    pub coin: i32,
+    from: [u8; 16],
+    text: [u8; 40],
+}
+
+impl Default for SMail {
+    fn default() -> Self {
+        Self::new(0, SItem::default(), 0, "", "")
+    }
+}
+
+impl SMail {
+    pub fn new(id: u32, item: SItem, coin: i32, from: &str, text: &str) -> SMail {
+        let mut mail = SMail {
+            id,
+            item,
+            coin,
+            from: [0; 16],
+            text: [0; 40],
+        };
+
+        mail.set_from(from);
+        mail.set_text(text);
+
+        mail
+    }
+
+    pub fn get_from(&self) -> String {
+        bytes_to_str(&self.from)
+    }
+    pub fn set_from(&mut self, from: &str) {
+        str_to_bytes(&mut self.from, from)
+    }
+
+    pub fn get_text(&self) -> String {
+        bytes_to_str(&self.text)
+    }
+    pub fn set_text(&mut self, text: &str) {
+        str_to_bytes(&mut self.text, text)
+    }
+}
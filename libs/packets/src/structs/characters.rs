@@ -0,0 +1,45 @@
+use crate::{
+    strings::{bytes_to_str, str_to_bytes},
+    structs::{item::SItem, position::SPosition, score::SScore},
+};
+
+pub const CHARACTERS_LEN: usize = 4;
+
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct SCharacters {
+    pub positions: [SPosition; CHARACTERS_LEN],
+    names: [[u8; 16]; CHARACTERS_LEN],
+    pub scores: [SScore; CHARACTERS_LEN],
+    pub equips: [[SItem; 16]; CHARACTERS_LEN],
+    pub guilds: [u16; CHARACTERS_LEN],
+    pub coins: [i32; CHARACTERS_LEN],
+    pub exps: [i64; CHARACTERS_LEN],
+}
+
+impl Default for SCharacters {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SCharacters {
+    pub fn new() -> SCharacters {
+        SCharacters {
+            positions: [SPosition::default(); CHARACTERS_LEN],
+            names: [[0; 16]; CHARACTERS_LEN],
+            scores: [SScore::default(); CHARACTERS_LEN],
+            equips: [[SItem::default(); 16]; CHARACTERS_LEN],
+            guilds: [0; CHARACTERS_LEN],
+            coins: [0; CHARACTERS_LEN],
+            exps: [0; CHARACTERS_LEN],
+        }
+    }
+
+    pub fn get_name(&self, slot: usize) -> String {
+        bytes_to_str(&self.names[slot])
+    }
+    pub fn set_name(&mut self, slot: usize, name: &str) {
+        str_to_bytes(&mut self.names[slot], name)
+    }
+}
@@ -0,0 +1,32 @@
+use crate::strings::{bytes_to_str, str_to_bytes};
+
+#[derive(Debug, Clone, Copy, Default)]
+#[repr(C)]
+pub struct SGuildMember {
+    name: [u8; 16],
+    pub rank: u8,
+    pub online: u8,
+    pub unk: u16,
+}
+
+impl SGuildMember {
+    pub fn new(name: &str, rank: u8, online: bool) -> SGuildMember {
+        let mut member = SGuildMember {
+            name: [0; 16],
+            rank,
+            online: online as u8,
+            unk: 0,
+        };
+
+        member.set_name(name);
+
+        member
+    }
+
+    pub fn get_name(&self) -> String {
+        bytes_to_str(&self.name)
+    }
+    pub fn set_name(&mut self, name: &str) {
+        str_to_bytes(&mut self.name, name)
+    }
+}
@@ -11,6 +11,12 @@ pub struct SHeader {
     pub timestamp: u32,
 }
 
+impl Default for SHeader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SHeader {
     pub fn new() -> SHeader {
         SHeader {
@@ -0,0 +1,22 @@
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[repr(C)]
+pub struct SItemEffect {
+    pub index: u8,
+    pub value: u8,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[repr(C)]
+pub struct SItem {
+    pub index: i16,
+    pub effects: [SItemEffect; 3],
+}
+
+impl SItem {
+    pub fn new(index: i16) -> SItem {
+        SItem {
+            index,
+            effects: [SItemEffect::default(); 3],
+        }
+    }
+}
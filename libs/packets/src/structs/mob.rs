@@ -0,0 +1,81 @@
+use crate::{
+    strings::{bytes_to_str, str_to_bytes},
+    structs::{item::SItem, position::SPosition, score::SScore},
+};
+
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct SMob {
+    name: [u8; 16],
+    pub cape: u8,
+    pub merchant: u8,
+    pub guild: u16,
+    pub class: u8,
+    pub affect_info: u8,
+    pub quest_info: u16,
+    pub coin: i32,
+    pub exp: i64,
+    pub last_position: SPosition,
+    pub base_score: SScore,
+    pub current_score: SScore,
+    pub equip: [SItem; 16],
+    pub inventory: [SItem; 64],
+    pub learned_skill: u32,
+    pub score_bonus: u16,
+    pub special_bonus: u16,
+    pub skill_bonus: u16,
+    pub critical: u8,
+    pub save_mana: u8,
+    pub skill_bar: [u8; 4],
+    pub guild_level: u8,
+    pub magic: u8,
+    pub regen_hp: u8,
+    pub regen_mp: u8,
+    pub resist: [u8; 4],
+}
+
+impl Default for SMob {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SMob {
+    pub fn new() -> SMob {
+        SMob {
+            name: [0; 16],
+            cape: 0,
+            merchant: 0,
+            guild: 0,
+            class: 0,
+            affect_info: 0,
+            quest_info: 0,
+            coin: 0,
+            exp: 0,
+            last_position: SPosition::default(),
+            base_score: SScore::default(),
+            current_score: SScore::default(),
+            equip: [SItem::default(); 16],
+            inventory: [SItem::default(); 64],
+            learned_skill: 0,
+            score_bonus: 0,
+            special_bonus: 0,
+            skill_bonus: 0,
+            critical: 0,
+            save_mana: 0,
+            skill_bar: [0; 4],
+            guild_level: 0,
+            magic: 0,
+            regen_hp: 0,
+            regen_mp: 0,
+            resist: [0; 4],
+        }
+    }
+
+    pub fn get_name(&self) -> String {
+        bytes_to_str(&self.name)
+    }
+    pub fn set_name(&mut self, name: &str) {
+        str_to_bytes(&mut self.name, name)
+    }
+}
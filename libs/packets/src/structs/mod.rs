@@ -1,2 +1,11 @@
+pub mod affect;
+pub mod auction;
+pub mod characters;
+pub mod guild;
 pub mod header;
+pub mod item;
+pub mod mob;
 pub mod packets;
+pub mod position;
+pub mod score;
+pub mod spell;
@@ -1,2 +1,68 @@
 pub mod p101;
+pub mod p10a;
+pub mod p110;
+pub mod p112;
+pub mod p114;
+pub mod p116;
+pub mod p165;
+pub mod p16f;
+pub mod p17c;
+pub mod p181;
+pub mod p182;
 pub mod p20d;
+pub mod p20f;
+pub mod p211;
+pub mod p213;
+pub mod p215;
+pub mod p26e;
+pub mod p270;
+pub mod p272;
+pub mod p277;
+pub mod p27b;
+pub mod p289;
+pub mod p28b;
+pub mod p2e5;
+pub mod p333;
+pub mod p334;
+pub mod p336;
+pub mod p337;
+pub mod p364;
+pub mod p36b;
+pub mod p36c;
+pub mod p373;
+pub mod p376;
+pub mod p379;
+pub mod p37a;
+pub mod p37d;
+pub mod p37e;
+pub mod p37f;
+pub mod p383;
+pub mod p384;
+pub mod p397;
+pub mod p398;
+pub mod p39a;
+pub mod p39d;
+pub mod p3a6;
+pub mod p3ab;
+pub mod p3b9;
+pub mod p3c0;
+pub mod p3c1;
+pub mod p3c2;
+pub mod p3c3;
+pub mod p3c4;
+pub mod p3c5;
+pub mod p3c6;
+pub mod p3c7;
+pub mod p3c8;
+pub mod p3c9;
+pub mod p3ca;
+pub mod p3cb;
+pub mod p3cc;
+pub mod p3cd;
+pub mod p3ce;
+pub mod p3cf;
+pub mod p3d0;
+pub mod p3d1;
+pub mod p3d2;
+pub mod p3d3;
+pub mod p3d4;
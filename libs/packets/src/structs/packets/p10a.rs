@@ -0,0 +1,29 @@
+use crate::{
+    strings::str_to_bytes,
+    structs::{characters::SCharacters, header::SHeader, item::SItem},
+};
+
+#[repr(C)]
+pub struct P10A {
+    pub header: SHeader,
+    pub characters: SCharacters,
+    pub storage: [SItem; 128],
+    pub storage_coin: i32,
+    username: [u8; 16],
+}
+
+impl P10A {
+    pub fn new(characters: SCharacters, username: &str) -> P10A {
+        let mut p = P10A {
+            header: SHeader::new_packet::<P10A>(0x10A),
+            characters,
+            storage: [SItem::default(); 128],
+            storage_coin: 0,
+            username: [0; 16],
+        };
+
+        str_to_bytes(&mut p.username, username);
+
+        p
+    }
+}
@@ -0,0 +1,16 @@
+use crate::structs::{characters::SCharacters, header::SHeader};
+
+#[repr(C)]
+pub struct P110 {
+    pub header: SHeader,
+    pub characters: SCharacters,
+}
+
+impl P110 {
+    pub fn new(characters: SCharacters) -> P110 {
+        P110 {
+            header: SHeader::new_packet::<P110>(0x110),
+            characters,
+        }
+    }
+}
@@ -0,0 +1,16 @@
+use crate::structs::{characters::SCharacters, header::SHeader};
+
+#[repr(C)]
+pub struct P112 {
+    pub header: SHeader,
+    pub characters: SCharacters,
+}
+
+impl P112 {
+    pub fn new(characters: SCharacters) -> P112 {
+        P112 {
+            header: SHeader::new_packet::<P112>(0x112),
+            characters,
+        }
+    }
+}
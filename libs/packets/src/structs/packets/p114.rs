@@ -0,0 +1,27 @@
+use crate::structs::{header::SHeader, mob::SMob, position::SPosition};
+
+#[repr(C)]
+pub struct P114 {
+    pub header: SHeader,
+    pub position: SPosition,
+    pub mob: SMob,
+    pub slot: u16,
+    pub client_id: u16,
+    unk1: [u8; 4],
+}
+
+impl P114 {
+    pub fn new(client_id: u16, slot: u16, position: SPosition, mob: SMob) -> P114 {
+        let mut header = SHeader::new_packet::<P114>(0x114);
+        header.client_id = client_id;
+
+        P114 {
+            header,
+            position,
+            mob,
+            slot,
+            client_id,
+            unk1: [0; 4],
+        }
+    }
+}
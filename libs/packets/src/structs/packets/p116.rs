@@ -0,0 +1,20 @@
+use crate::structs::header::SHeader;
+
+#[repr(C)]
+pub struct P116 {
+    pub header: SHeader,
+}
+
+impl Default for P116 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl P116 {
+    pub fn new() -> P116 {
+        P116 {
+            header: SHeader::new_packet::<P116>(0x116),
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use crate::structs::header::SHeader;
+
+#[repr(C)]
+pub struct P165 {
+    pub header: SHeader,
+    pub remove_type: i32,
+}
+
+impl P165 {
+    pub fn new(mob_id: u16, remove_type: i32) -> P165 {
+        let mut header = SHeader::new_packet::<P165>(0x165);
+        header.client_id = mob_id;
+
+        P165 {
+            header,
+            remove_type,
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use crate::structs::header::SHeader;
+
+#[repr(C)]
+pub struct P16F {
+    pub header: SHeader,
+    pub item_id: u16,
+    unk: u16,
+}
+
+impl P16F {
+    pub fn new(item_id: u16) -> P16F {
+        let header = SHeader::new_packet::<P16F>(0x16F);
+
+        P16F {
+            header,
+            item_id,
+            unk: 0,
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use crate::structs::{header::SHeader, item::SItem};
+
+pub const SHOP_LEN: usize = 27;
+
+#[repr(C)]
+pub struct P17C {
+    pub header: SHeader,
+    pub shop_type: i32,
+    pub items: [SItem; SHOP_LEN],
+    pub tax: i32,
+}
+
+impl P17C {
+    pub fn new(client_id: u16, items: [SItem; SHOP_LEN], tax: i32) -> P17C {
+        let mut header = SHeader::new_packet::<P17C>(0x17C);
+        header.client_id = client_id;
+
+        P17C {
+            header,
+            shop_type: 1,
+            items,
+            tax,
+        }
+    }
+}
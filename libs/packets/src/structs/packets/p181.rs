@@ -0,0 +1,25 @@
+use crate::structs::header::SHeader;
+
+#[repr(C)]
+pub struct P181 {
+    pub header: SHeader,
+    pub hp: i32,
+    pub mp: i32,
+    pub max_hp: i32,
+    pub max_mp: i32,
+}
+
+impl P181 {
+    pub fn new(client_id: u16, hp: i32, mp: i32, max_hp: i32, max_mp: i32) -> P181 {
+        let mut header = SHeader::new_packet::<P181>(0x181);
+        header.client_id = client_id;
+
+        P181 {
+            header,
+            hp,
+            mp,
+            max_hp,
+            max_mp,
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use crate::structs::{header::SHeader, item::SItem};
+
+#[repr(C)]
+pub struct P182 {
+    pub header: SHeader,
+    pub slot_type: i16,
+    pub slot: i16,
+    pub item: SItem,
+}
+
+impl P182 {
+    pub fn new(client_id: u16, slot_type: i16, slot: i16, item: SItem) -> P182 {
+        let mut header = SHeader::new_packet::<P182>(0x182);
+        header.client_id = client_id;
+
+        P182 {
+            header,
+            slot_type,
+            slot,
+            item,
+        }
+    }
+}
@@ -0,0 +1,15 @@
+use crate::{strings::bytes_to_str, structs::header::SHeader};
+
+#[repr(C)]
+pub struct P20F {
+    pub header: SHeader,
+    pub slot: i32,
+    name: [u8; 16],
+    pub class: i32,
+}
+
+impl P20F {
+    pub fn get_name(&self) -> String {
+        bytes_to_str(&self.name)
+    }
+}
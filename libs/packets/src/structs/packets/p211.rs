@@ -0,0 +1,19 @@
+use crate::{strings::bytes_to_str, structs::header::SHeader};
+
+#[repr(C)]
+pub struct P211 {
+    pub header: SHeader,
+    pub slot: i32,
+    name: [u8; 16],
+    password: [u8; 12],
+}
+
+impl P211 {
+    pub fn get_name(&self) -> String {
+        bytes_to_str(&self.name)
+    }
+
+    pub fn get_password(&self) -> String {
+        bytes_to_str(&self.password)
+    }
+}
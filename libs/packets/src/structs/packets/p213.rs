@@ -0,0 +1,8 @@
+use crate::structs::header::SHeader;
+
+#[repr(C)]
+pub struct P213 {
+    pub header: SHeader,
+    pub slot: i32,
+    unk1: [u8; 20],
+}
@@ -0,0 +1,6 @@
+use crate::structs::header::SHeader;
+
+#[repr(C)]
+pub struct P215 {
+    pub header: SHeader,
+}
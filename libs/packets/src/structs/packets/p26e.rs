@@ -0,0 +1,28 @@
+use crate::structs::{header::SHeader, item::SItem, position::SPosition};
+
+#[repr(C)]
+pub struct P26E {
+    pub header: SHeader,
+    pub position: SPosition,
+    pub item_id: u16,
+    pub item: SItem,
+    pub rotate: u8,
+    pub state: u8,
+    unk: [u8; 2],
+}
+
+impl P26E {
+    pub fn new(item_id: u16, position: SPosition, item: SItem) -> P26E {
+        let header = SHeader::new_packet::<P26E>(0x26E);
+
+        P26E {
+            header,
+            position,
+            item_id,
+            item,
+            rotate: 0,
+            state: 0,
+            unk: [0; 2],
+        }
+    }
+}
@@ -0,0 +1,11 @@
+use crate::structs::{header::SHeader, position::SPosition};
+
+#[repr(C)]
+pub struct P270 {
+    pub header: SHeader,
+    pub dest_type: i32,
+    pub dest_slot: i32,
+    pub item_id: u16,
+    pub position: SPosition,
+    pub unk: u16,
+}
@@ -0,0 +1,11 @@
+use crate::structs::{header::SHeader, position::SPosition};
+
+#[repr(C)]
+pub struct P272 {
+    pub header: SHeader,
+    pub src_type: i32,
+    pub src_slot: i32,
+    pub rotate: i32,
+    pub position: SPosition,
+    pub unk: i32,
+}
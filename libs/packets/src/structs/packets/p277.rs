@@ -0,0 +1,14 @@
+use crate::structs::header::SHeader;
+
+pub const BONUS_SCORE: i16 = 0;
+pub const BONUS_SPECIAL: i16 = 1;
+pub const BONUS_SKILL: i16 = 2;
+
+#[repr(C)]
+pub struct P277 {
+    pub header: SHeader,
+    pub bonus_type: i16,
+    pub detail: i16,
+    pub target_id: u16,
+    unk: u16,
+}
@@ -0,0 +1,8 @@
+use crate::structs::header::SHeader;
+
+#[repr(C)]
+pub struct P27B {
+    pub header: SHeader,
+    pub target_id: u16,
+    pub unk: u16,
+}
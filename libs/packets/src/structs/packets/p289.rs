@@ -0,0 +1,6 @@
+use crate::structs::header::SHeader;
+
+#[repr(C)]
+pub struct P289 {
+    pub header: SHeader,
+}
@@ -0,0 +1,9 @@
+use crate::structs::header::SHeader;
+
+#[repr(C)]
+pub struct P2E5 {
+    pub header: SHeader,
+    pub slot: i32,
+    pub item_index: i32,
+    pub amount: i32,
+}
@@ -0,0 +1,33 @@
+use crate::{
+    strings::{bytes_to_str, str_to_bytes},
+    structs::header::SHeader,
+};
+
+#[repr(C)]
+pub struct P333 {
+    pub header: SHeader,
+    message: [u8; 96],
+}
+
+impl P333 {
+    pub fn new(client_id: u16, message: &str) -> P333 {
+        let mut header = SHeader::new_packet::<P333>(0x333);
+        header.client_id = client_id;
+
+        let mut p = P333 {
+            header,
+            message: [0; 96],
+        };
+
+        p.set_message(message);
+
+        p
+    }
+
+    pub fn get_message(&self) -> String {
+        bytes_to_str(&self.message)
+    }
+    pub fn set_message(&mut self, message: &str) {
+        str_to_bytes(&mut self.message, message)
+    }
+}
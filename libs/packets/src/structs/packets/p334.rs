@@ -0,0 +1,43 @@
+use crate::{
+    strings::{bytes_to_str, str_to_bytes},
+    structs::header::SHeader,
+};
+
+#[repr(C)]
+pub struct P334 {
+    pub header: SHeader,
+    name: [u8; 16],
+    message: [u8; 128],
+}
+
+impl P334 {
+    pub fn new(client_id: u16, name: &str, message: &str) -> P334 {
+        let mut header = SHeader::new_packet::<P334>(0x334);
+        header.client_id = client_id;
+
+        let mut p = P334 {
+            header,
+            name: [0; 16],
+            message: [0; 128],
+        };
+
+        p.set_name(name);
+        p.set_message(message);
+
+        p
+    }
+
+    pub fn get_name(&self) -> String {
+        bytes_to_str(&self.name)
+    }
+    pub fn set_name(&mut self, name: &str) {
+        str_to_bytes(&mut self.name, name)
+    }
+
+    pub fn get_message(&self) -> String {
+        bytes_to_str(&self.message)
+    }
+    pub fn set_message(&mut self, message: &str) {
+        str_to_bytes(&mut self.message, message)
+    }
+}
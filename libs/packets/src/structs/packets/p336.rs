@@ -0,0 +1,27 @@
+use crate::structs::{header::SHeader, score::SScore};
+
+#[repr(C)]
+pub struct P336 {
+    pub header: SHeader,
+    pub score: SScore,
+    pub critical: u8,
+    pub save_mana: u8,
+    unk: [u8; 2],
+    pub affects: [u8; 32],
+}
+
+impl P336 {
+    pub fn new(client_id: u16, score: SScore) -> P336 {
+        let mut header = SHeader::new_packet::<P336>(0x336);
+        header.client_id = client_id;
+
+        P336 {
+            header,
+            score,
+            critical: 0,
+            save_mana: 0,
+            unk: [0; 2],
+            affects: [0; 32],
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use crate::structs::header::SHeader;
+
+#[repr(C)]
+pub struct P337 {
+    pub header: SHeader,
+    pub hold: i32,
+    pub exp: i64,
+    pub learned_skill: u32,
+    pub score_bonus: u16,
+    pub special_bonus: u16,
+    pub skill_bonus: u16,
+    unk: u16,
+    pub coin: i32,
+}
+
+impl P337 {
+    pub fn new(client_id: u16) -> P337 {
+        let mut header = SHeader::new_packet::<P337>(0x337);
+        header.client_id = client_id;
+
+        P337 {
+            header,
+            hold: 0,
+            exp: 0,
+            learned_skill: 0,
+            score_bonus: 0,
+            special_bonus: 0,
+            skill_bonus: 0,
+            unk: 0,
+            coin: 0,
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use crate::{
+    strings::str_to_bytes,
+    structs::{header::SHeader, position::SPosition, score::SScore},
+};
+
+#[repr(C)]
+pub struct P364 {
+    pub header: SHeader,
+    pub position: SPosition,
+    pub mob_id: u16,
+    name: [u8; 16],
+    pub chaos_points: u8,
+    pub current_kill: u8,
+    pub total_kill: u16,
+    pub equip: [u16; 16],
+    pub affects: [u8; 32],
+    pub guild: u16,
+    pub score: SScore,
+    pub spawn_type: u16,
+    pub equip_refine: [u8; 16],
+    tab: [u8; 26],
+}
+
+impl P364 {
+    pub fn new(mob_id: u16, name: &str, position: SPosition, score: SScore) -> P364 {
+        let mut header = SHeader::new_packet::<P364>(0x364);
+        header.client_id = mob_id;
+
+        let mut p = P364 {
+            header,
+            position,
+            mob_id,
+            name: [0; 16],
+            chaos_points: 0,
+            current_kill: 0,
+            total_kill: 0,
+            equip: [0; 16],
+            affects: [0; 32],
+            guild: 0,
+            score,
+            spawn_type: 0,
+            equip_refine: [0; 16],
+            tab: [0; 26],
+        };
+
+        str_to_bytes(&mut p.name, name);
+
+        p
+    }
+
+    pub fn set_tab(&mut self, tab: &str) {
+        str_to_bytes(&mut self.tab, tab)
+    }
+}
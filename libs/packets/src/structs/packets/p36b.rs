@@ -0,0 +1,21 @@
+use crate::structs::header::SHeader;
+
+#[repr(C)]
+pub struct P36B {
+    pub header: SHeader,
+    pub equip: [u16; 16],
+    pub equip_refine: [u8; 16],
+}
+
+impl P36B {
+    pub fn new(client_id: u16, equip: [u16; 16]) -> P36B {
+        let mut header = SHeader::new_packet::<P36B>(0x36B);
+        header.client_id = client_id;
+
+        P36B {
+            header,
+            equip,
+            equip_refine: [0; 16],
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use crate::structs::{header::SHeader, position::SPosition};
+
+pub const ROUTE_LEN: usize = 24;
+
+#[repr(C)]
+pub struct P36C {
+    pub header: SHeader,
+    pub position: SPosition,
+    pub action_type: i32,
+    pub speed: i32,
+    pub route: [u8; ROUTE_LEN],
+    pub target: SPosition,
+}
+
+impl P36C {
+    pub fn new(mob_id: u16, position: SPosition, target: SPosition, speed: i32) -> P36C {
+        let mut header = SHeader::new_packet::<P36C>(0x36C);
+        header.client_id = mob_id;
+
+        P36C {
+            header,
+            position,
+            action_type: 0,
+            speed,
+            route: [0; ROUTE_LEN],
+            target,
+        }
+    }
+
+    pub fn get_route(&self) -> Vec<u8> {
+        self.route
+            .iter()
+            .take_while(|b| **b != 0)
+            .copied()
+            .collect()
+    }
+    pub fn set_route(&mut self, route: &[u8]) {
+        self.route.fill(0);
+
+        for (index, step) in route.iter().take(ROUTE_LEN).enumerate() {
+            self.route[index] = *step;
+        }
+    }
+}
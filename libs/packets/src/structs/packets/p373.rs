@@ -0,0 +1,12 @@
+use crate::structs::{header::SHeader, position::SPosition};
+
+#[repr(C)]
+pub struct P373 {
+    pub header: SHeader,
+    pub src_type: i32,
+    pub src_slot: i32,
+    pub dest_type: i32,
+    pub dest_slot: i32,
+    pub position: SPosition,
+    pub warp_id: i32,
+}
@@ -0,0 +1,11 @@
+use crate::structs::header::SHeader;
+
+#[repr(C)]
+pub struct P376 {
+    pub header: SHeader,
+    pub dest_type: u8,
+    pub dest_slot: u8,
+    pub src_type: u8,
+    pub src_slot: u8,
+    pub warp_id: i32,
+}
@@ -0,0 +1,11 @@
+use crate::structs::header::SHeader;
+
+#[repr(C)]
+pub struct P379 {
+    pub header: SHeader,
+    pub target_id: u16,
+    pub shop_slot: u16,
+    pub inventory_slot: u16,
+    pub unk: u16,
+    pub coin: i32,
+}
@@ -0,0 +1,10 @@
+use crate::structs::header::SHeader;
+
+#[repr(C)]
+pub struct P37A {
+    pub header: SHeader,
+    pub target_id: u16,
+    pub slot_type: u16,
+    pub slot: u16,
+    pub unk: u16,
+}
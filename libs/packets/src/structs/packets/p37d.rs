@@ -0,0 +1,41 @@
+use crate::{
+    strings::{bytes_to_str, str_to_bytes},
+    structs::header::SHeader,
+};
+
+#[repr(C)]
+pub struct P37D {
+    pub header: SHeader,
+    pub leader_id: u16,
+    pub level: i16,
+    pub max_hp: i16,
+    pub hp: i16,
+    pub member_id: u16,
+    name: [u8; 16],
+    unk: u16,
+}
+
+impl P37D {
+    pub fn new(client_id: u16, leader_id: u16, member_id: u16) -> P37D {
+        let mut header = SHeader::new_packet::<P37D>(0x37D);
+        header.client_id = client_id;
+
+        P37D {
+            header,
+            leader_id,
+            level: 0,
+            max_hp: 0,
+            hp: 0,
+            member_id,
+            name: [0; 16],
+            unk: 0,
+        }
+    }
+
+    pub fn get_name(&self) -> String {
+        bytes_to_str(&self.name)
+    }
+    pub fn set_name(&mut self, name: &str) {
+        str_to_bytes(&mut self.name, name)
+    }
+}
@@ -0,0 +1,21 @@
+use crate::structs::header::SHeader;
+
+#[repr(C)]
+pub struct P37E {
+    pub header: SHeader,
+    pub member_id: u16,
+    pub unk: u16,
+}
+
+impl P37E {
+    pub fn new(client_id: u16, member_id: u16) -> P37E {
+        let mut header = SHeader::new_packet::<P37E>(0x37E);
+        header.client_id = client_id;
+
+        P37E {
+            header,
+            member_id,
+            unk: 0,
+        }
+    }
+}
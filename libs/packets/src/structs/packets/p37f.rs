@@ -0,0 +1,41 @@
+use crate::{
+    strings::{bytes_to_str, str_to_bytes},
+    structs::header::SHeader,
+};
+
+#[repr(C)]
+pub struct P37F {
+    pub header: SHeader,
+    pub level: i16,
+    pub max_hp: i16,
+    pub hp: i16,
+    pub leader_id: u16,
+    name: [u8; 16],
+    pub unk: u16,
+    pub target_id: u16,
+}
+
+impl P37F {
+    pub fn new(client_id: u16, leader_id: u16, target_id: u16) -> P37F {
+        let mut header = SHeader::new_packet::<P37F>(0x37F);
+        header.client_id = client_id;
+
+        P37F {
+            header,
+            level: 0,
+            max_hp: 0,
+            hp: 0,
+            leader_id,
+            name: [0; 16],
+            unk: 0,
+            target_id,
+        }
+    }
+
+    pub fn get_name(&self) -> String {
+        bytes_to_str(&self.name)
+    }
+    pub fn set_name(&mut self, name: &str) {
+        str_to_bytes(&mut self.name, name)
+    }
+}
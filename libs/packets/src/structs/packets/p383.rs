@@ -0,0 +1,37 @@
+use crate::structs::{header::SHeader, item::SItem};
+
+pub const TRADE_LEN: usize = 15;
+
+pub const TRADE_OFFER: u8 = 0;
+pub const TRADE_LOCK: u8 = 1;
+pub const TRADE_CONFIRM: u8 = 2;
+
+#[repr(C)]
+pub struct P383 {
+    pub header: SHeader,
+    pub items: [SItem; TRADE_LEN],
+    pub slots: [i8; TRADE_LEN],
+    unk: u8,
+    pub coin: i32,
+    pub state: u8,
+    unk2: u8,
+    pub target_id: u16,
+}
+
+impl P383 {
+    pub fn new(client_id: u16, target_id: u16) -> P383 {
+        let mut header = SHeader::new_packet::<P383>(0x383);
+        header.client_id = client_id;
+
+        P383 {
+            header,
+            items: [SItem::default(); TRADE_LEN],
+            slots: [-1; TRADE_LEN],
+            unk: 0,
+            coin: 0,
+            state: TRADE_OFFER,
+            unk2: 0,
+            target_id,
+        }
+    }
+}
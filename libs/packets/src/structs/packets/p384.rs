@@ -0,0 +1,15 @@
+use crate::structs::header::SHeader;
+
+#[repr(C)]
+pub struct P384 {
+    pub header: SHeader,
+}
+
+impl P384 {
+    pub fn new(client_id: u16) -> P384 {
+        let mut header = SHeader::new_packet::<P384>(0x384);
+        header.client_id = client_id;
+
+        P384 { header }
+    }
+}
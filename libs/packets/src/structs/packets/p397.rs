@@ -0,0 +1,45 @@
+use crate::{
+    strings::{bytes_to_str, str_to_bytes},
+    structs::{header::SHeader, item::SItem},
+};
+
+pub const VENDOR_LEN: usize = 12;
+
+#[repr(C)]
+pub struct P397 {
+    pub header: SHeader,
+    title: [u8; 24],
+    pub items: [SItem; VENDOR_LEN],
+    pub slots: [i8; VENDOR_LEN],
+    pub prices: [i32; VENDOR_LEN],
+    pub tax: i16,
+    pub target_id: u16,
+}
+
+impl P397 {
+    pub fn new(client_id: u16, target_id: u16, title: &str) -> P397 {
+        let mut header = SHeader::new_packet::<P397>(0x397);
+        header.client_id = client_id;
+
+        let mut p = P397 {
+            header,
+            title: [0; 24],
+            items: [SItem::default(); VENDOR_LEN],
+            slots: [-1; VENDOR_LEN],
+            prices: [0; VENDOR_LEN],
+            tax: 0,
+            target_id,
+        };
+
+        p.set_title(title);
+
+        p
+    }
+
+    pub fn get_title(&self) -> String {
+        bytes_to_str(&self.title)
+    }
+    pub fn set_title(&mut self, title: &str) {
+        str_to_bytes(&mut self.title, title)
+    }
+}
@@ -0,0 +1,10 @@
+use crate::structs::{header::SHeader, item::SItem};
+
+#[repr(C)]
+pub struct P398 {
+    pub header: SHeader,
+    pub item: SItem,
+    pub price: i32,
+    pub vendor_slot: i16,
+    pub target_id: u16,
+}
@@ -0,0 +1,43 @@
+use crate::structs::{header::SHeader, position::SPosition};
+
+pub const ATTACK_FLAG_CRITICAL: u8 = 0x01;
+pub const ATTACK_FLAG_MISS: u8 = 0x02;
+
+#[repr(C)]
+pub struct P39D {
+    pub header: SHeader,
+    pub attacker_position: SPosition,
+    pub target_position: SPosition,
+    pub attacker_id: u16,
+    pub target_id: u16,
+    pub skill_index: i16,
+    pub motion: i8,
+    pub flags: u8,
+    pub damage: i32,
+    pub target_hp: i32,
+}
+
+impl P39D {
+    pub fn new(
+        attacker_id: u16,
+        attacker_position: SPosition,
+        target_id: u16,
+        target_position: SPosition,
+    ) -> P39D {
+        let mut header = SHeader::new_packet::<P39D>(0x39D);
+        header.client_id = attacker_id;
+
+        P39D {
+            header,
+            attacker_position,
+            target_position,
+            attacker_id,
+            target_id,
+            skill_index: -1,
+            motion: 0,
+            flags: 0,
+            damage: 0,
+            target_hp: 0,
+        }
+    }
+}
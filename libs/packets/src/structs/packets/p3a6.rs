@@ -0,0 +1,10 @@
+use crate::structs::{header::SHeader, item::SItem};
+
+pub const COMPOSE_LEN: usize = 8;
+
+#[repr(C)]
+pub struct P3A6 {
+    pub header: SHeader,
+    pub items: [SItem; COMPOSE_LEN],
+    pub slots: [i8; COMPOSE_LEN],
+}
@@ -0,0 +1,15 @@
+use crate::{strings::bytes_to_str, structs::header::SHeader};
+
+#[repr(C)]
+pub struct P3AB {
+    pub header: SHeader,
+    pub leader_id: u16,
+    name: [u8; 16],
+    unk: u16,
+}
+
+impl P3AB {
+    pub fn get_name(&self) -> String {
+        bytes_to_str(&self.name)
+    }
+}
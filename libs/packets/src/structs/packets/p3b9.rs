@@ -0,0 +1,18 @@
+use crate::structs::{affect::SAffect, header::SHeader};
+
+pub const AFFECTS_LEN: usize = 32;
+
+#[repr(C)]
+pub struct P3B9 {
+    pub header: SHeader,
+    pub affects: [SAffect; AFFECTS_LEN],
+}
+
+impl P3B9 {
+    pub fn new(client_id: u16, affects: [SAffect; AFFECTS_LEN]) -> P3B9 {
+        let mut header = SHeader::new_packet::<P3B9>(0x3B9);
+        header.client_id = client_id;
+
+        P3B9 { header, affects }
+    }
+}
@@ -0,0 +1,9 @@
+use crate::structs::header::SHeader;
+
+#[repr(C)]
+pub struct P3C0 {
+    pub header: SHeader,
+    pub slot: i16,
+    pub unk: u16,
+    pub price: i32,
+}
@@ -0,0 +1,10 @@
+use crate::structs::header::SHeader;
+
+#[repr(C)]
+pub struct P3C1 {
+    pub header: SHeader,
+    pub item_index: i16,
+    pub page: u16,
+    pub min_price: i32,
+    pub max_price: i32,
+}
@@ -0,0 +1,25 @@
+use crate::structs::{auction::SAuction, header::SHeader};
+
+pub const AUCTION_PAGE_LEN: usize = 10;
+
+#[repr(C)]
+pub struct P3C2 {
+    pub header: SHeader,
+    pub page: u16,
+    pub pages: u16,
+    pub auctions: [SAuction; AUCTION_PAGE_LEN],
+}
+
+impl P3C2 {
+    pub fn new(client_id: u16, page: u16, pages: u16) -> P3C2 {
+        let mut header = SHeader::new_packet::<P3C2>(0x3C2);
+        header.client_id = client_id;
+
+        P3C2 {
+            header,
+            page,
+            pages,
+            auctions: [SAuction::default(); AUCTION_PAGE_LEN],
+        }
+    }
+}
@@ -0,0 +1,7 @@
+use crate::structs::header::SHeader;
+
+#[repr(C)]
+pub struct P3C4 {
+    pub header: SHeader,
+    pub auction_id: u32,
+}
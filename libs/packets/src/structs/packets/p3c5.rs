@@ -0,0 +1,25 @@
+use crate::structs::{auction::SMail, header::SHeader};
+
+pub const MAILBOX_LEN: usize = 10;
+
+#[repr(C)]
+pub struct P3C5 {
+    pub header: SHeader,
+    pub count: u16,
+    pub unk: u16,
+    pub mails: [SMail; MAILBOX_LEN],
+}
+
+impl P3C5 {
+    pub fn new(client_id: u16, count: u16) -> P3C5 {
+        let mut header = SHeader::new_packet::<P3C5>(0x3C5);
+        header.client_id = client_id;
+
+        P3C5 {
+            header,
+            count,
+            unk: 0,
+            mails: [SMail::default(); MAILBOX_LEN],
+        }
+    }
+}
@@ -0,0 +1,7 @@
+use crate::structs::header::SHeader;
+
+#[repr(C)]
+pub struct P3C6 {
+    pub header: SHeader,
+    pub mail_id: u32,
+}
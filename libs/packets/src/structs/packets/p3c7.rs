@@ -0,0 +1,8 @@
+use crate::structs::header::SHeader;
+
+#[repr(C)]
+pub struct P3C7 {
+    pub header: SHeader,
+    pub target_id: u16,
+    pub unk: u16,
+}
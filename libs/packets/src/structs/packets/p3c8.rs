@@ -0,0 +1,19 @@
+use crate::{
+    strings::{bytes_to_str, str_to_bytes},
+    structs::header::SHeader,
+};
+
+#[repr(C)]
+pub struct P3C8 {
+    pub header: SHeader,
+    name: [u8; 16],
+}
+
+impl P3C8 {
+    pub fn get_name(&self) -> String {
+        bytes_to_str(&self.name)
+    }
+    pub fn set_name(&mut self, name: &str) {
+        str_to_bytes(&mut self.name, name)
+    }
+}
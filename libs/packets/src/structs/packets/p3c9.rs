@@ -0,0 +1,53 @@
+use crate::{
+    strings::{bytes_to_str, str_to_bytes},
+    structs::{guild::SGuildMember, header::SHeader},
+};
+
+pub const GUILD_MEMBERS_LEN: usize = 50;
+
+#[repr(C)]
+pub struct P3C9 {
+    pub header: SHeader,
+    pub guild_id: u16,
+    pub rank: u8,
+    pub count: u8,
+    name: [u8; 16],
+    notice: [u8; 80],
+    pub members: [SGuildMember; GUILD_MEMBERS_LEN],
+}
+
+impl P3C9 {
+    pub fn new(client_id: u16, guild_id: u16, name: &str, notice: &str) -> P3C9 {
+        let mut header = SHeader::new_packet::<P3C9>(0x3C9);
+        header.client_id = client_id;
+
+        let mut packet = P3C9 {
+            header,
+            guild_id,
+            rank: 0,
+            count: 0,
+            name: [0; 16],
+            notice: [0; 80],
+            members: [SGuildMember::default(); GUILD_MEMBERS_LEN],
+        };
+
+        packet.set_name(name);
+        packet.set_notice(notice);
+
+        packet
+    }
+
+    pub fn get_name(&self) -> String {
+        bytes_to_str(&self.name)
+    }
+    pub fn set_name(&mut self, name: &str) {
+        str_to_bytes(&mut self.name, name)
+    }
+
+    pub fn get_notice(&self) -> String {
+        bytes_to_str(&self.notice)
+    }
+    pub fn set_notice(&mut self, notice: &str) {
+        str_to_bytes(&mut self.notice, notice)
+    }
+}
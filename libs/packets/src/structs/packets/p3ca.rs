@@ -0,0 +1,37 @@
+use crate::{
+    strings::{bytes_to_str, str_to_bytes},
+    structs::header::SHeader,
+};
+
+#[repr(C)]
+pub struct P3CA {
+    pub header: SHeader,
+    pub guild_id: u16,
+    pub target_id: u16,
+    name: [u8; 16],
+}
+
+impl P3CA {
+    pub fn new(client_id: u16, guild_id: u16, target_id: u16, name: &str) -> P3CA {
+        let mut header = SHeader::new_packet::<P3CA>(0x3CA);
+        header.client_id = client_id;
+
+        let mut packet = P3CA {
+            header,
+            guild_id,
+            target_id,
+            name: [0; 16],
+        };
+
+        packet.set_name(name);
+
+        packet
+    }
+
+    pub fn get_name(&self) -> String {
+        bytes_to_str(&self.name)
+    }
+    pub fn set_name(&mut self, name: &str) {
+        str_to_bytes(&mut self.name, name)
+    }
+}
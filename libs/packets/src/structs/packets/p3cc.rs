@@ -0,0 +1,26 @@
+use crate::{
+    strings::{bytes_to_str, str_to_bytes},
+    structs::header::SHeader,
+};
+
+pub const GUILD_ACTION_LEAVE: u8 = 0;
+pub const GUILD_ACTION_KICK: u8 = 1;
+pub const GUILD_ACTION_RANK: u8 = 2;
+
+#[repr(C)]
+pub struct P3CC {
+    pub header: SHeader,
+    pub action: u8,
+    pub rank: u8,
+    pub unk: u16,
+    name: [u8; 16],
+}
+
+impl P3CC {
+    pub fn get_name(&self) -> String {
+        bytes_to_str(&self.name)
+    }
+    pub fn set_name(&mut self, name: &str) {
+        str_to_bytes(&mut self.name, name)
+    }
+}
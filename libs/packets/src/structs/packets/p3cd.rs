@@ -0,0 +1,19 @@
+use crate::{
+    strings::{bytes_to_str, str_to_bytes},
+    structs::header::SHeader,
+};
+
+#[repr(C)]
+pub struct P3CD {
+    pub header: SHeader,
+    notice: [u8; 80],
+}
+
+impl P3CD {
+    pub fn get_notice(&self) -> String {
+        bytes_to_str(&self.notice)
+    }
+    pub fn set_notice(&mut self, notice: &str) {
+        str_to_bytes(&mut self.notice, notice)
+    }
+}
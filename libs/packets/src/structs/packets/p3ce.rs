@@ -0,0 +1,21 @@
+use crate::structs::{header::SHeader, item::SItem};
+
+pub const GUILD_STORAGE_LEN: usize = 40;
+
+#[repr(C)]
+pub struct P3CE {
+    pub header: SHeader,
+    pub items: [SItem; GUILD_STORAGE_LEN],
+}
+
+impl P3CE {
+    pub fn new(client_id: u16) -> P3CE {
+        let mut header = SHeader::new_packet::<P3CE>(0x3CE);
+        header.client_id = client_id;
+
+        P3CE {
+            header,
+            items: [SItem::default(); GUILD_STORAGE_LEN],
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use crate::structs::header::SHeader;
+
+pub const GUILD_STORAGE_OPEN: u8 = 0;
+pub const GUILD_STORAGE_DEPOSIT: u8 = 1;
+pub const GUILD_STORAGE_WITHDRAW: u8 = 2;
+
+#[repr(C)]
+pub struct P3CF {
+    pub header: SHeader,
+    pub action: u8,
+    pub unk: u8,
+    pub slot: i16,
+}
@@ -0,0 +1,25 @@
+use crate::structs::header::SHeader;
+
+pub const GUILD_MARK_LEN: usize = 384;
+
+#[repr(C)]
+pub struct P3D0 {
+    pub header: SHeader,
+    pub guild_id: u16,
+    pub unk: u16,
+    pub mark: [u8; GUILD_MARK_LEN],
+}
+
+impl P3D0 {
+    pub fn new(client_id: u16, guild_id: u16) -> P3D0 {
+        let mut header = SHeader::new_packet::<P3D0>(0x3D0);
+        header.client_id = client_id;
+
+        P3D0 {
+            header,
+            guild_id,
+            unk: 0,
+            mark: [0; GUILD_MARK_LEN],
+        }
+    }
+}
@@ -0,0 +1,8 @@
+use crate::structs::header::SHeader;
+
+#[repr(C)]
+pub struct P3D1 {
+    pub header: SHeader,
+    pub guild_id: u16,
+    pub unk: u16,
+}
@@ -0,0 +1,9 @@
+use crate::structs::header::SHeader;
+
+#[repr(C)]
+pub struct P3D2 {
+    pub header: SHeader,
+    pub guild_id: u16,
+    pub declare: u8,
+    pub unk: u8,
+}
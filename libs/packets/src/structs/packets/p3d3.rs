@@ -0,0 +1,8 @@
+use crate::structs::header::SHeader;
+
+#[repr(C)]
+pub struct P3D3 {
+    pub header: SHeader,
+    pub event: u16,
+    pub unk: u16,
+}
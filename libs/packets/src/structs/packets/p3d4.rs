@@ -0,0 +1,8 @@
+use crate::structs::header::SHeader;
+
+#[repr(C)]
+pub struct P3D4 {
+    pub header: SHeader,
+    pub rule: u16,
+    pub unk: u16,
+}
@@ -0,0 +1,12 @@
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[repr(C)]
+pub struct SPosition {
+    pub x: u16,
+    pub y: u16,
+}
+
+impl SPosition {
+    pub fn new(x: u16, y: u16) -> SPosition {
+        SPosition { x, y }
+    }
+}
@@ -0,0 +1,20 @@
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[repr(C)]
+pub struct SScore {
+    pub level: i32,
+    pub defense: i32,
+    pub damage: i32,
+    pub merchant: u8,
+    pub speed: u8,
+    pub direction: u8,
+    pub chaos_rate: u8,
+    pub max_hp: i32,
+    pub max_mp: i32,
+    pub hp: i32,
+    pub mp: i32,
+    pub str: i16,
+    pub int: i16,
+    pub dex: i16,
+    pub con: i16,
+    pub special: [u16; 4],
+}
@@ -0,0 +1,26 @@
+// one entry of the client SkillData.bin, the file is just these back to back
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[repr(C)]
+pub struct SSpell {
+    pub skill_point: i32,
+    pub target_type: i32,
+    pub mana_spent: i32,
+    pub delay: i32,
+    pub range: i32,
+    pub instance_type: i32,
+    pub instance_value: i32,
+    pub tick_type: i32,
+    pub tick_value: i32,
+    pub affect_type: i32,
+    pub affect_value: i32,
+    pub affect_time: i32,
+    pub act: [u8; 8],
+    pub instance_attribute: i32,
+    pub tick_attribute: i32,
+    pub aggressive: i32,
+    pub max_target: i32,
+    pub party: i32,
+    pub affect_resist: i32,
+    pub passive: i32,
+}
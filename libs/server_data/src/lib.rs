@@ -0,0 +1,19 @@
+use std::{env, path::PathBuf};
+
+pub const DATA_FOLDER: &str = "data";
+pub const DATA_FOLDER_VAR: &str = "W2_DATA_FOLDER";
+
+// the server watches its data folder for this file and reloads what is named in it, an
+// empty one reloads everything
+
+pub const RELOAD_REQUEST_FILE: &str = "reload.request";
+
+// the folder next to the binary unless the environment points somewhere else, so the server
+// and the manager can run from different places and still agree on it
+
+pub fn data_folder() -> PathBuf {
+    match env::var_os(DATA_FOLDER_VAR) {
+        Some(folder) if !folder.is_empty() => PathBuf::from(folder),
+        _ => PathBuf::from(DATA_FOLDER),
+    }
+}